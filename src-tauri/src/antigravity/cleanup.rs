@@ -5,11 +5,14 @@ use rusqlite::{params, Connection};
 use std::path::Path;
 
 // 导入 platform_utils 模块
+use crate::error::AgentError;
 use crate::platform;
 
-fn clear_database(db_path: &Path, db_name: &str) -> Result<usize, String> {
+#[tracing::instrument(name = "sqlite", skip(db_path), fields(op = "clear"))]
+fn clear_database(db_path: &Path, db_name: &str) -> Result<usize, AgentError> {
     tracing::info!(target: "cleanup::database", db_name = %db_name, "开始清理数据库");
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path)
+        .map_err(|e| AgentError::from_sqlite(e, format!("Failed to open {db_name}")))?;
 
     // 删除认证状态
     let antigravity_auth_status_rows = conn
@@ -46,10 +49,11 @@ fn clear_database(db_path: &Path, db_name: &str) -> Result<usize, String> {
     Ok(onboarding_rows + antigravity_auth_status_rows + oauth_rows + user_status_rows)
 }
 
-pub async fn clear_all_antigravity_data() -> Result<String, String> {
+pub async fn clear_all_antigravity_data() -> Result<String, AgentError> {
     tracing::info!(target: "cleanup::main", "开始清除 Antigravity 用户认证数据");
 
-    let app_data = platform::get_antigravity_db_path().unwrap();
+    let app_data = platform::get_antigravity_db_path()
+        .ok_or_else(|| AgentError::NotFound("Antigravity database path not found".to_string()))?;
 
     let mut msg = String::new();
 
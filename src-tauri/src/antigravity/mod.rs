@@ -1,4 +1,6 @@
 pub mod cleanup;
 pub mod path_config;
+pub mod readiness;
 pub mod restore;
+pub mod snapshot;
 pub mod starter;
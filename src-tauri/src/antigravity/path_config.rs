@@ -2,6 +2,7 @@
 //! 负责保存和读取用户自定义的 Antigravity 可执行文件路径
 
 use crate::directories;
+use crate::error::AgentError;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -19,7 +20,7 @@ fn get_config_file_path() -> PathBuf {
 }
 
 /// 保存用户自定义可执行文件路径
-pub fn save_custom_executable_path(path: String) -> Result<(), String> {
+pub fn save_custom_executable_path(path: String) -> Result<(), AgentError> {
     let config_file = get_config_file_path();
     let mut config = read_config().unwrap_or_default();
 
@@ -34,43 +35,45 @@ pub fn save_custom_executable_path(path: String) -> Result<(), String> {
 fn write_config(
     config_file: &std::path::Path,
     config: &AntigravityPathConfig,
-) -> Result<(), String> {
-    let json =
-        serde_json::to_string_pretty(config).map_err(|e| format!("序列化配置失败: {}", e))?;
-    fs::write(config_file, json).map_err(|e| format!("写入配置文件失败: {}", e))?;
+) -> Result<(), AgentError> {
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize path config: {e}")))?;
+    fs::write(config_file, json)
+        .map_err(|e| AgentError::from_io(e, "Failed to write path config file"))?;
     Ok(())
 }
 
 /// 读取配置文件
-fn read_config() -> Result<AntigravityPathConfig, String> {
+fn read_config() -> Result<AntigravityPathConfig, AgentError> {
     let config_file = get_config_file_path();
 
     if !config_file.exists() {
         return Ok(AntigravityPathConfig::default());
     }
 
-    let content =
-        fs::read_to_string(&config_file).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let content = fs::read_to_string(&config_file)
+        .map_err(|e| AgentError::from_io(e, "Failed to read path config file"))?;
 
-    let config: AntigravityPathConfig =
-        serde_json::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))?;
+    let config: AntigravityPathConfig = serde_json::from_str(&content)
+        .map_err(|e| AgentError::Internal(format!("Failed to parse path config file: {e}")))?;
 
     Ok(config)
 }
 
 /// 从配置文件读取自定义可执行文件路径
-pub fn get_custom_executable_path() -> Result<Option<String>, String> {
+pub fn get_custom_executable_path() -> Result<Option<String>, AgentError> {
     let config = read_config()?;
     Ok(config.custom_executable_path)
 }
 
 /// 清除自定义路径配置
 #[allow(dead_code)]
-pub fn clear_custom_path() -> Result<(), String> {
+pub fn clear_custom_path() -> Result<(), AgentError> {
     let config_file = get_config_file_path();
 
     if config_file.exists() {
-        fs::remove_file(&config_file).map_err(|e| format!("删除配置文件失败: {}", e))?;
+        fs::remove_file(&config_file)
+            .map_err(|e| AgentError::from_io(e, "Failed to delete path config file"))?;
         tracing::info!("✅ 已清除自定义 Antigravity 路径");
     }
 
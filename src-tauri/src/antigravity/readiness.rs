@@ -0,0 +1,80 @@
+//! 等待 Antigravity 退出
+//!
+//! 关闭进程后轮询，直到没有匹配 Antigravity 的进程、且 `state.vscdb` 可以取得写锁，
+//! 代替固定时长的等待：慢机器上不会提前改写数据库，快机器上也不必空等。
+//!
+//! 总超时由 `AppSettings.switch_ready_timeout_secs` 配置，超时时错误信息说明卡在哪个条件上。
+
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::AgentError;
+use crate::platform;
+
+/// 轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 读取设置中的总超时
+pub fn configured_timeout() -> Duration {
+    let mut settings =
+        crate::app_settings::load_settings_from_disk(&crate::directories::get_app_settings_file());
+    settings.validate();
+    Duration::from_secs(settings.switch_ready_timeout_secs)
+}
+
+/// 尝试立即取得写锁（`BEGIN IMMEDIATE`），不等待 SQLite 的忙等超时
+fn try_write_lock(db_path: &Path) -> Result<(), rusqlite::Error> {
+    let conn = Connection::open(db_path)?;
+    conn.busy_timeout(Duration::ZERO)?;
+    conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;")
+}
+
+/// 等待 Antigravity 进程全部退出、数据库可写
+///
+/// # 返回
+/// - `Ok(())`: 两个条件均已满足
+/// - `Err(AgentError::Timeout)`: 超时，信息中包含未满足的条件
+pub async fn wait_until_stopped(timeout: Duration) -> Result<(), AgentError> {
+    let started = Instant::now();
+    let deadline = started + timeout;
+
+    loop {
+        let remaining = platform::list_antigravity_processes();
+        if remaining.is_empty() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            return Err(AgentError::Timeout(format!(
+                "Antigravity processes did not exit within {}s (still running: {})",
+                timeout.as_secs(),
+                remaining.join(", ")
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    // 数据库尚未创建（首次使用）时无需等待
+    if let Some(db_path) = platform::get_antigravity_db_path().filter(|path| path.exists()) {
+        loop {
+            let error = match try_write_lock(&db_path) {
+                Ok(()) => break,
+                Err(error) => error,
+            };
+            if Instant::now() >= deadline {
+                return Err(AgentError::Timeout(format!(
+                    "Could not get a write lock on state.vscdb within {}s: {error}",
+                    timeout.as_secs()
+                )));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    tracing::debug!(
+        target: "antigravity::readiness",
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Antigravity 已退出，数据库可写"
+    );
+    Ok(())
+}
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 
 // 导入相关模块
 use crate::constants::database;
+use crate::error::AgentError;
 use crate::platform;
 
 /// 恢复 Antigravity 状态（精简版）
@@ -19,26 +20,40 @@ use crate::platform;
 ///
 /// # 返回
 /// - `Ok(message)`: 成功消息
-/// - `Err(message)`: 错误信息
+/// - `Err(AgentError::NotFound)`: 账户文件或数据库路径不存在
+/// - `Err(AgentError)`: 其他错误
 pub async fn save_antigravity_account_to_file(
     account_file_path: PathBuf,
-) -> Result<String, String> {
-    let content = fs::read_to_string(&account_file_path).map_err(|e| e.to_string())?;
-    let account_data: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+) -> Result<String, AgentError> {
+    let content = fs::read_to_string(&account_file_path).map_err(|e| {
+        AgentError::from_io(
+            e,
+            format!(
+                "Failed to read account file ({})",
+                account_file_path.display()
+            ),
+        )
+    })?;
+    let account_data: Value = serde_json::from_str(&content)
+        .map_err(|e| AgentError::Internal(format!("Failed to parse account file: {e}")))?;
 
-    let app_data = platform::get_antigravity_db_path().unwrap();
+    let app_data = platform::get_antigravity_db_path()
+        .ok_or_else(|| AgentError::NotFound("Antigravity database path not found".to_string()))?;
 
     // 确保数据库目录存在
     if let Some(parent) = app_data.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("创建数据库目录失败: {}", e))?;
+        fs::create_dir_all(parent)
+            .map_err(|e| AgentError::from_io(e, "Failed to create database directory"))?;
     }
 
     let mut msg = String::new();
 
     // 内联恢复逻辑：写回 AUTH_STATUS / OAUTH_TOKEN / USER_STATUS
-    let restore_db = |db_path: &PathBuf, db_name: &str| -> Result<usize, String> {
+    let restore_db = |db_path: &PathBuf, db_name: &str| -> Result<usize, AgentError> {
+        let _span = tracing::info_span!("sqlite", op = "restore", db_name = %db_name).entered();
         tracing::info!(target: "restore::database", db_name = %db_name, "开始恢复数据库");
-        let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+        let conn = Connection::open(db_path)
+            .map_err(|e| AgentError::from_sqlite(e, format!("Failed to open {db_name}")))?;
 
         let mut restored_count = 0;
 
@@ -84,6 +99,12 @@ pub async fn save_antigravity_account_to_file(
                     }
                 }
             }
+        } else if let Err(e) = conn.execute(
+            "DELETE FROM ItemTable WHERE key = ?",
+            [database::OAUTH_TOKEN],
+        ) {
+            // 备份中没有该键时删除旧值，避免热切换（不先清理）时残留上一个账户的数据
+            tracing::warn!(target: "restore::database", key = %database::OAUTH_TOKEN, error = %e, "删除旧值失败（忽略）");
         }
 
         // [NEW] 恢复 User Status
@@ -102,6 +123,11 @@ pub async fn save_antigravity_account_to_file(
                     }
                 }
             }
+        } else if let Err(e) = conn.execute(
+            "DELETE FROM ItemTable WHERE key = ?",
+            [database::USER_STATUS],
+        ) {
+            tracing::warn!(target: "restore::database", key = %database::USER_STATUS, error = %e, "删除旧值失败（忽略）");
         }
 
         Ok(restored_count)
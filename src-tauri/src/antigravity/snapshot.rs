@@ -0,0 +1,125 @@
+//! 登录数据快照
+//!
+//! 切换账户前记录 `state.vscdb` 与 `state.vscdb.backup` 中的三个认证键，
+//! 后续任一步骤失败时写回，避免用户停留在“已登出、新账户又没写入”的状态。
+//!
+//! 清理步骤会删除整个 `state.vscdb.backup`，回滚时先用已恢复的主库复制出备份库，再写回其中的键。
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+use crate::constants::database;
+use crate::error::AgentError;
+use crate::platform;
+
+/// 快照覆盖的键
+const AUTH_KEYS: [&str; 3] = [
+    database::AUTH_STATUS,
+    database::OAUTH_TOKEN,
+    database::USER_STATUS,
+];
+
+/// 单个数据库中认证键的值，`None` 表示该键原本不存在
+#[derive(Debug)]
+struct DbKeys {
+    path: PathBuf,
+    values: Vec<(&'static str, Option<String>)>,
+}
+
+/// 主库与备份库的登录数据快照
+#[derive(Debug)]
+pub struct AuthSnapshot {
+    main: DbKeys,
+    /// 备份库原本不存在时为 `None`
+    backup: Option<DbKeys>,
+}
+
+fn read_keys(path: &Path, db_name: &str) -> Result<DbKeys, AgentError> {
+    let conn = Connection::open(path)
+        .map_err(|e| AgentError::from_sqlite(e, format!("Failed to open {db_name}")))?;
+
+    let mut values = Vec::with_capacity(AUTH_KEYS.len());
+    for key in AUTH_KEYS {
+        let value = conn
+            .query_row("SELECT value FROM ItemTable WHERE key = ?", [key], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()
+            .map_err(|e| {
+                AgentError::from_sqlite(e, format!("Failed to read {key} from {db_name}"))
+            })?;
+        values.push((key, value));
+    }
+
+    Ok(DbKeys {
+        path: path.to_path_buf(),
+        values,
+    })
+}
+
+/// 在一个事务中写回所有键，原本不存在的键被删除
+fn write_keys(keys: &DbKeys, db_name: &str) -> Result<(), AgentError> {
+    let mut conn = Connection::open(&keys.path)
+        .map_err(|e| AgentError::from_sqlite(e, format!("Failed to open {db_name}")))?;
+    let tx = conn.transaction().map_err(|e| {
+        AgentError::from_sqlite(e, format!("Failed to begin rollback of {db_name}"))
+    })?;
+
+    for (key, value) in &keys.values {
+        let written = match value {
+            Some(value) => tx.execute(
+                "INSERT OR REPLACE INTO ItemTable (key, value) VALUES (?, ?)",
+                params![key, value],
+            ),
+            None => tx.execute("DELETE FROM ItemTable WHERE key = ?", [key]),
+        };
+        written.map_err(|e| {
+            AgentError::from_sqlite(e, format!("Failed to roll back {key} in {db_name}"))
+        })?;
+    }
+
+    tx.commit()
+        .map_err(|e| AgentError::from_sqlite(e, format!("Failed to commit rollback of {db_name}")))
+}
+
+impl AuthSnapshot {
+    /// 记录当前登录数据（须在修改数据库之前调用）
+    pub fn capture() -> Result<Self, AgentError> {
+        let main_path = platform::get_antigravity_db_path().ok_or_else(|| {
+            AgentError::NotFound("Antigravity database path not found".to_string())
+        })?;
+        let backup_path = main_path.with_extension("vscdb.backup");
+
+        let main = read_keys(&main_path, "state.vscdb")?;
+        let backup = if backup_path.exists() {
+            Some(read_keys(&backup_path, "state.vscdb.backup")?)
+        } else {
+            None
+        };
+
+        tracing::info!(
+            target: "snapshot",
+            signed_in = main.values[0].1.is_some(),
+            has_backup_db = backup.is_some(),
+            "已记录登录数据快照"
+        );
+        Ok(Self { main, backup })
+    }
+
+    /// 将登录数据恢复到快照时的状态
+    pub fn restore(&self) -> Result<(), AgentError> {
+        tracing::warn!(target: "snapshot", "回滚登录数据");
+        write_keys(&self.main, "state.vscdb")?;
+
+        if let Some(backup) = &self.backup {
+            if !backup.path.exists() {
+                std::fs::copy(&self.main.path, &backup.path)
+                    .map_err(|e| AgentError::from_io(e, "Failed to recreate state.vscdb.backup"))?;
+            }
+            write_keys(backup, "state.vscdb.backup")?;
+        }
+
+        tracing::info!(target: "snapshot", "登录数据已回滚");
+        Ok(())
+    }
+}
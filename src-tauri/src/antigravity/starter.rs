@@ -2,6 +2,7 @@
 ///
 /// 提供跨平台的 Antigravity 应用程序启动功能
 /// 支持 Windows、macOS 和 Linux 系统
+use crate::error::AgentError;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
@@ -10,7 +11,8 @@ use std::process::{Command, Stdio};
 /// # 返回值
 ///
 /// * `Ok(String)` - 启动成功，返回成功消息
-/// * `Err(String)` - 启动失败，返回错误信息
+/// * `Err(AgentError::NotFound)` - 未找到 Antigravity 可执行文件
+/// * `Err(AgentError)` - 启动失败
 ///
 /// # 示例
 ///
@@ -20,14 +22,17 @@ use std::process::{Command, Stdio};
 ///     Err(e) => println!("启动失败: {}", e),
 /// }
 /// ```
-pub fn start_antigravity() -> Result<String, String> {
+pub fn start_antigravity() -> Result<String, AgentError> {
     // 优先使用用户配置的可执行文件路径
     if let Ok(Some(custom_exec)) = crate::antigravity::path_config::get_custom_executable_path() {
         let path = PathBuf::from(&custom_exec);
         if path.exists() && path.is_file() {
             tracing::info!("📁 使用自定义 Antigravity 可执行文件: {}", custom_exec);
-            return try_start_from_path(&path)
-                .map_err(|e| format!("无法启动自定义 Antigravity: {}. 请检查路径是否正确", e));
+            return try_start_from_path(&path).map_err(|e| {
+                AgentError::Internal(format!(
+                    "Failed to start custom Antigravity executable: {e}. Please check the configured path"
+                ))
+            });
         } else {
             tracing::warn!("⚠️ 自定义可执行文件路径无效: {}", custom_exec);
         }
@@ -35,10 +40,12 @@ pub fn start_antigravity() -> Result<String, String> {
 
     // 回退到自动检测
     match std::env::consts::OS {
-        "windows" => start_antigravity_windows(),
-        "macos" => start_antigravity_macos(),
+        "windows" => start_antigravity_windows().map_err(AgentError::Internal),
+        "macos" => start_antigravity_macos().map_err(AgentError::Internal),
         "linux" => start_antigravity_linux(),
-        _ => Err("不支持的操作系统".to_string()),
+        other => Err(AgentError::Internal(format!(
+            "Unsupported operating system: {other}"
+        ))),
     }
 }
 
@@ -122,11 +129,13 @@ fn start_antigravity_macos() -> Result<String, String> {
 }
 
 /// 在 Linux 平台启动 Antigravity
-fn start_antigravity_linux() -> Result<String, String> {
+fn start_antigravity_linux() -> Result<String, AgentError> {
     let antigravity_path = std::path::PathBuf::from("/usr/share/antigravity/antigravity");
 
     if !antigravity_path.exists() {
-        return Err("Antigravity 未安装。请先安装 Antigravity 应用。".to_string());
+        return Err(AgentError::NotFound(
+            "Antigravity is not installed. Please install Antigravity first".to_string(),
+        ));
     }
 
     let mut cmd = std::process::Command::new(&antigravity_path);
@@ -148,7 +157,9 @@ fn start_antigravity_linux() -> Result<String, String> {
 
     match cmd.spawn() {
         Ok(_) => Ok("Antigravity 已启动".to_string()),
-        Err(e) => Err(format!("启动 Antigravity 失败: {}", e)),
+        Err(e) => Err(AgentError::Internal(format!(
+            "Failed to start Antigravity: {e}"
+        ))),
     }
 }
 
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::AppHandle;
 
 /// 应用程序设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,8 +18,30 @@ pub struct AppSettings {
     /// 语言偏好设置
     #[serde(default = "default_language")]
     pub language: String,
+    /// 本地 API 的 TCP 端口
+    pub api_tcp_port: u16,
+    /// 是否监听 TCP 端口；未设置时自动决定（GUI 模式监听，headless 模式在 Unix socket 可用时不监听）
+    pub api_tcp_enabled: Option<bool>,
+    /// 是否在配置目录下监听 Unix socket（仅 Unix）
+    pub api_unix_socket_enabled: bool,
+    /// 是否开启局域网远程访问（TLS，重启后生效）
+    pub remote_access_enabled: bool,
+    /// 远程访问监听的 TCP 端口（所有网卡）
+    pub remote_access_port: u16,
+    /// 切换账户时等待 Antigravity 退出、数据库解锁的总超时（秒）
+    pub switch_ready_timeout_secs: u64,
+    /// 数据库监控检测到新登录或令牌刷新时自动备份当前账户（备份包含令牌，默认关闭）
+    pub auto_capture_enabled: bool,
+    /// 不自动备份的邮箱（不区分大小写）
+    pub auto_capture_exclude: Vec<String>,
 }
 
+/// 等待 Antigravity 退出的默认超时（秒）
+pub const DEFAULT_SWITCH_READY_TIMEOUT_SECS: u64 = 15;
+
+/// 允许配置的超时范围（秒）
+const SWITCH_READY_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 1..=300;
+
 fn default_language() -> String {
     "en".to_string()
 }
@@ -37,6 +58,14 @@ impl Default for AppSettings {
             debug_mode: false,
             private_mode: default_private_mode(),
             language: default_language(),
+            api_tcp_port: crate::server::listen::DEFAULT_TCP_PORT,
+            api_tcp_enabled: None,
+            api_unix_socket_enabled: true,
+            remote_access_enabled: false,
+            remote_access_port: crate::server::listen::DEFAULT_REMOTE_PORT,
+            switch_ready_timeout_secs: DEFAULT_SWITCH_READY_TIMEOUT_SECS,
+            auto_capture_enabled: false,
+            auto_capture_exclude: Vec::new(),
         }
     }
 }
@@ -68,8 +97,28 @@ impl AppSettings {
             changed = true;
         }
 
+        if !SWITCH_READY_TIMEOUT_RANGE.contains(&self.switch_ready_timeout_secs) {
+            tracing::warn!(
+                target: "app_settings::validate",
+                value = self.switch_ready_timeout_secs,
+                "switch_ready_timeout_secs 超出范围，已修正"
+            );
+            self.switch_ready_timeout_secs = self.switch_ready_timeout_secs.clamp(
+                *SWITCH_READY_TIMEOUT_RANGE.start(),
+                *SWITCH_READY_TIMEOUT_RANGE.end(),
+            );
+            changed = true;
+        }
+
         changed
     }
+
+    /// 邮箱是否在自动备份的排除列表中
+    pub fn is_auto_capture_excluded(&self, email: &str) -> bool {
+        self.auto_capture_exclude
+            .iter()
+            .any(|excluded| excluded.trim().eq_ignore_ascii_case(email.trim()))
+    }
 }
 
 /// 应用程序设置管理器
@@ -80,7 +129,7 @@ pub struct AppSettingsManager {
 
 impl AppSettingsManager {
     /// 创建新的设置管理器
-    pub fn new(_app_handle: &AppHandle) -> Self {
+    pub fn new() -> Self {
         // 使用统一的配置目录
         let config_path = crate::directories::get_app_settings_file();
 
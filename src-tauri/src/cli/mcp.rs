@@ -0,0 +1,79 @@
+//! # MCP stdio 传输
+//!
+//! `antigravity-agent mcp` 从 stdin 逐行读取 JSON-RPC 消息，向 stdout 逐行写出响应，
+//! 供以子进程方式启动 MCP 服务的宿主（IDE、桌面客户端）使用。协议处理见 [`crate::server::mcp`]。
+//!
+//! 与其他子命令一样，请求优先转发给正在运行的实例（`POST /api/mcp`，使用管理 Token），
+//! 没有运行中的实例或指定了 `--direct` 时在本进程内直接处理。日志只写 stderr。
+
+use super::transport;
+use crate::server::auth::ApiScope;
+use crate::server::mcp;
+use crate::server::rpc::{RpcMessage, RpcResponse};
+use crate::services::account::Redaction;
+use reqwest::Method;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// 运行 stdio 服务，stdin 关闭时返回退出码
+pub async fn serve(direct: bool) -> i32 {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => return 0,
+            Err(e) => {
+                tracing::error!(target: "cli::mcp", error = %e, "读取 stdin 失败");
+                return 1;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle(message, direct).await,
+            Err(e) => Some(mcp::parse_error(e)),
+        };
+        let Some(response) = response else {
+            continue;
+        };
+
+        let written = async {
+            stdout.write_all(format!("{response}\n").as_bytes()).await?;
+            stdout.flush().await
+        };
+        if let Err(e) = written.await {
+            tracing::error!(target: "cli::mcp", error = %e, "写入 stdout 失败");
+            return 1;
+        }
+    }
+}
+
+/// 处理一条消息；通知不产生响应，在本地处理即可
+async fn handle(message: Value, direct: bool) -> Option<Value> {
+    let is_request = message.get("id").is_some() && message.get("method").is_some();
+    if is_request && !direct {
+        match transport::call_running(Method::POST, "/api/mcp", Some(message.clone())).await {
+            Ok(Some(response)) => return Some(response),
+            Ok(None) => {}
+            // 实例已收到请求但返回了错误（如 Token 无效、超时），不再本地重试，避免重复执行切换
+            Err(error) => {
+                tracing::warn!(target: "cli::mcp", code = error.code(), error = error.message(), "转发到运行中的实例失败");
+                let response = RpcResponse::failure(RpcMessage::id_of(&message), error.into());
+                return serde_json::to_value(response).ok();
+            }
+        }
+    }
+
+    let settings =
+        crate::app_settings::load_settings_from_disk(&crate::directories::get_app_settings_file());
+    mcp::handle_message(
+        message,
+        ApiScope::Admin,
+        Redaction::new(settings.private_mode),
+    )
+    .await
+}
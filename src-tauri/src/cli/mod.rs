@@ -0,0 +1,502 @@
+//! # 命令行接口
+//!
+//! 同一个二进制文件支持以子命令方式运行账户操作，便于在 shell 和 CI 中编写账户轮换脚本：
+//!
+//! ```text
+//! antigravity-agent accounts list
+//! antigravity-agent switch user@example.com
+//! antigravity-agent metrics user@example.com --json
+//! antigravity-agent export --output accounts.json
+//! antigravity-agent mcp
+//! ```
+//!
+//! 命令优先通过本地 API 调用正在运行的实例，没有运行中的实例时直接调用服务层，见 [`transport`]。
+//! 不带子命令启动时仍进入 GUI（或 `--headless`）模式。
+
+pub mod mcp;
+pub mod transport;
+
+use crate::error::AgentError;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use transport::Route;
+
+/// 用法错误的退出码
+const EXIT_USAGE: i32 = 2;
+
+/// 命令执行失败的退出码
+const EXIT_FAILURE: i32 = 1;
+
+const USAGE: &str = "\
+Usage: antigravity-agent <command> [options]
+
+Commands:
+  accounts list              List backed-up accounts and their IDs (* marks the current one)
+  accounts current           Show the account currently signed in to Antigravity
+  switch <email|id>          Switch Antigravity to a backed-up account
+  backup-current             Back up the account currently signed in
+  metrics <email|id>         Show quota metrics for an account
+  refresh-quota <email|id>   Trigger a quota refresh for an account
+  export [--output <file>]   Export all account backups (stdout by default)
+  import <file|->            Import account backups from an export file
+  doctor                     Check the environment and suggest fixes
+  mcp                        Serve the Model Context Protocol over stdio
+
+Options:
+  --json                     Print machine-readable JSON
+  --direct                   Do not use a running instance; call services directly
+  -h, --help                 Show this help
+
+Without a command, the agent starts normally (GUI, or API only with --headless).";
+
+/// 子命令
+#[derive(Debug)]
+pub enum Command {
+    ListAccounts,
+    CurrentAccount,
+    Switch { email: String },
+    BackupCurrent,
+    Metrics { email: String },
+    RefreshQuota { email: String },
+    Export { output: Option<PathBuf> },
+    Import { source: String, entries: Value },
+    Doctor,
+}
+
+/// 一次命令行调用
+#[derive(Debug)]
+struct Invocation {
+    command: Command,
+    json: bool,
+    direct: bool,
+}
+
+/// 解析结果
+enum Parsed {
+    /// 不是 CLI 调用，继续启动应用
+    NotCli,
+    /// 打印帮助
+    Help,
+    Run(Invocation),
+    /// MCP stdio 服务，见 [`mcp`]
+    Mcp {
+        direct: bool,
+    },
+}
+
+/// 附加到启动进程（终端、脚本）的控制台
+///
+/// release 构建使用 `windows_subsystem = "windows"`，进程默认没有控制台，子命令的 stdout / stderr 会丢失。
+/// 从资源管理器启动（没有父控制台）时附加失败，忽略即可。
+#[cfg(windows)]
+fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: 只传入常量参数，失败时返回 0，不影响进程状态
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// 如果命令行包含子命令则执行并返回退出码，否则返回 `None`
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let parsed = parse(&args);
+    if !matches!(parsed, Ok(Parsed::NotCli)) {
+        attach_parent_console();
+    }
+
+    let invocation = match parsed {
+        Ok(Parsed::NotCli) => return None,
+        Ok(Parsed::Help) => {
+            println!("{USAGE}");
+            return Some(0);
+        }
+        Ok(Parsed::Run(invocation)) => invocation,
+        Ok(Parsed::Mcp { direct }) => {
+            init_tracing();
+            return Some(match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime.block_on(mcp::serve(direct)),
+                Err(e) => {
+                    eprintln!("error: failed to start async runtime: {e}");
+                    EXIT_FAILURE
+                }
+            });
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return Some(EXIT_USAGE);
+        }
+    };
+
+    init_tracing();
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: failed to start async runtime: {e}");
+            return Some(EXIT_FAILURE);
+        }
+    };
+
+    let json_output = invocation.json;
+    Some(match runtime.block_on(run(invocation)) {
+        Ok(code) => code,
+        Err(error) => {
+            if json_output {
+                println!(
+                    "{}",
+                    json!({ "ok": false, "code": error.code(), "message": error.message() })
+                );
+            } else {
+                eprintln!("error: {} ({})", error.message(), error.code());
+            }
+            EXIT_FAILURE
+        }
+    })
+}
+
+/// CLI 只向 stderr 输出警告及以上日志（可被 `RUST_LOG` 覆盖），避免污染 `--json` 输出
+fn init_tracing() {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+fn parse(args: &[String]) -> Result<Parsed, String> {
+    let mut json = false;
+    let mut direct = false;
+    let mut help = false;
+    let mut output = None;
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--direct" => direct = true,
+            "-h" | "--help" => help = true,
+            "-o" | "--output" => {
+                output = Some(PathBuf::from(
+                    iter.next().ok_or("--output requires a file path")?,
+                ));
+            }
+            "-" => positional.push(arg.as_str()),
+            flag if flag.starts_with('-') => positional.push(flag),
+            value => positional.push(value),
+        }
+    }
+
+    let Some((&name, rest)) = positional.split_first() else {
+        return Ok(Parsed::NotCli);
+    };
+
+    // 未知的第一个参数交给应用本身处理（如 --headless）
+    let command = match (name, rest) {
+        ("help", _) => return Ok(Parsed::Help),
+        ("accounts", ["list"]) => Command::ListAccounts,
+        ("accounts", ["current"]) => Command::CurrentAccount,
+        ("accounts", _) => return Err("usage: accounts <list|current>".to_string()),
+        ("switch", [email]) => Command::Switch {
+            email: email.to_string(),
+        },
+        ("backup-current", []) => Command::BackupCurrent,
+        ("doctor", []) => Command::Doctor,
+        ("mcp", []) if help => return Ok(Parsed::Help),
+        ("mcp", []) => {
+            if output.is_some() {
+                return Err("--output is only valid for export".to_string());
+            }
+            return Ok(Parsed::Mcp { direct });
+        }
+        ("metrics", [email]) => Command::Metrics {
+            email: email.to_string(),
+        },
+        ("refresh-quota", [email]) => Command::RefreshQuota {
+            email: email.to_string(),
+        },
+        ("export", []) => Command::Export {
+            output: output.take(),
+        },
+        ("import", [source]) => Command::Import {
+            source: source.to_string(),
+            entries: Value::Null,
+        },
+        ("switch" | "metrics" | "refresh-quota", _) => {
+            return Err(format!("usage: {name} <email|id>"));
+        }
+        ("import", _) => return Err("usage: import <file|->".to_string()),
+        ("backup-current" | "export" | "doctor" | "mcp", _) => {
+            return Err(format!("{name} takes no arguments"));
+        }
+        _ if help => return Ok(Parsed::Help),
+        _ => return Ok(Parsed::NotCli),
+    };
+
+    if help {
+        return Ok(Parsed::Help);
+    }
+    if output.is_some() {
+        return Err("--output is only valid for export".to_string());
+    }
+
+    Ok(Parsed::Run(Invocation {
+        command,
+        json,
+        direct,
+    }))
+}
+
+/// 执行命令，返回退出码
+async fn run(mut invocation: Invocation) -> Result<i32, AgentError> {
+    if let Command::Import { source, entries } = &mut invocation.command {
+        *entries = read_import(source)?;
+    }
+
+    let (value, route) = transport::execute(&invocation.command, invocation.direct).await?;
+    if route == Route::Direct && !invocation.json {
+        eprintln!("(no running agent found, ran directly)");
+    }
+
+    match &invocation.command {
+        Command::Export { output } => {
+            write_export(&value, output.as_ref(), invocation.json)?;
+            return Ok(0);
+        }
+        // 有检查未通过时以失败状态码退出，便于脚本判断
+        Command::Doctor => {
+            if invocation.json {
+                print_json(&value);
+            } else {
+                print_doctor(&value);
+            }
+            let ok = value.get("ok").and_then(Value::as_bool).unwrap_or(false);
+            return Ok(if ok { 0 } else { EXIT_FAILURE });
+        }
+        _ if invocation.json => print_json(&value),
+        Command::ListAccounts => {
+            // 标记当前账户，获取失败时不影响列表输出
+            let current = transport::execute(&Command::CurrentAccount, route == Route::Direct)
+                .await
+                .ok()
+                .and_then(|(current, _)| account_id(&current).map(str::to_string));
+            print_accounts(&value, current.as_deref());
+        }
+        Command::CurrentAccount => match account_email(&value) {
+            Some(email) => println!("{email}"),
+            None => println!("(not signed in)"),
+        },
+        Command::Switch { .. } | Command::BackupCurrent => print_message(&value),
+        Command::Metrics { .. } => print_metrics(&value),
+        Command::RefreshQuota { .. } => print_refresh(&value),
+        Command::Import { .. } => print_import(&value),
+    }
+
+    Ok(0)
+}
+
+/// 读取导出文件（`-` 表示 stdin）
+fn read_import(source: &str) -> Result<Value, AgentError> {
+    let content = if source == "-" {
+        let mut buffer = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buffer)
+            .map_err(|e| AgentError::from_io(e, "Failed to read stdin"))?;
+        buffer
+    } else {
+        std::fs::read_to_string(source)
+            .map_err(|e| AgentError::from_io(e, format!("Failed to read {source}")))?
+    };
+
+    let entries: Value = serde_json::from_str(&content)
+        .map_err(|e| AgentError::InvalidInput(format!("Invalid export file: {e}")))?;
+    if !entries.is_array() {
+        return Err(AgentError::InvalidInput(
+            "Invalid export file: expected a JSON array".to_string(),
+        ));
+    }
+    Ok(entries)
+}
+
+/// 写出导出数据；文件包含账户凭证，权限设为仅当前用户可读写
+fn write_export(value: &Value, output: Option<&PathBuf>, json: bool) -> Result<(), AgentError> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize export: {e}")))?;
+    let count = value.as_array().map(Vec::len).unwrap_or_default();
+
+    let Some(path) = output else {
+        println!("{content}");
+        return Ok(());
+    };
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| AgentError::from_io(e, format!("Failed to create {}", path.display())))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| AgentError::from_io(e, format!("Failed to write {}", path.display())))?;
+
+    if json {
+        print_json(&json!({ "ok": true, "count": count, "path": path }));
+    } else {
+        println!("Exported {count} account(s) to {}", path.display());
+    }
+    Ok(())
+}
+
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+    );
+}
+
+fn account_email(account: &Value) -> Option<&str> {
+    account
+        .get("antigravity_auth_status")?
+        .get("email")?
+        .as_str()
+        .filter(|email| !email.is_empty())
+}
+
+fn account_id(account: &Value) -> Option<&str> {
+    account.get("account_id")?.as_str()
+}
+
+/// 每行输出邮箱与账户 ID；隐私模式下邮箱被遮盖，可用 ID 指定账户
+fn print_accounts(accounts: &Value, current_id: Option<&str>) {
+    let rows: Vec<(&str, &str)> = accounts
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|account| Some((account_email(account)?, account_id(account)?)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if rows.is_empty() {
+        println!("No accounts backed up yet");
+        return;
+    }
+
+    let width = rows.iter().map(|(email, _)| email.len()).max().unwrap_or(0);
+    for (email, id) in rows {
+        let marker = if Some(id) == current_id { "*" } else { " " };
+        println!("{marker} {email:<width$}  {id}");
+    }
+}
+
+fn print_message(result: &Value) {
+    let message = result
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("Done");
+    println!("{message}");
+}
+
+fn print_metrics(metrics: &Value) {
+    let email = metrics
+        .get("email")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    println!("{email}");
+
+    let quotas = metrics
+        .get("quotas")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    if quotas.is_empty() {
+        println!("  (no quota information)");
+    }
+
+    for quota in quotas {
+        let name = quota
+            .get("model_name")
+            .and_then(Value::as_str)
+            .unwrap_or("?");
+        let percentage = quota
+            .get("percentage")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+        let reset = quota
+            .get("reset_text")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if reset.is_empty() {
+            println!("  {name:<14} {:>5.1}%", percentage * 100.0);
+        } else {
+            println!("  {name:<14} {:>5.1}%  resets {reset}", percentage * 100.0);
+        }
+    }
+}
+
+fn print_refresh(result: &Value) {
+    print_message(result);
+    for (label, key) in [
+        ("triggered", "triggered_models"),
+        ("skipped", "skipped_details"),
+        ("failed", "failed_models"),
+    ] {
+        let items: Vec<&str> = result
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|list| list.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !items.is_empty() {
+            println!("  {label}: {}", items.join(", "));
+        }
+    }
+}
+
+fn print_import(result: &Value) {
+    let restored = result
+        .get("restoredCount")
+        .and_then(Value::as_u64)
+        .unwrap_or_default();
+    println!("Imported {restored} account(s)");
+
+    for failed in result
+        .get("failed")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let filename = failed
+            .get("filename")
+            .and_then(Value::as_str)
+            .unwrap_or("?");
+        let error = failed.get("error").and_then(Value::as_str).unwrap_or("");
+        println!("  failed: {filename}: {error}");
+    }
+}
+
+fn print_doctor(report: &Value) {
+    for check in report
+        .get("checks")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let passed = check.get("status").and_then(Value::as_str) == Some("pass");
+        let id = check.get("id").and_then(Value::as_str).unwrap_or("?");
+        let message = check.get("message").and_then(Value::as_str).unwrap_or("");
+        println!("[{}] {id}: {message}", if passed { "ok" } else { "FAIL" });
+        if let Some(fix) = check.get("fix").and_then(Value::as_str) {
+            println!("       fix: {fix}");
+        }
+    }
+}
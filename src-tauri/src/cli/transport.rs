@@ -0,0 +1,290 @@
+//! # CLI 传输层
+//!
+//! 优先通过本地 API 调用正在运行的 Agent 实例，使切换进度、事件推送等与 GUI 保持一致；
+//! 本地 API 不可达（没有运行中的实例）时直接调用服务层。
+//!
+//! 本地 API 优先走配置目录下的 Unix socket（见 [`crate::server::listen`]），其次是 TCP 端口。
+
+use super::Command;
+use crate::error::AgentError;
+use crate::server::listen::{ListenConfig, DEFAULT_TCP_PORT};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// 连接超时（没有运行中的实例时应尽快回退）
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 请求超时（切换账户需要等待 Antigravity 重启）
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 本地 API 请求：方法、路径、JSON 请求体
+type ApiRequest = (Method, &'static str, Option<Value>);
+
+/// 命令的执行方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// 通过本地 API
+    Api,
+    /// 直接调用服务层
+    Direct,
+}
+
+/// API 调用失败
+enum ApiFailure {
+    /// 没有运行中的实例
+    Unreachable(String),
+    /// 实例返回了错误
+    Failed(AgentError),
+}
+
+/// 执行命令，返回结果与实际使用的执行方式
+///
+/// # 参数
+///
+/// - `direct_only`: 跳过本地 API，直接调用服务层（`--direct`）
+pub async fn execute(command: &Command, direct_only: bool) -> Result<(Value, Route), AgentError> {
+    if !direct_only {
+        let (method, path, body) = api_request(command);
+        if let Some(value) = call_running(method, path, body).await? {
+            return Ok((value, Route::Api));
+        }
+    }
+
+    call_direct(command)
+        .await
+        .map(|value| (value, Route::Direct))
+}
+
+/// 调用运行中实例的本地 API
+///
+/// 没有运行中的实例（socket 与 TCP 均不可达）时返回 `Ok(None)`。
+pub async fn call_running(
+    method: Method,
+    path: &'static str,
+    body: Option<Value>,
+) -> Result<Option<Value>, AgentError> {
+    match send((method, path, body)).await {
+        Ok(value) => Ok(Some(value)),
+        Err(ApiFailure::Failed(error)) => Err(error),
+        Err(ApiFailure::Unreachable(reason)) => {
+            tracing::debug!(target: "cli", reason = %reason, "本地 API 不可达");
+            Ok(None)
+        }
+    }
+}
+
+/// 命令对应的本地 API 请求
+fn api_request(command: &Command) -> ApiRequest {
+    match command {
+        Command::ListAccounts => (Method::GET, "/api/get_antigravity_accounts", None),
+        Command::CurrentAccount => (
+            Method::GET,
+            "/api/get_current_antigravity_account_info",
+            None,
+        ),
+        Command::Switch { email } => (
+            Method::POST,
+            "/api/switch_to_antigravity_account",
+            Some(json!({ "account_name": email })),
+        ),
+        Command::BackupCurrent => (Method::POST, "/api/save_antigravity_current_account", None),
+        Command::Metrics { email } => (
+            Method::POST,
+            "/api/get_account_metrics",
+            Some(json!({ "email": email })),
+        ),
+        Command::RefreshQuota { email } => (
+            Method::POST,
+            "/api/trigger_quota_refresh",
+            Some(json!({ "email": email })),
+        ),
+        Command::Export { .. } => (Method::GET, "/api/collect_account_contents", None),
+        Command::Import { entries, .. } => (
+            Method::POST,
+            "/api/restore_backup_files",
+            Some(entries.clone()),
+        ),
+        Command::Doctor => (Method::GET, "/api/doctor", None),
+    }
+}
+
+async fn send(request: ApiRequest) -> Result<Value, ApiFailure> {
+    // 只读取、不生成：没有 Token 文件说明没有可用的运行中实例，回退到直接执行
+    let tokens = crate::server::auth::load_tokens()
+        .map_err(|e| ApiFailure::Unreachable(format!("No local API token: {e}")))?;
+    let config = ListenConfig::for_client();
+
+    // socket 存在时优先使用；socket 残留但无人监听时再尝试 TCP
+    #[cfg(unix)]
+    if let Some(socket) = &config.unix_socket {
+        match send_unix(socket, &request, &tokens.admin).await {
+            Err(ApiFailure::Unreachable(reason)) => {
+                tracing::debug!(target: "cli", reason = %reason, "Unix socket 不可用，尝试 TCP");
+            }
+            result => return result.and_then(into_result),
+        }
+    }
+
+    let port = config.tcp_port.unwrap_or(DEFAULT_TCP_PORT);
+    send_tcp(port, request, &tokens.admin)
+        .await
+        .and_then(into_result)
+}
+
+/// 将 API 响应转换为结果，非 2xx 响应按错误体 `{code, message}` 还原为 [`AgentError`]
+fn into_result((status, body): (StatusCode, Value)) -> Result<Value, ApiFailure> {
+    if status.is_success() {
+        return Ok(body);
+    }
+
+    let code = body.get("code").and_then(Value::as_str).unwrap_or_default();
+    let message = body
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Local API returned {status}"));
+    Err(ApiFailure::Failed(AgentError::from_code(code, message)))
+}
+
+fn invalid_response(status: StatusCode, error: impl std::fmt::Display) -> ApiFailure {
+    ApiFailure::Failed(AgentError::Upstream(format!(
+        "Invalid local API response ({status}): {error}"
+    )))
+}
+
+async fn send_tcp(
+    port: u16,
+    (method, path, body): ApiRequest,
+    token: &str,
+) -> Result<(StatusCode, Value), ApiFailure> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| {
+            ApiFailure::Failed(AgentError::Internal(format!(
+                "Failed to build HTTP client: {e}"
+            )))
+        })?;
+
+    let mut request = client
+        .request(method, format!("http://127.0.0.1:{port}{path}"))
+        .bearer_auth(token);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        if e.is_connect() {
+            ApiFailure::Unreachable(e.to_string())
+        } else {
+            ApiFailure::Failed(AgentError::Upstream(format!(
+                "Local API request failed: {e}"
+            )))
+        }
+    })?;
+
+    let status = response.status();
+    let body = response
+        .json()
+        .await
+        .map_err(|e| invalid_response(status, e))?;
+    Ok((status, body))
+}
+
+/// 通过 Unix socket 发送请求（HTTP/1.1）
+#[cfg(unix)]
+async fn send_unix(
+    socket: &std::path::Path,
+    (method, path, body): &ApiRequest,
+    token: &str,
+) -> Result<(StatusCode, Value), ApiFailure> {
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Bytes;
+    use hyper::header;
+
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::UnixStream::connect(socket))
+        .await
+        .map_err(|_| ApiFailure::Unreachable("Connecting to unix socket timed out".to_string()))?
+        .map_err(|e| ApiFailure::Unreachable(e.to_string()))?;
+
+    let (mut sender, connection) =
+        hyper::client::conn::http1::handshake(hyper_util::rt::TokioIo::new(stream))
+            .await
+            .map_err(|e| ApiFailure::Unreachable(e.to_string()))?;
+    tokio::spawn(connection);
+
+    let payload = match body {
+        Some(body) => Bytes::from(body.to_string()),
+        None => Bytes::new(),
+    };
+    let request = hyper::Request::builder()
+        .method(method.clone())
+        .uri(*path)
+        .header(header::HOST, "localhost")
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(payload))
+        .map_err(|e| ApiFailure::Failed(AgentError::Internal(format!("Invalid request: {e}"))))?;
+
+    let exchange = async {
+        let response = sender.send_request(request).await?;
+        let status = response.status();
+        let bytes = response.into_body().collect().await?.to_bytes();
+        Ok::<_, hyper::Error>((status, bytes))
+    };
+    let (status, bytes) = tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+        .await
+        .map_err(|_| {
+            ApiFailure::Failed(AgentError::Timeout(
+                "Local API request timed out".to_string(),
+            ))
+        })?
+        .map_err(|e| {
+            ApiFailure::Failed(AgentError::Upstream(format!(
+                "Local API request failed: {e}"
+            )))
+        })?;
+
+    let body = serde_json::from_slice(&bytes).map_err(|e| invalid_response(status, e))?;
+    Ok((status, body))
+}
+
+/// 将服务层返回值序列化为 JSON
+fn to_value<T: Serialize>(value: T) -> Result<Value, AgentError> {
+    serde_json::to_value(value)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize result: {e}")))
+}
+
+async fn call_direct(command: &Command) -> Result<Value, AgentError> {
+    use crate::services::{account, backup};
+
+    let config_dir = crate::directories::get_config_directory();
+    // 与经由 API 时的输出一致：隐私模式下遮盖邮箱
+    let settings =
+        crate::app_settings::load_settings_from_disk(&crate::directories::get_app_settings_file());
+    let redaction = account::Redaction::new(settings.private_mode);
+
+    match command {
+        Command::ListAccounts => to_value(redaction.accounts(account::get_all(&config_dir).await?)),
+        Command::CurrentAccount => to_value(redaction.account(account::get_current().await?)),
+        Command::Switch { email } => to_value(account::switch(email.clone()).await?),
+        Command::BackupCurrent => to_value(account::backup_current().await?),
+        Command::Metrics { email } => {
+            to_value(redaction.metrics(account::get_metrics(&config_dir, email.clone()).await?))
+        }
+        Command::RefreshQuota { email } => to_value(
+            redaction
+                .trigger_result(account::trigger_quota_refresh(&config_dir, email.clone()).await?),
+        ),
+        Command::Export { .. } => to_value(backup::collect_contents(&config_dir).await?),
+        Command::Import { entries, .. } => {
+            let entries = serde_json::from_value(entries.clone())
+                .map_err(|e| AgentError::InvalidInput(format!("Invalid export file: {e}")))?;
+            to_value(backup::restore_files(&config_dir, entries).await?)
+        }
+        Command::Doctor => to_value(crate::services::diagnostics::doctor().await?),
+    }
+}
@@ -1,6 +1,10 @@
 //! 数据库监控模块 - 监控关键 key 的变化并推送事件
+//!
+//! 登录变化时还会按 `auto_capture_enabled` 设置自动备份当前账户。自动备份只由该设置控制，
+//! 不受前端启动或停止监控（`start_monitoring` / `stop_monitoring`）影响，窗口关闭时同样生效。
 
 use crate::constants::database;
+use crate::events::{self, AgentEvent};
 use serde_json::Value;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
@@ -10,41 +14,55 @@ use tracing::{error, info, warn};
 
 /// 数据库监控器
 pub struct DatabaseMonitor {
-    app_handle: AppHandle,
+    /// GUI 模式下用于通知前端，headless 模式下为空
+    app_handle: Option<AppHandle>,
     last_data: Arc<Mutex<Option<Value>>>,
+    /// 是否向前端窗口推送 `database-changed`（由前端启动或停止）
     is_running: Arc<Mutex<bool>>,
+    /// 轮询任务是否已启动
+    is_polling: Arc<Mutex<bool>>,
 }
 
 impl DatabaseMonitor {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: Option<AppHandle>) -> Self {
         Self {
             app_handle,
             last_data: Arc::new(Mutex::new(None)),
             is_running: Arc::new(Mutex::new(false)),
+            is_polling: Arc::new(Mutex::new(false)),
         }
     }
 
-    /// 启动数据库监控
+    /// 启动数据库监控（向前端窗口推送数据库变化）
     pub async fn start_monitoring(&self) {
         info!("🔧 启动数据库自动监控");
+        *self.is_running.lock().await = true;
+        self.start_polling().await;
+    }
+
+    /// 启动轮询任务，重复调用时忽略
+    ///
+    /// 轮询一直运行到进程退出：自动备份与事件总线上的 `account_changed` 不依赖前端，
+    /// 只有发给前端窗口的 `database-changed` 在监控启动后才推送。
+    pub async fn start_polling(&self) {
+        {
+            let mut polling = self.is_polling.lock().await;
+            if *polling {
+                return;
+            }
+            *polling = true;
+        }
 
         let last_data = self.last_data.clone();
         let is_running = self.is_running.clone();
         let app_handle = self.app_handle.clone();
 
-        *is_running.lock().await = true;
-
         tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(10));
 
             loop {
                 interval.tick().await;
 
-                if !*is_running.lock().await {
-                    info!("⏹️ 数据库监控已停止");
-                    break;
-                }
-
                 let Some(new_data) = Self::get_data() else {
                     continue;
                 };
@@ -57,10 +75,23 @@ impl DatabaseMonitor {
                     None => false, // 首次加载不触发事件
                 };
 
+                // 首次加载时同样检查，补上 Agent 未运行期间的登录
+                if has_changes || last.is_none() {
+                    Self::capture(&app_handle).await;
+                }
+
                 if has_changes {
                     info!("📢 检测到数据库变化");
-                    if let Err(e) = app_handle.emit("database-changed", &new_data) {
-                        error!("❌ 推送事件失败: {}", e);
+                    // SSE、WebSocket 与 D-Bus 订阅者不依赖前端是否启动了监控
+                    events::publish(AgentEvent::AccountChanged {
+                        email: Self::extract_email(&new_data),
+                    });
+                    if *is_running.lock().await {
+                        if let Some(app_handle) = &app_handle {
+                            if let Err(e) = app_handle.emit("database-changed", &new_data) {
+                                error!("❌ 推送事件失败: {}", e);
+                            }
+                        }
                     }
                 }
 
@@ -69,12 +100,50 @@ impl DatabaseMonitor {
         });
     }
 
-    /// 停止数据库监控
+    /// 监控是否在运行
+    pub async fn is_monitoring(&self) -> bool {
+        *self.is_running.lock().await
+    }
+
+    /// 停止数据库监控（不再向前端窗口推送变化，自动备份与事件总线照常）
     pub async fn stop_monitoring(&self) {
         info!("⏹️ 停止数据库自动监控");
         *self.is_running.lock().await = false;
     }
 
+    /// 按设置自动备份当前账户，备份后通知前端刷新列表
+    async fn capture(app_handle: &Option<AppHandle>) {
+        let settings = crate::app_settings::load_settings_from_disk(
+            &crate::directories::get_app_settings_file(),
+        );
+        if !settings.auto_capture_enabled {
+            return;
+        }
+
+        match crate::services::account::auto_capture(&settings).await {
+            Ok(Some((account_name, reason))) => {
+                info!(account_name = %account_name, reason = ?reason, "已自动备份当前账户");
+                if let Some(app_handle) = app_handle {
+                    let payload =
+                        serde_json::json!({ "account_name": account_name, "reason": reason });
+                    if let Err(e) = app_handle.emit("account-captured", payload) {
+                        error!("❌ 推送事件失败: {}", e);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!(error = %e.message(), "自动备份当前账户失败"),
+        }
+    }
+
+    /// 从监控数据中提取当前登录邮箱
+    fn extract_email(data: &Value) -> Option<String> {
+        data.get(database::AUTH_STATUS)?
+            .get("email")?
+            .as_str()
+            .map(str::to_string)
+    }
+
     /// 获取数据库数据（失败返回 None，内部记录日志）
     fn get_data() -> Option<Value> {
         let db_path = crate::platform::get_antigravity_db_path()?;
@@ -83,6 +152,7 @@ impl DatabaseMonitor {
             Ok(c) => c,
             Err(e) => {
                 warn!("打开数据库失败: {}", e);
+                crate::telemetry::record_db_monitor_poll_error();
                 return None;
             }
         };
@@ -92,17 +162,22 @@ impl DatabaseMonitor {
             database::OAUTH_TOKEN,
             database::AUTH_STATUS,
         ];
-        let mut stmt = conn
+        let rows = conn
             .prepare("SELECT key, value FROM ItemTable WHERE key IN (?, ?, ?)")
-            .ok()?;
-
-        let rows: Vec<(String, String)> = stmt
-            .query_map(rusqlite::params![keys[0], keys[1], keys[2]], |row| {
-                Ok((row.get(0)?, row.get(1)?))
-            })
-            .ok()?
-            .filter_map(|r| r.ok())
-            .collect();
+            .and_then(|mut stmt| {
+                let rows = stmt.query_map(rusqlite::params![keys[0], keys[1], keys[2]], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?;
+                Ok(rows.filter_map(|r| r.ok()).collect::<Vec<_>>())
+            });
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("读取数据库失败: {}", e);
+                crate::telemetry::record_db_monitor_poll_error();
+                return None;
+            }
+        };
 
         let mut data = serde_json::Map::new();
         for (key, value) in rows {
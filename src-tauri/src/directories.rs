@@ -55,6 +55,31 @@ pub fn get_antigravity_path_file() -> PathBuf {
     get_config_directory().join("antigravity_path.json")
 }
 
+/// 获取本地 API Token 文件路径
+pub fn get_api_tokens_file() -> PathBuf {
+    get_config_directory().join("api_tokens.json")
+}
+
+/// 获取本地 API Unix socket 路径
+pub fn get_api_socket_file() -> PathBuf {
+    get_config_directory().join("agent.sock")
+}
+
+/// 获取已配对远程设备文件路径
+pub fn get_remote_devices_file() -> PathBuf {
+    get_config_directory().join("remote_devices.json")
+}
+
+/// 获取远程监听器 TLS 证书路径
+pub fn get_remote_tls_cert_file() -> PathBuf {
+    get_config_directory().join("remote_tls_cert.pem")
+}
+
+/// 获取远程监听器 TLS 私钥路径
+pub fn get_remote_tls_key_file() -> PathBuf {
+    get_config_directory().join("remote_tls_key.pem")
+}
+
 /// 在应用启动时检查账户备份格式。
 /// 发现旧格式账户文件则重命名为 `原文件名.old`。
 fn rename_legacy_backup_files_in_dir(dir: &PathBuf, dir_label: &str) -> io::Result<usize> {
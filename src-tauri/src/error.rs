@@ -0,0 +1,184 @@
+//! 统一错误类型
+//!
+//! 服务层（`services::*`、`antigravity::*`）统一返回 [`AgentError`]。
+//! 每个变体对应一个稳定的机器可读 `code` 和 HTTP 状态码，
+//! 与成功时的 `CommandResult::code` 对称，调用方无需匹配错误字符串。
+
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use std::fmt;
+
+/// Agent 错误
+#[derive(Debug, Clone)]
+pub enum AgentError {
+    /// 请求的资源不存在（账户备份、数据库、可执行文件等）
+    NotFound(String),
+    /// 参数不合法
+    InvalidInput(String),
+    /// 操作要求 Antigravity 未运行
+    AntigravityRunning(String),
+    /// SQLite 数据库被占用
+    DbLocked(String),
+    /// 缺少或无效的凭证（本地 API Token 或 Google OAuth Token）
+    Unauthorized(String),
+    /// 凭证有效但权限不足
+    Forbidden(String),
+    /// 文件路径超出 Agent 允许访问的目录，见 [`crate::path_policy`]
+    PathNotAllowed(String),
+    /// 上游服务（Google API、VS Code 扩展等）返回错误
+    Upstream(String),
+    /// 等待上游响应或条件满足超时
+    Timeout(String),
+    /// 当前运行模式不支持该功能（如 `--headless` 下的托盘与窗口）
+    Unavailable(String),
+    /// 其他内部错误
+    Internal(String),
+}
+
+impl AgentError {
+    /// 机器可读的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AgentError::NotFound(_) => "not_found",
+            AgentError::InvalidInput(_) => "invalid_input",
+            AgentError::AntigravityRunning(_) => "antigravity_running",
+            AgentError::DbLocked(_) => "db_locked",
+            AgentError::Unauthorized(_) => "unauthorized",
+            AgentError::Forbidden(_) => "forbidden",
+            AgentError::PathNotAllowed(_) => "path_not_allowed",
+            AgentError::Upstream(_) => "upstream_error",
+            AgentError::Timeout(_) => "timeout",
+            AgentError::Unavailable(_) => "unavailable",
+            AgentError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// 人类可读的错误信息
+    pub fn message(&self) -> &str {
+        match self {
+            AgentError::NotFound(msg)
+            | AgentError::InvalidInput(msg)
+            | AgentError::AntigravityRunning(msg)
+            | AgentError::DbLocked(msg)
+            | AgentError::Unauthorized(msg)
+            | AgentError::Forbidden(msg)
+            | AgentError::PathNotAllowed(msg)
+            | AgentError::Upstream(msg)
+            | AgentError::Timeout(msg)
+            | AgentError::Unavailable(msg)
+            | AgentError::Internal(msg) => msg,
+        }
+    }
+
+    /// 在错误信息前追加上下文，保留错误类型
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        let wrap = |msg: String| format!("{context}: {msg}");
+        match self {
+            AgentError::NotFound(msg) => AgentError::NotFound(wrap(msg)),
+            AgentError::InvalidInput(msg) => AgentError::InvalidInput(wrap(msg)),
+            AgentError::AntigravityRunning(msg) => AgentError::AntigravityRunning(wrap(msg)),
+            AgentError::DbLocked(msg) => AgentError::DbLocked(wrap(msg)),
+            AgentError::Unauthorized(msg) => AgentError::Unauthorized(wrap(msg)),
+            AgentError::Forbidden(msg) => AgentError::Forbidden(wrap(msg)),
+            AgentError::PathNotAllowed(msg) => AgentError::PathNotAllowed(wrap(msg)),
+            AgentError::Upstream(msg) => AgentError::Upstream(wrap(msg)),
+            AgentError::Timeout(msg) => AgentError::Timeout(wrap(msg)),
+            AgentError::Unavailable(msg) => AgentError::Unavailable(wrap(msg)),
+            AgentError::Internal(msg) => AgentError::Internal(wrap(msg)),
+        }
+    }
+
+    /// 从错误码还原（如解析 HTTP API 返回的错误体），未知错误码视为内部错误
+    pub fn from_code(code: &str, message: String) -> Self {
+        match code {
+            "not_found" => AgentError::NotFound(message),
+            "invalid_input" => AgentError::InvalidInput(message),
+            "antigravity_running" => AgentError::AntigravityRunning(message),
+            "db_locked" => AgentError::DbLocked(message),
+            "unauthorized" => AgentError::Unauthorized(message),
+            "forbidden" => AgentError::Forbidden(message),
+            "path_not_allowed" => AgentError::PathNotAllowed(message),
+            "upstream_error" => AgentError::Upstream(message),
+            "timeout" => AgentError::Timeout(message),
+            "unavailable" => AgentError::Unavailable(message),
+            _ => AgentError::Internal(message),
+        }
+    }
+
+    /// 从 SQLite 错误构造，区分数据库被锁定的情况
+    pub fn from_sqlite(error: rusqlite::Error, context: impl fmt::Display) -> Self {
+        let is_locked = matches!(
+            error.sqlite_error_code(),
+            Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+        );
+        if is_locked {
+            AgentError::DbLocked(format!("{context}: {error}"))
+        } else {
+            AgentError::Internal(format!("{context}: {error}"))
+        }
+    }
+
+    /// 从 IO 错误构造，区分文件不存在的情况
+    pub fn from_io(error: std::io::Error, context: impl fmt::Display) -> Self {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            AgentError::NotFound(format!("{context}: {error}"))
+        } else {
+            AgentError::Internal(format!("{context}: {error}"))
+        }
+    }
+}
+
+impl fmt::Display for AgentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AgentError {}
+
+/// 尚未迁移到 [`AgentError`] 的模块仍返回 `String`，统一视为内部错误
+impl From<String> for AgentError {
+    fn from(message: String) -> Self {
+        AgentError::Internal(message)
+    }
+}
+
+impl ResponseError for AgentError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AgentError::NotFound(_) => StatusCode::NOT_FOUND,
+            AgentError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            AgentError::AntigravityRunning(_) => StatusCode::CONFLICT,
+            AgentError::DbLocked(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AgentError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AgentError::Forbidden(_) | AgentError::PathNotAllowed(_) => StatusCode::FORBIDDEN,
+            AgentError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            AgentError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AgentError::Unavailable(_) => StatusCode::NOT_IMPLEMENTED,
+            AgentError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorResponse {
+            ok: false,
+            code: self.code(),
+            message: self.message().to_string(),
+            error: self.message().to_string(),
+        })
+    }
+}
+
+/// 错误响应体
+///
+/// 与 `CommandResult` 对齐：`{ ok, code, message }`，并保留旧版客户端读取的 `error` 字段。
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct ErrorResponse {
+    /// 始终为 `false`
+    pub ok: bool,
+    /// 机器可读的错误码（如 `not_found`、`antigravity_running`）
+    pub code: &'static str,
+    pub message: String,
+    /// 同 `message`（兼容旧版客户端）
+    pub error: String,
+}
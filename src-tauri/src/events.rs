@@ -0,0 +1,271 @@
+//! # Agent 事件总线
+//!
+//! 进程内的发布/订阅通道，把账户、备份、切换进度、设置、进程状态等变化
+//! 统一成 [`AgentEvent`]，供 HTTP 层以 SSE（`GET /api/events`）推送给任意客户端。
+//!
+//! ## 背景
+//!
+//! 之前这些变化只通过 `app_handle.emit(...)` 发给 Tauri WebView，
+//! 外部脚本和仪表盘必须实现自定义的 `/ws` 协议才能感知。
+//!
+//! ## 设计
+//!
+//! - 基于 `tokio::sync::broadcast`，发布方不关心是否有订阅者
+//! - 订阅者处理过慢时会丢弃最旧的事件（`Lagged`），不会阻塞发布方
+//! - 通过 `lazy_static` 实现全局单例，与 `CONNECTION_MANAGER` 一致
+//! - 每个事件归属一个 [`Topic`]，扩展通过 `/ws` 的 `subscribe` 只接收关心的主题
+
+use crate::services::account::{AccountMetadata, AccountMetrics};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+/// 事件通道容量
+///
+/// 超出后最慢的订阅者会收到 `Lagged`，丢失最旧的事件。
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// 进程状态轮询间隔
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 事件主题
+///
+/// 扩展按主题订阅事件，避免收到无关的推送。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    /// 账户变化、备份、切换进度
+    Accounts,
+    /// 当前账户的配额
+    Quotas,
+    /// Antigravity 进程启停
+    Process,
+    /// 应用设置
+    Settings,
+    /// Agent 日志（已脱敏）
+    Logs,
+}
+
+impl Topic {
+    /// 全部主题
+    pub const ALL: [Topic; 5] = [
+        Topic::Accounts,
+        Topic::Quotas,
+        Topic::Process,
+        Topic::Settings,
+        Topic::Logs,
+    ];
+}
+
+/// Agent 事件
+///
+/// 序列化后通过 `type` 字段区分事件类型，与 SSE 的 `event:` 字段一致。
+///
+/// # JSON 格式示例
+///
+/// ```json
+/// {"type": "account_changed", "email": "user@example.com"}
+/// {"type": "switch_progress", "account_name": "user@example.com", "stage": "restoring", "message": "..."}
+/// ```
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentEvent {
+    /// Antigravity 数据库中的登录账户发生变化
+    AccountChanged {
+        /// 新的账户邮箱（未登录或解析失败时为空）
+        email: Option<String>,
+    },
+    /// 账户备份文件已写入
+    BackupWritten {
+        account_name: String,
+        file_path: String,
+    },
+    /// 数据库监控自动备份了新登录或令牌已刷新的账户
+    AccountCaptured {
+        account_name: String,
+        reason: CaptureReason,
+    },
+    /// 账户元数据已修改
+    AccountMetadataUpdated {
+        account_id: String,
+        metadata: AccountMetadata,
+    },
+    /// 请求切换账户（如托盘菜单点击），由前端执行实际切换
+    SwitchRequested {
+        account_name: String,
+        source: String,
+    },
+    /// 账户切换进度
+    SwitchProgress {
+        account_name: String,
+        stage: SwitchStage,
+        message: String,
+    },
+    /// 应用设置已修改
+    SettingsChanged { key: String, value: Value },
+    /// 远程设备已配对、修改作用域或撤销
+    RemoteDevicesChanged,
+    /// Antigravity 进程已启动
+    ProcessStarted,
+    /// Antigravity 进程已退出
+    ProcessStopped,
+    /// 账户配额已刷新
+    QuotaUpdated {
+        account_id: String,
+        email: String,
+        metrics: AccountMetrics,
+    },
+    /// Agent 日志（INFO 及以上，已脱敏）
+    Log {
+        level: String,
+        target: String,
+        message: String,
+    },
+}
+
+impl AgentEvent {
+    /// 事件名称（SSE `event:` 字段）
+    pub fn name(&self) -> &'static str {
+        match self {
+            AgentEvent::AccountChanged { .. } => "account_changed",
+            AgentEvent::BackupWritten { .. } => "backup_written",
+            AgentEvent::AccountCaptured { .. } => "account_captured",
+            AgentEvent::AccountMetadataUpdated { .. } => "account_metadata_updated",
+            AgentEvent::SwitchRequested { .. } => "switch_requested",
+            AgentEvent::SwitchProgress { .. } => "switch_progress",
+            AgentEvent::SettingsChanged { .. } => "settings_changed",
+            AgentEvent::RemoteDevicesChanged => "remote_devices_changed",
+            AgentEvent::ProcessStarted => "process_started",
+            AgentEvent::ProcessStopped => "process_stopped",
+            AgentEvent::QuotaUpdated { .. } => "quota_updated",
+            AgentEvent::Log { .. } => "log",
+        }
+    }
+
+    /// 事件所属主题
+    pub fn topic(&self) -> Topic {
+        match self {
+            AgentEvent::AccountChanged { .. }
+            | AgentEvent::BackupWritten { .. }
+            | AgentEvent::AccountCaptured { .. }
+            | AgentEvent::AccountMetadataUpdated { .. }
+            | AgentEvent::SwitchRequested { .. }
+            | AgentEvent::SwitchProgress { .. } => Topic::Accounts,
+            AgentEvent::SettingsChanged { .. } | AgentEvent::RemoteDevicesChanged => {
+                Topic::Settings
+            }
+            AgentEvent::ProcessStarted | AgentEvent::ProcessStopped => Topic::Process,
+            AgentEvent::QuotaUpdated { .. } => Topic::Quotas,
+            AgentEvent::Log { .. } => Topic::Logs,
+        }
+    }
+}
+
+/// 自动备份的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureReason {
+    /// 该邮箱尚无备份
+    New,
+    /// 已有备份，但登录令牌已变化
+    Refreshed,
+}
+
+/// 账户切换阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchStage {
+    /// 正在关闭 Antigravity
+    Stopping,
+    /// 正在清理当前登录数据
+    Clearing,
+    /// 正在写入目标账户数据
+    Restoring,
+    /// 正在通知已连接的窗口重新加载（热切换）
+    Reloading,
+    /// 正在启动 Antigravity
+    Starting,
+    /// 某一步失败，正在恢复切换前的登录数据
+    RollingBack,
+    /// 切换完成
+    Completed,
+    /// 切换失败
+    Failed,
+}
+
+/// 事件总线
+pub struct EventBus {
+    sender: broadcast::Sender<AgentEvent>,
+}
+
+impl EventBus {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// 发布事件（没有订阅者时直接丢弃）
+    pub fn publish(&self, event: AgentEvent) {
+        tracing::debug!(target: "events", event = event.name(), "发布事件");
+        let _ = self.sender.send(event);
+    }
+
+    /// 订阅后续事件
+    pub fn subscribe(&self) -> broadcast::Receiver<AgentEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 是否有订阅者（用于跳过高频事件的构造开销）
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局事件总线实例
+    pub static ref EVENT_BUS: EventBus = EventBus::new();
+}
+
+/// 发布事件到全局事件总线
+pub fn publish(event: AgentEvent) {
+    EVENT_BUS.publish(event);
+}
+
+/// 订阅全局事件总线
+pub fn subscribe() -> broadcast::Receiver<AgentEvent> {
+    EVENT_BUS.subscribe()
+}
+
+/// 启动 Antigravity 进程状态监听
+///
+/// 定时检查进程是否运行，状态变化时发布 `ProcessStarted` / `ProcessStopped`。
+/// 首次检查只记录初始状态，不发布事件。
+pub fn spawn_process_watcher() {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PROCESS_POLL_INTERVAL);
+        let mut last_running: Option<bool> = None;
+
+        loop {
+            interval.tick().await;
+
+            let running = tokio::task::spawn_blocking(crate::platform::is_antigravity_running)
+                .await
+                .unwrap_or(false);
+
+            if let Some(previous) = last_running {
+                if previous != running {
+                    tracing::info!(target: "events", running, "Antigravity 进程状态变化");
+                    publish(if running {
+                        AgentEvent::ProcessStarted
+                    } else {
+                        AgentEvent::ProcessStopped
+                    });
+                }
+            }
+
+            last_running = Some(running);
+        }
+    });
+}
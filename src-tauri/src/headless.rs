@@ -0,0 +1,50 @@
+//! # Headless 模式
+//!
+//! `antigravity-agent --headless` 只启动本地 API（REST / SSE / WebSocket），
+//! 不创建 Tauri 窗口和托盘，适用于没有图形界面的远程 Linux 开发机。
+//!
+//! - 设置直接读写 `app_settings.json`（[`AppHost::headless`]）
+//! - 数据库监控随进程启动，账户变化照常推送给扩展
+//! - 托盘、窗口相关接口返回 `unavailable`
+//!
+//! 配套的 systemd 用户服务模板见 `src-tauri/resources/systemd/antigravity-agent.service`。
+
+use crate::host::AppHost;
+use crate::instance::LaunchAction;
+use crate::server::auth::ApiTokens;
+use crate::AppState;
+use std::sync::Arc;
+
+/// 命令行开关
+pub const HEADLESS_FLAG: &str = "--headless";
+
+/// 是否以 headless 模式启动
+pub fn is_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == HEADLESS_FLAG)
+}
+
+/// 以 headless 模式运行，直到 HTTP 服务停止
+///
+/// 服务线程退出即视为失败（通常是端口被占用），以非零状态码退出，
+/// 便于 systemd 按 `Restart=on-failure` 重启。
+pub fn run(app_state: AppState, api_tokens: Arc<ApiTokens>, actions: Vec<LaunchAction>) -> ! {
+    tracing::info!(target: "app::headless", "以 headless 模式启动，仅提供本地 API");
+
+    let host = AppHost::headless();
+
+    // 与 GUI 模式一致：监听进程启停并推送账户变化
+    crate::events::spawn_process_watcher();
+    let monitor_host = host.clone();
+    tauri::async_runtime::spawn(async move {
+        monitor_host.db_monitor().start_monitoring().await;
+    });
+
+    let server = crate::server::init(host.clone(), app_state, api_tokens);
+    crate::instance::perform(host, actions);
+    if server.join().is_err() {
+        tracing::error!(target: "app::headless", "HTTP 服务线程异常退出");
+    }
+
+    tracing::error!(target: "app::headless", "HTTP 服务已停止，退出进程");
+    std::process::exit(1);
+}
@@ -0,0 +1,68 @@
+//! # 宿主环境
+//!
+//! HTTP 服务通过 [`AppHost`] 访问设置、数据库监控以及 Tauri 运行时。
+//!
+//! - GUI 模式：持有 `AppHandle`，托盘、窗口和前端事件可用
+//! - `--headless` 模式：没有 Tauri 运行时，设置直接读写配置文件，
+//!   依赖 GUI 的接口返回 [`AgentError::Unavailable`]
+
+use crate::app_settings::AppSettingsManager;
+use crate::db_monitor::DatabaseMonitor;
+use crate::error::AgentError;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// HTTP 服务的宿主环境
+#[derive(Clone)]
+pub struct AppHost {
+    /// GUI 模式下的 Tauri 句柄，headless 模式下为空
+    app: Option<AppHandle>,
+    settings: Arc<AppSettingsManager>,
+    db_monitor: Arc<DatabaseMonitor>,
+}
+
+impl AppHost {
+    /// GUI 模式，与 Tauri 共享 `setup` 中注册的设置管理器和数据库监控器
+    pub fn gui(app: &AppHandle) -> Self {
+        Self {
+            app: Some(app.clone()),
+            settings: app.state::<Arc<AppSettingsManager>>().inner().clone(),
+            db_monitor: app.state::<Arc<DatabaseMonitor>>().inner().clone(),
+        }
+    }
+
+    /// headless 模式，不依赖 Tauri 运行时
+    pub fn headless() -> Self {
+        Self {
+            app: None,
+            settings: Arc::new(AppSettingsManager::new()),
+            db_monitor: Arc::new(DatabaseMonitor::new(None)),
+        }
+    }
+
+    /// 是否运行在 headless 模式
+    pub fn is_headless(&self) -> bool {
+        self.app.is_none()
+    }
+
+    /// 应用设置
+    pub fn settings(&self) -> &AppSettingsManager {
+        &self.settings
+    }
+
+    /// 数据库监控器
+    pub fn db_monitor(&self) -> &DatabaseMonitor {
+        &self.db_monitor
+    }
+
+    /// 获取 Tauri 句柄，headless 模式下返回 `Unavailable`
+    ///
+    /// # 参数
+    ///
+    /// - `feature`: 功能名称，用于错误信息（如 "System tray"）
+    pub fn app_handle(&self, feature: &str) -> Result<&AppHandle, AgentError> {
+        self.app.as_ref().ok_or_else(|| {
+            AgentError::Unavailable(format!("{feature} is unavailable in headless mode"))
+        })
+    }
+}
@@ -0,0 +1,218 @@
+//! # 单实例协调
+//!
+//! 第二次启动应用时，如果已有实例在运行，把启动参数通过本地 API 转发给它后退出，
+//! 避免启动一个端口被占用、没有 API 的残缺实例。
+//!
+//! 支持的启动参数：
+//!
+//! - `--switch <email>`：切换到指定账户
+//! - `--show`：显示主窗口（GUI 实例）
+//! - `antigravity-agent://switch?account=<email>`、`antigravity-agent://show`：URL scheme，与上面等价，
+//!   便于在团队 wiki 中放置切换账户的链接
+//!
+//! 不带参数的第二次启动等同于 `--show`。没有运行中的实例时，参数在本实例启动后执行。
+//!
+//! URL scheme 的注册：Linux 通过 deb 包中的 `.desktop` 文件（`MimeType=x-scheme-handler/antigravity-agent`），
+//! Windows 通过 NSIS 安装脚本写入注册表，两者都把 URL 作为命令行参数传入；
+//! macOS 通过 `Info.plist` 注册，URL 由 [`tauri::RunEvent::Opened`] 送达正在运行的实例。
+
+use crate::error::AgentError;
+use crate::host::AppHost;
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// URL scheme 名称
+pub const URL_SCHEME: &str = "antigravity-agent";
+
+/// 启动动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchAction {
+    /// 显示主窗口
+    Show,
+    /// 切换账户
+    Switch { email: String },
+}
+
+/// 启动协调结果
+pub enum Launch {
+    /// 已转发给运行中的实例（或参数错误），以给定状态码退出
+    Exit(i32),
+    /// 没有运行中的实例，正常启动后执行这些动作
+    Start(Vec<LaunchAction>),
+}
+
+impl LaunchAction {
+    /// 解析 `antigravity-agent://<action>?...` 链接
+    pub fn parse_url(url: &str) -> Result<Self, AgentError> {
+        let parsed = tauri::Url::parse(url)
+            .map_err(|e| AgentError::InvalidInput(format!("Invalid link {url}: {e}")))?;
+        if parsed.scheme() != URL_SCHEME {
+            return Err(AgentError::InvalidInput(format!(
+                "Unsupported link scheme: {}",
+                parsed.scheme()
+            )));
+        }
+
+        match parsed.host_str().unwrap_or_default() {
+            "show" => Ok(Self::Show),
+            "switch" => parsed
+                .query_pairs()
+                .find(|(key, _)| key == "account")
+                .map(|(_, email)| email.trim().to_string())
+                .filter(|email| !email.is_empty())
+                .map(|email| Self::Switch { email })
+                .ok_or_else(|| {
+                    AgentError::InvalidInput("Switch link requires ?account=<email>".to_string())
+                }),
+            other => Err(AgentError::InvalidInput(format!(
+                "Unsupported link action: {other}"
+            ))),
+        }
+    }
+
+    /// 对应的本地 API 请求
+    fn api_request(&self) -> (Method, &'static str, Option<Value>) {
+        match self {
+            Self::Show => (Method::POST, "/api/restore_from_tray", None),
+            Self::Switch { email } => (
+                Method::POST,
+                "/api/switch_to_antigravity_account",
+                Some(json!({ "account_name": email })),
+            ),
+        }
+    }
+}
+
+/// 从命令行参数中提取启动动作，忽略其他参数（如 `--headless`）
+pub fn parse_args(args: &[String]) -> Result<Vec<LaunchAction>, AgentError> {
+    let mut actions = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--show" => actions.push(LaunchAction::Show),
+            "--switch" => {
+                let email = iter.next().ok_or_else(|| {
+                    AgentError::InvalidInput("--switch requires an account email".to_string())
+                })?;
+                actions.push(LaunchAction::Switch {
+                    email: email.clone(),
+                });
+            }
+            url if url.starts_with(&format!("{URL_SCHEME}:")) => {
+                actions.push(LaunchAction::parse_url(url)?);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(actions)
+}
+
+/// 启动前检查是否已有实例在运行，有则转发启动参数
+pub fn coordinate() -> Launch {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let actions = match parse_args(&args) {
+        Ok(actions) => actions,
+        Err(error) => {
+            eprintln!("error: {}", error.message());
+            return Launch::Exit(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        // 无法检测时按首个实例启动，绑定端口失败会在日志中体现
+        Err(_) => return Launch::Start(actions),
+    };
+
+    runtime.block_on(async move {
+        let running =
+            match crate::cli::transport::call_running(Method::GET, "/api/get_all_settings", None)
+                .await
+            {
+                Ok(Some(settings)) => settings,
+                Ok(None) => return Launch::Start(actions),
+                Err(error) => {
+                    eprintln!(
+                        "error: the local API is served by another process: {}",
+                        error.message()
+                    );
+                    return Launch::Exit(1);
+                }
+            };
+
+        if crate::headless::is_requested() {
+            eprintln!("error: Antigravity Agent is already running");
+            return Launch::Exit(1);
+        }
+
+        let running_headless = running
+            .get("headless")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let actions = match (actions.is_empty(), running_headless) {
+            (false, _) => actions,
+            (true, false) => vec![LaunchAction::Show],
+            (true, true) => {
+                println!("Antigravity Agent is already running in headless mode");
+                return Launch::Exit(0);
+            }
+        };
+
+        Launch::Exit(forward(&actions).await)
+    })
+}
+
+/// 依次转发动作，返回退出码
+async fn forward(actions: &[LaunchAction]) -> i32 {
+    let mut code = 0;
+
+    for action in actions {
+        let (method, path, body) = action.api_request();
+        match crate::cli::transport::call_running(method, path, body).await {
+            Ok(Some(result)) => {
+                if let Some(message) = result.get("message").and_then(Value::as_str) {
+                    println!("{message}");
+                }
+            }
+            Ok(None) => {
+                eprintln!("error: the running instance stopped responding");
+                return 1;
+            }
+            Err(error) => {
+                eprintln!("error: {} ({})", error.message(), error.code());
+                code = 1;
+            }
+        }
+    }
+
+    code
+}
+
+/// 在本实例中执行启动动作（首次启动的参数、macOS 送达的链接）
+pub fn perform(host: AppHost, actions: Vec<LaunchAction>) {
+    if actions.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        for action in actions {
+            let result = match &action {
+                LaunchAction::Show => crate::services::system::tray::restore(&host).await,
+                LaunchAction::Switch { email } => crate::services::account::switch(email.clone())
+                    .await
+                    .map(|result| result.message),
+            };
+
+            match result {
+                Ok(message) => {
+                    tracing::info!(target: "app::instance", action = ?action, "{}", message)
+                }
+                Err(error) => {
+                    tracing::warn!(target: "app::instance", action = ?action, error = %error, "启动动作执行失败")
+                }
+            }
+        }
+    });
+}
@@ -8,14 +8,22 @@ use tracing_subscriber::{prelude::*, EnvFilter};
 // Modules
 mod antigravity;
 mod app_settings;
+mod cli;
 mod config_manager;
 mod constants;
 mod directories;
+mod error;
+mod events;
+mod headless;
+mod host;
+mod instance;
+mod path_policy;
 mod platform;
 mod proto;
 mod server; // New module
 mod services; // Service layer
 mod system_tray;
+mod telemetry;
 mod utils;
 mod window;
 
@@ -25,7 +33,7 @@ mod setup;
 mod state;
 
 // Re-export AppState for compatibility with other modules
-pub use state::{AntigravityAccount, AppState, ProfileInfo};
+pub use state::{AppState, ProfileInfo};
 
 /// 初始化双层日志系统（控制台 + 文件）
 fn init_tracing() -> WorkerGuard {
@@ -68,12 +76,24 @@ fn init_tracing() -> WorkerGuard {
                 .with_ansi(false) // 文件不使用颜色代码
                 .compact(), // 使用紧凑格式而非 JSON，便于脱敏处理
         )
+        .with(crate::utils::event_log_layer::EventLogLayer) // 转发到事件总线（logs 主题）
         .init();
 
     guard // 返回 guard 以防止日志缓冲区被过早清理
 }
 
 fn main() {
+    // 子命令（accounts list、switch 等）直接执行后退出，不启动应用
+    if let Some(code) = cli::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // 已有实例在运行时转发启动参数（--switch、--show、antigravity-agent:// 链接）后退出
+    let launch_actions = match instance::coordinate() {
+        instance::Launch::Exit(code) => std::process::exit(code),
+        instance::Launch::Start(actions) => actions,
+    };
+
     // 初始化双层日志系统（控制台 + 文件）
     let _guard = init_tracing();
 
@@ -93,6 +113,15 @@ fn main() {
     // 初始化 AppState (内部已包含 Arc<Mutex>)
     let app_state = AppState::default();
 
+    // 加载（或首次生成）本地 API Token，HTTP Server 与前端共用同一份
+    let api_tokens = std::sync::Arc::new(
+        server::auth::load_or_create_tokens().expect("无法初始化本地 API Token"),
+    );
+
+    if headless::is_requested() {
+        headless::run(app_state, api_tokens, launch_actions);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_shell::init())
@@ -103,17 +132,42 @@ fn main() {
         .plugin(tauri_plugin_http::init())
         // 注意：这里我们使用 external state management，以便让 HTTP Server 共享同一个 State
         .manage(app_state.clone())
+        .manage(api_tokens.clone())
         .setup(move |app| {
             setup::init(app)?;
 
             // 启动 HTTP Server
             // 传递相同的 app_state 实例给 server
-            let handle = app.handle().clone();
-            server::init(handle, app_state.clone());
+            let host = host::AppHost::gui(app.handle());
+            server::init(host.clone(), app_state.clone(), api_tokens.clone());
+
+            // 首次启动时携带的 --switch / --show / 链接
+            instance::perform(host, launch_actions);
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![
+            server::auth::get_api_token,
+            server::listen::get_api_base_url,
+            path_policy::allow_export_path
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, _event| {
+            // macOS 通过 Apple Event 送达 antigravity-agent:// 链接
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                let actions = urls
+                    .iter()
+                    .filter_map(|url| match instance::LaunchAction::parse_url(url.as_str()) {
+                        Ok(action) => Some(action),
+                        Err(e) => {
+                            tracing::warn!(target: "app::instance", url = %url, error = %e, "忽略无效链接");
+                            None
+                        }
+                    })
+                    .collect();
+                instance::perform(host::AppHost::gui(_app), actions);
+            }
+        });
 }
@@ -0,0 +1,227 @@
+//! # 路径策略
+//!
+//! 本地 API 可触发的文件读写统一经过本模块，只允许落在 Agent 自己的目录中：
+//!
+//! - 账户备份目录 `~/.antigravity-agent/antigravity-accounts`（只通过账户名、导入文件名定位）
+//! - 用户通过 GUI 保存对话框选择的导出文件（只允许写入该文件一次）
+//!
+//! 配置目录根部的文件（`api_tokens.json`、`app_settings.json` 等）只由各自的模块读写，
+//! 不对 API 开放。
+//!
+//! ## 背景
+//!
+//! `write_text_file` 曾可写入任意绝对路径；删除、恢复备份时直接用 `format!("{name}.json")` 拼接路径；
+//! 导入包中的 `filename` 原样拼接到账户目录。持有 Token 的客户端或恶意导入包都可以借 `../` 越出目录。
+//!
+//! ## 规则
+//!
+//! - 账户名、导入文件名必须是单段文件名（不含路径分隔符、`..`、控制字符）
+//! - 路径先规范化（解析符号链接）再判断是否位于允许的目录内
+//! - 违反策略时返回 [`AgentError::PathNotAllowed`]（错误码 `path_not_allowed`）
+//!
+//! 导出文件只能通过 Tauri IPC 命令 [`allow_export_path`] 登记，HTTP 客户端无法自行扩大范围。
+//! 登记的是确切的文件路径而不是所在目录，否则导出一次到 `~/backup.json` 后，
+//! 持有 Token 的客户端就能写入 `~/.bashrc` 等同目录文件。
+
+use crate::error::AgentError;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+
+lazy_static::lazy_static! {
+    /// 用户在保存对话框中选择、尚未写入的导出文件（所在目录已规范化）
+    static ref EXPORT_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+fn not_allowed(message: impl Into<String>) -> AgentError {
+    AgentError::PathNotAllowed(message.into())
+}
+
+/// 校验单段文件名（账户名、导入包中的文件名）
+fn validate_file_name(name: &str) -> Result<(), AgentError> {
+    let invalid = name.trim().is_empty()
+        || name == "."
+        || name == ".."
+        || name
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '/' | '\\' | ':'));
+    if invalid {
+        return Err(not_allowed(format!("Invalid file name: {name:?}")));
+    }
+    Ok(())
+}
+
+/// 账户备份文件：`<accounts_dir>/<name>.json`
+pub fn account_file(accounts_dir: &Path, name: &str) -> Result<PathBuf, AgentError> {
+    validate_file_name(name)?;
+    ensure_within(&accounts_dir.join(format!("{name}.json")), &[accounts_dir])
+}
+
+/// 账户目录下子目录中的账户文件：`<accounts_dir>/<subdir>/<name>.json`
+///
+/// 以已存在的账户目录为边界校验，子目录尚未创建时同样可用。
+pub fn nested_account_file(
+    accounts_dir: &Path,
+    subdir: &str,
+    name: &str,
+) -> Result<PathBuf, AgentError> {
+    validate_file_name(subdir)?;
+    validate_file_name(name)?;
+    ensure_within(
+        &accounts_dir.join(subdir).join(format!("{name}.json")),
+        &[accounts_dir],
+    )
+}
+
+/// 导入包中的备份文件：文件名必须是 `<name>.json`
+pub fn import_file(accounts_dir: &Path, filename: &str) -> Result<PathBuf, AgentError> {
+    validate_file_name(filename)?;
+    if !filename.ends_with(".json") {
+        return Err(not_allowed(format!(
+            "Imported file must be a .json file: {filename:?}"
+        )));
+    }
+    ensure_within(&accounts_dir.join(filename), &[accounts_dir])
+}
+
+/// 取用一次导出授权：`path` 必须是用户通过 [`allow_export_path`] 登记的文件，取用后授权失效
+pub fn take_export_file(path: impl AsRef<Path>) -> Result<PathBuf, AgentError> {
+    let path = path.as_ref();
+    if !path.is_absolute() {
+        return Err(not_allowed(format!(
+            "Path must be absolute: {}",
+            path.display()
+        )));
+    }
+
+    let resolved = canonicalize_lenient(path)
+        .ok_or_else(|| not_allowed(format!("Invalid path: {}", path.display())))?;
+    let mut files = EXPORT_FILES.lock();
+    let Some(index) = files.iter().position(|file| file == &resolved) else {
+        tracing::warn!(
+            target: "path_policy",
+            path = %path.display(),
+            "拒绝写入未登记的导出文件"
+        );
+        return Err(not_allowed(format!(
+            "Path was not chosen as an export file: {}",
+            path.display()
+        )));
+    };
+    Ok(files.swap_remove(index))
+}
+
+/// 规范化 `path` 并检查是否位于 `roots` 之一
+///
+/// 目标文件可能尚不存在：规范化最深的已存在祖先目录，再拼接剩余部分（剩余部分不允许出现 `..`）。
+fn ensure_within(path: &Path, roots: &[&Path]) -> Result<PathBuf, AgentError> {
+    let resolved = canonicalize_lenient(path)
+        .ok_or_else(|| not_allowed(format!("Invalid path: {}", path.display())))?;
+
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(&root) && resolved != root);
+    if !allowed {
+        tracing::warn!(
+            target: "path_policy",
+            path = %path.display(),
+            "拒绝访问允许目录之外的路径"
+        );
+        return Err(not_allowed(format!(
+            "Path is outside the directories Antigravity Agent may access: {}",
+            path.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+fn canonicalize_lenient(path: &Path) -> Option<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    // 悬空的符号链接也视为已存在，规范化失败即拒绝，避免写入时跟随链接越界
+    while existing.symlink_metadata().is_err() {
+        // `file_name` 对以 `..` 结尾的路径返回 None
+        rest.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+
+    let mut resolved = existing.canonicalize().ok()?;
+    resolved.extend(rest.iter().rev());
+    Some(resolved)
+}
+
+/// 登记用户选择的导出文件，允许通过 [`take_export_file`] 写入一次
+pub fn allow_export_file(file: &Path) -> Result<PathBuf, AgentError> {
+    let invalid = || not_allowed(format!("Invalid export path: {}", file.display()));
+    let name = file.file_name().ok_or_else(invalid)?;
+    validate_file_name(&name.to_string_lossy())?;
+    let dir = file
+        .parent()
+        .filter(|dir| dir.is_absolute())
+        .ok_or_else(invalid)?
+        .canonicalize()
+        .map_err(|e| AgentError::from_io(e, "Failed to resolve export directory"))?;
+    let file = dir.join(name);
+
+    let mut files = EXPORT_FILES.lock();
+    if !files.contains(&file) {
+        tracing::info!(target: "path_policy", file = %file.display(), "登记导出文件");
+        files.push(file.clone());
+    }
+    Ok(file)
+}
+
+/// 前端在保存对话框返回后调用，登记用户选择的导出文件
+///
+/// 仅通过 Tauri IPC 暴露（不在 HTTP API 中），其他本地客户端无法自行扩大可写范围。
+#[tauri::command]
+pub fn allow_export_path(path: String) -> Result<(), String> {
+    allow_export_file(Path::new(&path))
+        .map(|_| ())
+        .map_err(|e| e.message().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agent-path-policy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn export_grant_covers_only_the_chosen_file_once() {
+        let dir = temp_dir();
+        let chosen = dir.join("backup.enc");
+        allow_export_file(&chosen).unwrap();
+
+        assert!(take_export_file(dir.join(".bashrc")).is_err());
+        assert!(take_export_file(dir.join("nested").join("backup.enc")).is_err());
+        assert_eq!(take_export_file(&chosen).unwrap(), chosen);
+        assert!(take_export_file(&chosen).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn export_grant_rejects_traversal() {
+        let dir = temp_dir();
+        allow_export_file(&dir.join("backup.enc")).unwrap();
+
+        assert!(take_export_file(dir.join("sub").join("..").join("backup.enc")).is_err());
+        assert!(take_export_file("relative/backup.enc").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn nested_account_file_works_before_subdirectory_exists() {
+        let dir = temp_dir();
+
+        let path = nested_account_file(&dir, "metadata", "user@example.com").unwrap();
+        assert_eq!(path, dir.join("metadata").join("user@example.com.json"));
+        assert!(nested_account_file(&dir, "metadata", "../escape").is_err());
+        assert!(account_file(&dir, "..").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
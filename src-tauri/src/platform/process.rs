@@ -1,5 +1,9 @@
+use crate::error::AgentError;
+
 /// 关闭Antigravity进程 - 使用sysinfo库实现跨平台统一处理
-pub fn kill_antigravity_processes() -> Result<String, String> {
+///
+/// 未找到匹配进程时返回 [`AgentError::NotFound`]。
+pub fn kill_antigravity_processes() -> Result<String, AgentError> {
     tracing::info!("🔍 开始搜索并关闭 Antigravity 进程");
 
     // 使用sysinfo库获取所有进程
@@ -41,7 +45,9 @@ pub fn kill_antigravity_processes() -> Result<String, String> {
     if killed_processes.is_empty() {
         tracing::info!("ℹ️ 未找到匹配的 Antigravity 进程");
         tracing::info!("🔍 搜索的进程模式: {:?}", process_patterns);
-        Err("未找到Antigravity进程".to_string())
+        Err(AgentError::NotFound(
+            "Antigravity process not found".to_string(),
+        ))
     } else {
         let success_msg = format!("已成功关闭Antigravity进程: {}", killed_processes.join(", "));
         tracing::info!("🎉 {}", success_msg);
@@ -62,7 +68,9 @@ pub fn is_antigravity_running() -> bool {
         let process_name = process.name();
         let process_cmd = process.cmd().join(" ");
 
-        if matches_antigravity_process(process_name, &process_cmd, &process_patterns) {
+        if is_alive(process)
+            && matches_antigravity_process(process_name, &process_cmd, &process_patterns)
+        {
             tracing::debug!(
                 "✅ 发现运行中的 Antigravity 进程: {} (PID: {})",
                 process_name,
@@ -76,6 +84,36 @@ pub fn is_antigravity_running() -> bool {
     false
 }
 
+/// 列出仍在运行的 Antigravity 进程（`名称 (PID: n)`）
+///
+/// 只刷新进程列表，开销比 [`is_antigravity_running`] 小，适合在关闭进程后轮询。
+pub fn list_antigravity_processes() -> Vec<String> {
+    let mut system = sysinfo::System::new();
+    system.refresh_processes();
+
+    let process_patterns = get_antigravity_process_patterns();
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            is_alive(process)
+                && matches_antigravity_process(
+                    process.name(),
+                    &process.cmd().join(" "),
+                    &process_patterns,
+                )
+        })
+        .map(|(pid, process)| format!("{} (PID: {})", process.name(), pid))
+        .collect()
+}
+
+/// 进程是否仍在运行
+///
+/// Agent 启动的 Antigravity 被关闭后，在父进程回收前会以僵尸进程的形式留在进程表中，不算运行。
+fn is_alive(process: &sysinfo::Process) -> bool {
+    process.status() != sysinfo::ProcessStatus::Zombie
+}
+
 /// 获取 Antigravity 进程匹配模式
 fn get_antigravity_process_patterns() -> Vec<ProcessPattern> {
     match std::env::consts::OS {
@@ -122,14 +160,14 @@ fn matches_antigravity_process(
             ProcessPattern::ExactName(name) => {
                 if process_name == *name {
                     tracing::debug!("✅ 精确匹配进程名: {}", name);
-                    tracing::info!("🎯 匹配模式: ProcessPattern::ExactName(\"{}\")", name);
+                    tracing::debug!("🎯 匹配模式: ProcessPattern::ExactName(\"{}\")", name);
                     matched = true;
                 }
             }
             ProcessPattern::CmdContains(text) => {
                 if process_cmd.contains(text) {
                     tracing::debug!("✅ 命令行包含匹配: {}", text);
-                    tracing::info!("🎯 匹配模式: ProcessPattern::CmdContains(\"{}\")", text);
+                    tracing::debug!("🎯 匹配模式: ProcessPattern::CmdContains(\"{}\")", text);
                     matched = true;
                 }
             }
@@ -144,3 +182,31 @@ pub enum ProcessPattern {
     ExactName(&'static str),   // 精确匹配进程名
     CmdContains(&'static str), // 命令行包含指定文本
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 已退出但未被回收的子进程不算运行
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn zombie_processes_are_not_alive() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = sysinfo::Pid::from_u32(child.id());
+
+        let mut system = sysinfo::System::new();
+        let mut status = None;
+        for _ in 0..50 {
+            system.refresh_processes();
+            status = system.process(pid).map(|process| process.status());
+            if status == Some(sysinfo::ProcessStatus::Zombie) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(status, Some(sysinfo::ProcessStatus::Zombie));
+        assert!(!is_alive(system.process(pid).unwrap()));
+
+        child.wait().unwrap();
+    }
+}
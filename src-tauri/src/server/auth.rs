@@ -0,0 +1,444 @@
+//! # 本地 API 鉴权模块
+//!
+//! 为 `/api/*`、`/ws` 与 `/metrics` 提供基于 Bearer Token 的访问控制，以及 Origin 白名单。
+//!
+//! ## 背景
+//!
+//! HTTP 服务监听在 `127.0.0.1:56789`，浏览器中任意网页都可以向其发起请求。
+//! 如果不做鉴权，恶意网页即可清空账户数据或读取 refresh token。
+//!
+//! ## 设计
+//!
+//! - 每次安装生成一份随机 Token，保存在配置目录的 `api_tokens.json`（Unix 下权限 0600）
+//! - Token 分为两种作用域：
+//!   - `read`：只读接口（状态栏等只需要读取数据的客户端使用）
+//!   - `admin`：全部接口（Tauri 前端、扩展面板使用）
+//! - 请求必须携带 `Authorization: Bearer <token>`
+//! - `/ws` 与 `/api/events` 额外支持 `?access_token=<token>`，因为浏览器的 WebSocket / EventSource API 无法自定义请求头
+//! - 带 `Origin` 头的请求必须来自白名单（Tauri WebView、VS Code WebView、本地开发服务器）
+//! - 远程监听器（TLS）只接受已配对设备的 Token，见 [`crate::services::remote`]
+
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, Method},
+    Error, HttpMessage, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AgentError;
+
+// =============================================================================
+// 作用域与 Token
+// =============================================================================
+
+/// API 访问作用域
+///
+/// `Admin` 包含 `Read` 的全部权限。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    /// 只读：查询账户、配额、设置等
+    Read,
+    /// 管理：切换账户、删除备份、写文件等会修改状态的操作
+    Admin,
+}
+
+impl ApiScope {
+    /// 当前作用域是否满足 `required`
+    pub fn allows(self, required: ApiScope) -> bool {
+        self >= required
+    }
+}
+
+/// 本地 API Token
+///
+/// 持久化在 `~/.antigravity-agent/api_tokens.json`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokens {
+    /// 管理 Token（全部接口）
+    pub admin: String,
+    /// 只读 Token（仅只读接口）
+    pub read_only: String,
+}
+
+impl ApiTokens {
+    fn generate() -> Self {
+        Self {
+            admin: generate_token(),
+            read_only: generate_token(),
+        }
+    }
+
+    /// 根据 Token 解析作用域
+    pub fn scope_of(&self, token: &str) -> Option<ApiScope> {
+        if constant_time_eq(token.as_bytes(), self.admin.as_bytes()) {
+            Some(ApiScope::Admin)
+        } else if constant_time_eq(token.as_bytes(), self.read_only.as_bytes()) {
+            Some(ApiScope::Read)
+        } else {
+            None
+        }
+    }
+}
+
+/// 生成 256 bit 随机 Token（两个 UUID v4 拼接）
+pub(crate) fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// 常量时间比较，避免通过响应时间推测 Token
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// 只读取 Token 文件，不存在或损坏时返回错误
+///
+/// 供 CLI 等客户端使用：客户端不能重新生成 Token，否则运行中的实例仍使用内存中的旧 Token，
+/// 前端与扩展下次重新加载时也会失去授权。
+pub fn load_tokens() -> Result<ApiTokens, String> {
+    let path = crate::directories::get_api_tokens_file();
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read API token file ({}): {e}", path.display()))?;
+    match serde_json::from_str::<ApiTokens>(&content) {
+        Ok(tokens) if !tokens.admin.is_empty() && !tokens.read_only.is_empty() => Ok(tokens),
+        _ => Err(format!("API token file is invalid ({})", path.display())),
+    }
+}
+
+/// 读取 Token 文件，不存在或损坏时重新生成
+pub fn load_or_create_tokens() -> Result<ApiTokens, String> {
+    let path = crate::directories::get_api_tokens_file();
+
+    match load_tokens() {
+        Ok(tokens) => return Ok(tokens),
+        Err(_) if path.exists() => {
+            tracing::warn!(target: "server::auth", "API Token 文件无效，重新生成");
+        }
+        Err(_) => {}
+    }
+
+    let tokens = ApiTokens::generate();
+    let json = serde_json::to_string_pretty(&tokens)
+        .map_err(|e| format!("Failed to serialize API tokens: {e}"))?;
+    crate::utils::private_file::write(&path, json)
+        .map_err(|e| format!("Failed to write API token file ({}): {e}", path.display()))?;
+
+    tracing::info!(target: "server::auth", "已生成新的 API Token");
+    Ok(tokens)
+}
+
+// =============================================================================
+// Origin 白名单
+// =============================================================================
+
+/// 允许的精确 Origin
+///
+/// - `tauri://localhost`: macOS / Linux 上的 Tauri WebView
+/// - `http(s)://tauri.localhost`: Windows 上的 Tauri WebView
+/// - `http://localhost:1420`: `npm run dev` 开发服务器
+const ALLOWED_ORIGINS: [&str; 5] = [
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+    "http://127.0.0.1:1420",
+];
+
+/// 允许的 Origin 前缀（VS Code WebView 的 Origin 带随机 ID）
+const ALLOWED_ORIGIN_PREFIXES: [&str; 1] = ["vscode-webview://"];
+
+/// 检查 Origin 是否在白名单中
+pub fn is_allowed_origin(origin: &str) -> bool {
+    ALLOWED_ORIGINS.contains(&origin)
+        || ALLOWED_ORIGIN_PREFIXES
+            .iter()
+            .any(|prefix| origin.starts_with(prefix))
+}
+
+/// 构造 CORS 配置（仅放行白名单 Origin）
+pub fn cors() -> actix_cors::Cors {
+    actix_cors::Cors::default()
+        .allowed_origin_fn(|origin, _req_head| {
+            origin.to_str().map(is_allowed_origin).unwrap_or(false)
+        })
+        .allowed_methods(vec!["GET", "POST", "OPTIONS"])
+        .allowed_headers(vec![
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::HeaderName::from_static(super::middleware::REQUEST_ID_HEADER),
+        ])
+        .expose_headers(vec![header::HeaderName::from_static(
+            super::middleware::REQUEST_ID_HEADER,
+        )])
+        .max_age(3600)
+}
+
+// =============================================================================
+// 路由作用域
+// =============================================================================
+
+/// 以 POST 暴露但不修改任何状态的接口，只读 Token 即可访问
+const READ_ONLY_POST_ROUTES: [&str; 1] = ["/api/get_account_metrics"];
+
+/// 以 GET 暴露但返回原始令牌的接口（导出备份），需要管理 Token
+const ADMIN_GET_ROUTES: [&str; 1] = ["/api/collect_account_contents"];
+
+/// 远程设备配对接口：只在远程监听器上提供，凭配对码访问，无需 Token
+const REMOTE_PAIR_ROUTE: &str = "/api/pair_remote_device";
+
+/// 远程监听器上允许访问的路由（账户与配额），其余路由一律拒绝
+///
+/// 设置、可执行文件路径、扩展安装、文件写入、窗口与托盘等接口可以在本机执行任意程序或写文件，
+/// 只在本地监听器上提供；远程访问本身也只能在本机管理。
+/// `/ws` 与 MCP 只暴露账户与配额操作，并在消息层按作用域校验。
+const REMOTE_ALLOWED_ROUTES: [&str; 11] = [
+    "/api/is_antigravity_running",
+    "/api/get_antigravity_accounts",
+    "/api/get_current_antigravity_account_info",
+    "/api/save_antigravity_current_account",
+    "/api/switch_to_antigravity_account",
+    "/api/get_account_metrics",
+    "/api/trigger_quota_refresh",
+    "/api/events",
+    MCP_ROUTE,
+    "/ws",
+    REMOTE_PAIR_ROUTE,
+];
+
+/// MCP 端点，只读 Token 可以调用只读工具
+const MCP_ROUTE: &str = "/api/mcp";
+
+/// 允许通过 `access_token` 查询参数传递 Token 的路由
+const QUERY_TOKEN_ROUTES: [&str; 2] = ["/ws", "/api/events"];
+
+/// 计算请求所需作用域，返回 `None` 表示无需鉴权
+fn required_scope(method: &Method, path: &str) -> Option<ApiScope> {
+    if path == "/ws" {
+        // 连接本身只需要只读权限，具体操作在消息层再做校验
+        return Some(ApiScope::Read);
+    }
+
+    if path == MCP_ROUTE {
+        // 同 /ws：工具调用时按工具再校验作用域
+        return Some(ApiScope::Read);
+    }
+
+    if path == "/metrics" {
+        // Prometheus 抓取配置可使用只读 Token（bearer_token_file）
+        return Some(ApiScope::Read);
+    }
+
+    if !path.starts_with("/api/") || path == REMOTE_PAIR_ROUTE {
+        return None;
+    }
+
+    if ADMIN_GET_ROUTES.contains(&path) {
+        return Some(ApiScope::Admin);
+    }
+
+    if method == Method::GET || READ_ONLY_POST_ROUTES.contains(&path) {
+        Some(ApiScope::Read)
+    } else {
+        Some(ApiScope::Admin)
+    }
+}
+
+/// 从请求中提取 Token
+///
+/// 优先 `Authorization: Bearer`，[`QUERY_TOKEN_ROUTES`] 额外支持 `access_token` 查询参数。
+fn extract_token(req: &ServiceRequest) -> Option<String> {
+    let from_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+
+    if from_header.is_some() || !QUERY_TOKEN_ROUTES.contains(&req.path()) {
+        return from_header;
+    }
+
+    req.query_string()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "access_token")
+        .map(|(_, value)| value.to_string())
+}
+
+/// 请求是否来自远程监听器
+///
+/// 远程监听器是唯一的 TLS 监听器，本地 TCP 与 Unix socket 均为明文。
+fn is_remote(req: &ServiceRequest) -> bool {
+    req.request().app_config().secure()
+}
+
+/// 检查路由在当前监听器上是否可用
+fn check_listener(req: &ServiceRequest, remote: bool) -> Result<(), AgentError> {
+    let path = req.path();
+    if remote && !REMOTE_ALLOWED_ROUTES.contains(&path) {
+        return Err(AgentError::Forbidden(
+            "This route is only available on the agent machine".to_string(),
+        ));
+    }
+    if !remote && path == REMOTE_PAIR_ROUTE {
+        return Err(AgentError::Forbidden(
+            "Pairing is only available on the remote listener".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 经设备 Token 鉴权的远程请求，中间件将设备 ID 写入请求扩展
+#[derive(Debug, Clone)]
+pub struct RemoteDeviceId(pub String);
+
+// =============================================================================
+// 中间件
+// =============================================================================
+
+/// 鉴权中间件工厂
+///
+/// 校验通过后会把 [`ApiScope`] 写入请求扩展，供后续处理器读取。
+pub struct ApiAuth {
+    tokens: Arc<ApiTokens>,
+}
+
+impl ApiAuth {
+    pub fn new(tokens: Arc<ApiTokens>) -> Self {
+        Self { tokens }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ApiAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiAuthMiddleware {
+            service: Rc::new(service),
+            tokens: self.tokens.clone(),
+        }))
+    }
+}
+
+pub struct ApiAuthMiddleware<S> {
+    service: Rc<S>,
+    tokens: Arc<ApiTokens>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = self.service.clone();
+        let tokens = self.tokens.clone();
+
+        Box::pin(async move {
+            let remote = is_remote(&req);
+            if let Err(e) = check_listener(&req, remote) {
+                tracing::warn!(target: "server::auth", path = %req.path(), remote, "路由在当前监听器上不可用");
+                return Ok(req.into_response(e.error_response()).map_into_right_body());
+            }
+
+            let Some(required) = required_scope(req.method(), req.path()) else {
+                return svc.call(req).await.map(|res| res.map_into_left_body());
+            };
+
+            // 1. Origin 白名单（非浏览器客户端不带 Origin，直接进入 Token 校验）
+            if let Some(origin) = req.headers().get(header::ORIGIN) {
+                let origin = origin.to_str().unwrap_or("");
+                if !is_allowed_origin(origin) {
+                    tracing::warn!(target: "server::auth", origin = %origin, path = %req.path(), "拒绝非白名单 Origin");
+                    let response =
+                        AgentError::Forbidden("Origin not allowed".to_string()).error_response();
+                    return Ok(req.into_response(response).map_into_right_body());
+                }
+            }
+
+            // 2. Token 与作用域：远程监听器只接受设备 Token，本地监听器只接受本地 Token
+            let credentials = extract_token(&req).and_then(|token| {
+                if remote {
+                    crate::services::remote::authenticate(&token)
+                        .map(|(device_id, scope)| (scope, Some(RemoteDeviceId(device_id))))
+                } else {
+                    tokens.scope_of(&token).map(|scope| (scope, None))
+                }
+            });
+            match credentials {
+                Some((scope, device)) if scope.allows(required) => {
+                    req.extensions_mut().insert(scope);
+                    if let Some(device) = device {
+                        req.extensions_mut().insert(device);
+                    }
+                    svc.call(req).await.map(|res| res.map_into_left_body())
+                }
+                Some(_) => {
+                    tracing::warn!(target: "server::auth", path = %req.path(), "Token 作用域不足");
+                    let response = AgentError::Forbidden(
+                        "Token scope does not allow this operation".to_string(),
+                    )
+                    .error_response();
+                    Ok(req.into_response(response).map_into_right_body())
+                }
+                None => {
+                    tracing::warn!(target: "server::auth", path = %req.path(), "缺少或无效的 API Token");
+                    let mut response =
+                        AgentError::Unauthorized("Missing or invalid API token".to_string())
+                            .error_response();
+                    response.headers_mut().insert(
+                        header::WWW_AUTHENTICATE,
+                        header::HeaderValue::from_static("Bearer"),
+                    );
+                    Ok(req.into_response(response).map_into_right_body())
+                }
+            }
+        })
+    }
+}
+
+// =============================================================================
+// Tauri 命令
+// =============================================================================
+
+/// 供 Tauri 前端获取管理 Token
+///
+/// 前端通过 IPC 获取 Token 后再访问 HTTP API，Token 不会暴露给普通网页。
+#[tauri::command]
+pub fn get_api_token(tokens: tauri::State<'_, Arc<ApiTokens>>) -> String {
+    tokens.admin.clone()
+}
@@ -0,0 +1,217 @@
+//! # D-Bus 服务（仅 Linux）
+//!
+//! 在会话总线上发布 `org.antigravity.Agent`，桌面快捷键（GNOME / KDE）和面板脚本无需 HTTP 与 Token
+//! 即可切换账户。会话总线只对当前用户开放，访问范围与 `agent.sock` 相同。
+//!
+//! - 对象路径：`/org/antigravity/Agent`
+//! - 接口：`org.antigravity.Agent`
+//!
+//! 方法与 HTTP API 调用同一组 `services::account` 函数，返回的邮箱同样按隐私模式脱敏；
+//! 账户参数接受邮箱或 `account_id`。失败时返回 `org.antigravity.Agent.Error.<Code>` 错误。
+//!
+//! 信号由事件总线转发：账户变化（`AccountChanged`）与 Antigravity 进程启停（`ProcessStateChanged`）。
+//!
+//! 会话总线不可用（如无图形会话的服务器）或名称已被占用时只记录日志，不影响其他功能。
+
+use crate::error::AgentError;
+use crate::events::AgentEvent;
+use crate::host::AppHost;
+use crate::services::account::{self, Redaction};
+use tokio::sync::broadcast::error::RecvError;
+use zbus::object_server::SignalEmitter;
+
+/// 会话总线上的服务名
+pub const BUS_NAME: &str = "org.antigravity.Agent";
+
+/// 对象路径
+pub const OBJECT_PATH: &str = "/org/antigravity/Agent";
+
+/// D-Bus 错误，名称为 `org.antigravity.Agent.Error.<变体名>`，与 [`AgentError`] 一一对应
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.antigravity.Agent.Error")]
+enum DbusError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NotFound(String),
+    InvalidInput(String),
+    AntigravityRunning(String),
+    DbLocked(String),
+    Unauthorized(String),
+    Forbidden(String),
+    PathNotAllowed(String),
+    Upstream(String),
+    Timeout(String),
+    Unavailable(String),
+    Internal(String),
+}
+
+impl From<AgentError> for DbusError {
+    fn from(error: AgentError) -> Self {
+        match error {
+            AgentError::NotFound(msg) => DbusError::NotFound(msg),
+            AgentError::InvalidInput(msg) => DbusError::InvalidInput(msg),
+            AgentError::AntigravityRunning(msg) => DbusError::AntigravityRunning(msg),
+            AgentError::DbLocked(msg) => DbusError::DbLocked(msg),
+            AgentError::Unauthorized(msg) => DbusError::Unauthorized(msg),
+            AgentError::Forbidden(msg) => DbusError::Forbidden(msg),
+            AgentError::PathNotAllowed(msg) => DbusError::PathNotAllowed(msg),
+            AgentError::Upstream(msg) => DbusError::Upstream(msg),
+            AgentError::Timeout(msg) => DbusError::Timeout(msg),
+            AgentError::Unavailable(msg) => DbusError::Unavailable(msg),
+            AgentError::Internal(msg) => DbusError::Internal(msg),
+        }
+    }
+}
+
+/// 账户操作返回 `ok: false` 时同样作为错误返回，脚本只需检查调用是否成功
+fn command_message(result: account::CommandResult) -> Result<String, DbusError> {
+    if result.ok {
+        Ok(result.message)
+    } else {
+        Err(DbusError::Internal(format!(
+            "{}: {}",
+            result.code, result.message
+        )))
+    }
+}
+
+fn email_of(account: &account::AntigravityAccountResponse) -> String {
+    account
+        .antigravity_auth_status
+        .get("email")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+struct AgentInterface {
+    host: AppHost,
+}
+
+impl AgentInterface {
+    fn redaction(&self) -> Redaction {
+        Redaction::for_host(&self.host)
+    }
+}
+
+#[zbus::interface(name = "org.antigravity.Agent")]
+impl AgentInterface {
+    /// 已备份的账户：`(account_id, email)` 数组
+    async fn list_accounts(&self) -> Result<Vec<(String, String)>, DbusError> {
+        let config_dir = crate::directories::get_config_directory();
+        let accounts = self
+            .redaction()
+            .accounts(account::get_all(&config_dir).await?);
+        Ok(accounts
+            .iter()
+            .map(|account| (account.account_id.clone(), email_of(account)))
+            .collect())
+    }
+
+    /// 当前登录的账户：`(account_id, email)`，未登录时为两个空字符串
+    async fn current_account(&self) -> Result<(String, String), DbusError> {
+        let account = self.redaction().account(account::get_current().await?);
+        Ok((account.account_id.clone(), email_of(&account)))
+    }
+
+    /// 切换到指定账户（邮箱或 `account_id`），返回结果说明
+    async fn switch(&self, account: String) -> Result<String, DbusError> {
+        command_message(account::switch(account).await?)
+    }
+
+    /// 备份当前账户，返回结果说明
+    async fn backup_current(&self) -> Result<String, DbusError> {
+        command_message(account::backup_current().await?)
+    }
+
+    /// 账户配额，JSON 格式与 `POST /api/get_account_metrics` 的响应相同
+    async fn get_metrics(&self, account: String) -> Result<String, DbusError> {
+        let config_dir = crate::directories::get_config_directory();
+        let metrics = self
+            .redaction()
+            .metrics(account::get_metrics(&config_dir, account).await?);
+        serde_json::to_string(&metrics)
+            .map_err(|e| DbusError::Internal(format!("Failed to serialize metrics: {e}")))
+    }
+
+    /// Antigravity 中登录的账户发生变化（未登录时两个参数均为空字符串）
+    #[zbus(signal)]
+    async fn account_changed(
+        emitter: &SignalEmitter<'_>,
+        account_id: &str,
+        email: &str,
+    ) -> zbus::Result<()>;
+
+    /// Antigravity 进程启动（`true`）或退出（`false`）
+    #[zbus(signal)]
+    async fn process_state_changed(emitter: &SignalEmitter<'_>, running: bool) -> zbus::Result<()>;
+}
+
+/// 在会话总线上发布服务，并把事件总线上的变化转发为信号
+pub fn spawn(host: AppHost) {
+    actix_web::rt::spawn(async move {
+        let redaction_host = host.clone();
+        let connection = match zbus::connection::Builder::session()
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, AgentInterface { host }))
+        {
+            Ok(builder) => builder.build().await,
+            Err(e) => Err(e),
+        };
+        let connection = match connection {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!(target: "server::dbus", error = %e, "D-Bus 服务不可用");
+                return;
+            }
+        };
+        tracing::info!(target: "server::dbus", name = BUS_NAME, "D-Bus 服务已发布");
+
+        let emitter = match SignalEmitter::new(&connection, OBJECT_PATH) {
+            Ok(emitter) => emitter,
+            Err(e) => {
+                tracing::warn!(target: "server::dbus", error = %e, "无法创建 D-Bus 信号发送器");
+                return;
+            }
+        };
+        forward_signals(&emitter, &redaction_host).await;
+    });
+}
+
+/// 将账户与进程事件转发为 D-Bus 信号
+async fn forward_signals(emitter: &SignalEmitter<'_>, host: &AppHost) {
+    let mut receiver = crate::events::subscribe();
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(target: "server::dbus", skipped, "D-Bus 信号转发过慢，已丢弃事件");
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let sent = match event {
+            AgentEvent::AccountChanged { email } => {
+                let email = email.unwrap_or_default();
+                let account_id = if email.is_empty() {
+                    String::new()
+                } else {
+                    account::account_id(&email)
+                };
+                let email = Redaction::for_host(host).email(&email);
+                AgentInterface::account_changed(emitter, &account_id, &email).await
+            }
+            AgentEvent::ProcessStarted => {
+                AgentInterface::process_state_changed(emitter, true).await
+            }
+            AgentEvent::ProcessStopped => {
+                AgentInterface::process_state_changed(emitter, false).await
+            }
+            _ => continue,
+        };
+        if let Err(e) = sent {
+            tracing::debug!(target: "server::dbus", error = %e, "发送 D-Bus 信号失败");
+        }
+    }
+}
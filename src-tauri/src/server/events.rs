@@ -0,0 +1,147 @@
+//! # SSE 事件流
+//!
+//! `GET /api/events` 以 Server-Sent Events 格式推送 [`AgentEvent`]，
+//! 任何 HTTP 客户端（`curl -N`、浏览器 `EventSource`、脚本）都可以订阅，
+//! 无需实现 `/ws` 的自定义协议。
+//!
+//! ## 格式
+//!
+//! ```text
+//! event: account_changed
+//! data: {"type":"account_changed","email":"user@example.com"}
+//!
+//! ```
+//!
+//! 空闲时定期发送 `: keep-alive` 注释行，防止代理或客户端超时断开。
+//!
+//! 可通过 `?topics=accounts,process` 只订阅部分主题；默认订阅除 `logs` 外的全部主题。
+
+use super::auth::RemoteDeviceId;
+use crate::error::AgentError;
+use crate::events::{AgentEvent, Topic};
+use crate::host::AppHost;
+use crate::services::account::Redaction;
+use actix_web::http::header;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// 空闲保活间隔
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 客户端断线后的建议重连间隔（毫秒）
+const RETRY_MILLIS: u64 = 3000;
+
+/// 将事件编码为一条 SSE 消息（按隐私模式脱敏）
+fn format_event(event: &AgentEvent, redaction: Redaction) -> String {
+    let data = redaction.event(event).to_string();
+    format!("event: {}\ndata: {}\n\n", event.name(), data)
+}
+
+/// 查询参数
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct EventsQuery {
+    /// 逗号分隔的主题列表（`accounts`、`quotas`、`process`、`settings`、`logs`），默认除 `logs` 外的全部主题
+    topics: Option<String>,
+}
+
+/// 解析 `topics` 查询参数
+fn parse_topics(raw: Option<&str>) -> Result<HashSet<Topic>, AgentError> {
+    let Some(raw) = raw else {
+        return Ok(Topic::ALL
+            .into_iter()
+            .filter(|topic| *topic != Topic::Logs)
+            .collect());
+    };
+
+    raw.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .map_err(|_| AgentError::InvalidInput(format!("Unknown event topic: {name}")))
+        })
+        .collect()
+}
+
+/// SSE 事件流处理函数
+///
+/// # 路由
+///
+/// ```text
+/// GET http://127.0.0.1:56789/api/events?topics=accounts,quotas
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    summary = "Server-Sent Events stream of agent events",
+    params(EventsQuery),
+    responses((
+        status = 200,
+        description = "`event: <type>` / `data: <AgentEvent JSON>` messages",
+        content_type = "text/event-stream",
+        body = AgentEvent
+    ))
+)]
+pub async fn events_handler(
+    req: HttpRequest,
+    host: web::Data<AppHost>,
+    query: web::Query<EventsQuery>,
+) -> Result<HttpResponse, AgentError> {
+    let topics = parse_topics(query.topics.as_deref())?;
+    // 远程设备被撤销后，在下一条消息或保活前结束事件流
+    let device_id = req
+        .extensions()
+        .get::<RemoteDeviceId>()
+        .map(|device| device.0.clone());
+    tracing::info!(target: "server::events", ?topics, "新的 SSE 订阅");
+    let receiver = crate::events::subscribe();
+
+    let hello = futures_util::stream::once(async {
+        Ok::<_, actix_web::Error>(web::Bytes::from(format!("retry: {RETRY_MILLIS}\n\n")))
+    });
+
+    let events = futures_util::stream::unfold(
+        (receiver, topics, device_id, host),
+        |(mut receiver, topics, device_id, host)| async move {
+            // 未订阅主题的事件直接跳过，保活计时不因此重置
+            let keep_alive = tokio::time::sleep(KEEP_ALIVE_INTERVAL);
+            tokio::pin!(keep_alive);
+            let chunk = loop {
+                tokio::select! {
+                    result = receiver.recv() => match result {
+                        Ok(event) if topics.contains(&event.topic()) => {
+                            break format_event(&event, Redaction::for_host(&host))
+                        }
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!(target: "server::events", skipped, "SSE 订阅者处理过慢，已丢弃事件");
+                            break format!(": lagged {skipped}\n\n");
+                        }
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = &mut keep_alive => break ": keep-alive\n\n".to_string(),
+                }
+            };
+            if let Some(device_id) = &device_id {
+                if crate::services::remote::device_scope(device_id).is_none() {
+                    tracing::warn!(target: "server::events", device_id = %device_id, "远程设备已撤销，结束事件流");
+                    return None;
+                }
+            }
+            Some((
+                Ok::<_, actix_web::Error>(web::Bytes::from(chunk)),
+                (receiver, topics, device_id, host),
+            ))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "text/event-stream"))
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(futures_util::StreamExt::chain(hello, events)))
+}
@@ -0,0 +1,119 @@
+//! # 监听地址
+//!
+//! 本地 API 可以同时监听两种地址，二者提供同一个 actix `App`（含 `/ws`）：
+//!
+//! - **Unix socket**（仅 Unix）：配置目录下的 `agent.sock`，权限 0600。
+//!   多用户主机上只有 Agent 所属用户可以连接，访问范围由文件系统权限限定。
+//! - **TCP**：`127.0.0.1:<api_tcp_port>`，同一台机器上的任何用户都可以连接。
+//!   GUI 的 WebView 只能通过 TCP 访问，因此 GUI 模式总是监听；
+//!   headless 模式在 Unix socket 可用时默认不监听，可通过 `api_tcp_enabled` 开启。
+//!
+//! - **远程（TLS）**：`0.0.0.0:<remote_access_port>`，默认关闭，只接受已配对设备的 Token，
+//!   见 [`crate::services::remote`]。
+//!
+//! CLI 与 VS Code 扩展在 socket 存在时优先使用 socket。
+
+use crate::app_settings::AppSettings;
+use std::path::PathBuf;
+
+/// 默认 TCP 端口
+pub const DEFAULT_TCP_PORT: u16 = 56789;
+
+/// 默认远程访问端口
+pub const DEFAULT_REMOTE_PORT: u16 = 56790;
+
+/// 解析后的监听配置
+#[derive(Debug, Clone)]
+pub struct ListenConfig {
+    /// TCP 端口，`None` 表示不监听 TCP
+    pub tcp_port: Option<u16>,
+    /// Unix socket 路径，`None` 表示不监听 socket
+    pub unix_socket: Option<PathBuf>,
+    /// 远程访问（TLS）端口，`None` 表示不开启远程访问
+    pub remote_port: Option<u16>,
+}
+
+impl ListenConfig {
+    /// 根据设置与运行模式决定监听地址
+    pub fn resolve(settings: &AppSettings, headless: bool) -> Self {
+        let unix_socket = (cfg!(unix) && settings.api_unix_socket_enabled)
+            .then(crate::directories::get_api_socket_file);
+
+        // GUI 的 WebView 只能通过 TCP 访问；没有 socket 时 TCP 是唯一入口
+        let tcp_enabled = if headless && unix_socket.is_some() {
+            settings.api_tcp_enabled.unwrap_or(false)
+        } else {
+            if settings.api_tcp_enabled == Some(false) {
+                tracing::warn!(
+                    target: "server::listen",
+                    headless,
+                    "TCP 是当前唯一可用的本地 API 入口，忽略 api_tcp_enabled = false"
+                );
+            }
+            true
+        };
+
+        Self {
+            tcp_port: tcp_enabled.then_some(settings.api_tcp_port),
+            unix_socket,
+            remote_port: settings
+                .remote_access_enabled
+                .then_some(settings.remote_access_port),
+        }
+    }
+
+    /// 客户端（CLI）使用的配置：只读取设置，不关心运行模式
+    ///
+    /// socket 文件存在即优先使用；TCP 端口来自设置，无论当前实例是否监听。
+    pub fn for_client() -> Self {
+        let settings = crate::app_settings::load_settings_from_disk(
+            &crate::directories::get_app_settings_file(),
+        );
+        let unix_socket = (cfg!(unix) && settings.api_unix_socket_enabled)
+            .then(crate::directories::get_api_socket_file)
+            .filter(|path| path.exists());
+
+        Self {
+            tcp_port: Some(settings.api_tcp_port),
+            unix_socket,
+            remote_port: None,
+        }
+    }
+}
+
+/// 绑定 Unix socket 并将权限设为 0600
+///
+/// 已存在的 socket 文件如果仍可连接，说明另一个实例正在运行，返回错误；
+/// 否则视为上次异常退出残留的文件，删除后重新绑定。
+#[cfg(unix)]
+pub fn bind_unix_socket(
+    path: &std::path::Path,
+) -> std::io::Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("{} is in use by another instance", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// 供 Tauri 前端获取本地 API 地址（TCP 端口可配置）
+#[tauri::command]
+pub fn get_api_base_url(
+    settings: tauri::State<'_, std::sync::Arc<crate::app_settings::AppSettingsManager>>,
+) -> String {
+    format!(
+        "http://127.0.0.1:{}/api",
+        settings.get_settings().api_tcp_port
+    )
+}
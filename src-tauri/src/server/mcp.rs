@@ -0,0 +1,536 @@
+//! # MCP 服务
+//!
+//! 按 [Model Context Protocol](https://modelcontextprotocol.io) 向 IDE 中的 AI Agent 暴露账户与配额，
+//! 让 Agent 自己查询哪个账户还有剩余配额、必要时切换账户。两种传输方式共用本模块：
+//!
+//! - Streamable HTTP：`POST /api/mcp`，每个请求返回一个 JSON 响应（不使用 SSE 流）
+//! - stdio：`antigravity-agent mcp` 子命令，见 [`crate::cli::mcp`]
+//!
+//! ## 工具
+//!
+//! | 工具 | 服务 | 作用域 |
+//! | --- | --- | --- |
+//! | `list_accounts` | `account::get_all` | read |
+//! | `get_account_quota` | `account::get_metrics` | read |
+//! | `refresh_quota` | `account::trigger_quota_refresh` | admin |
+//! | `switch_account` | `account::switch` | admin |
+//!
+//! 会修改状态的工具带有 `readOnlyHint: false` 注解与 [`CONFIRMATION_META_KEY`] 元数据，
+//! 宿主据此在调用前向用户确认。
+//!
+//! ## 资源
+//!
+//! - `antigravity://accounts/current`：当前登录的账户
+//! - `antigravity://quota/{model}`：所有已备份账户在该模型上的剩余配额
+//!
+//! 返回的邮箱与 HTTP API 一样按隐私模式脱敏。
+
+use super::rpc::{RpcError, RpcMessage, RpcRequest, RpcResponse};
+use crate::error::AgentError;
+use crate::host::AppHost;
+use crate::server::auth::ApiScope;
+use crate::services::account::{self, AccountMetrics, Redaction};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use serde_json::{json, Value};
+
+/// 支持的协议版本，按从新到旧排列；客户端请求的版本不受支持时回复最新版本
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// 工具元数据中标记“调用前需要用户确认”的键
+pub const CONFIRMATION_META_KEY: &str = "antigravity-agent/requiresConfirmation";
+
+/// 当前账户资源
+const CURRENT_ACCOUNT_URI: &str = "antigravity://accounts/current";
+
+/// 模型配额资源前缀，后接百分号编码的模型名
+const QUOTA_URI_PREFIX: &str = "antigravity://quota/";
+
+const INSTRUCTIONS: &str = "Antigravity Agent manages the Google accounts used by the Antigravity IDE. \
+Use list_accounts and get_account_quota (or the antigravity://quota/{model} resources) to find an account \
+with quota left, and switch_account to move Antigravity to it. Accounts are identified by email or account_id.";
+
+// =============================================================================
+// 工具定义
+// =============================================================================
+
+struct Tool {
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    scope: ApiScope,
+    /// 是否需要账户参数
+    takes_account: bool,
+    /// 是否会破坏性地修改状态（切换账户会重启 Antigravity）
+    destructive: bool,
+}
+
+const TOOLS: [Tool; 4] = [
+    Tool {
+        name: "list_accounts",
+        title: "List accounts",
+        description: "List backed-up Antigravity accounts with their account_id and whether each is the one currently signed in.",
+        scope: ApiScope::Read,
+        takes_account: false,
+        destructive: false,
+    },
+    Tool {
+        name: "get_account_quota",
+        title: "Get account quota",
+        description: "Remaining quota per model for an account (percentage is the fraction left, 1.0 = full) and when it resets.",
+        scope: ApiScope::Read,
+        takes_account: true,
+        destructive: false,
+    },
+    Tool {
+        name: "refresh_quota",
+        title: "Refresh quota",
+        description: "Trigger a quota refresh for the models of an account that are still at full quota, so their reset timer starts.",
+        scope: ApiScope::Admin,
+        takes_account: true,
+        destructive: false,
+    },
+    Tool {
+        name: "switch_account",
+        title: "Switch account",
+        description: "Switch Antigravity to a backed-up account. Antigravity is closed and restarted, so unsaved work in it may be lost.",
+        scope: ApiScope::Admin,
+        takes_account: true,
+        destructive: true,
+    },
+];
+
+impl Tool {
+    fn read_only(&self) -> bool {
+        self.scope == ApiScope::Read
+    }
+
+    fn definition(&self) -> Value {
+        let input_schema = if self.takes_account {
+            json!({
+                "type": "object",
+                "properties": {
+                    "account": {
+                        "type": "string",
+                        "description": "Account email or account_id"
+                    }
+                },
+                "required": ["account"]
+            })
+        } else {
+            json!({ "type": "object", "properties": {} })
+        };
+
+        let mut definition = json!({
+            "name": self.name,
+            "title": self.title,
+            "description": self.description,
+            "inputSchema": input_schema,
+            "annotations": {
+                "title": self.title,
+                "readOnlyHint": self.read_only(),
+                "destructiveHint": self.destructive,
+                "idempotentHint": self.read_only(),
+                "openWorldHint": self.name != "list_accounts"
+            }
+        });
+        if !self.read_only() {
+            definition["_meta"] = json!({ CONFIRMATION_META_KEY: true });
+        }
+        definition
+    }
+}
+
+// =============================================================================
+// 消息处理
+// =============================================================================
+
+/// 处理一条 MCP 消息，返回需要回复的 JSON-RPC 响应
+///
+/// 通知和客户端发来的响应不需要回复，返回 `None`。`scope` 决定可调用的工具。
+pub async fn handle_message(
+    message: Value,
+    scope: ApiScope,
+    redaction: Redaction,
+) -> Option<Value> {
+    let id = RpcMessage::id_of(&message);
+    let request = match RpcMessage::from_value(message) {
+        Ok(RpcMessage::Request(request)) => request,
+        Ok(RpcMessage::Response(_)) => return None,
+        Err(error) => return to_json(RpcResponse::failure(id, error)),
+    };
+
+    let id = request.id.clone()?;
+    let method = request.method.clone();
+    tracing::debug!(target: "server::mcp", method = %method, "收到 MCP 请求");
+
+    let outcome = call_method(request, scope, redaction).await;
+    if let Err(error) = &outcome {
+        tracing::warn!(target: "server::mcp", method = %method, code = error.code, error = %error.message, "MCP 请求失败");
+    }
+    to_json(match outcome {
+        Ok(result) => RpcResponse::success(Some(id), result),
+        Err(error) => RpcResponse::failure(Some(id), error),
+    })
+}
+
+/// 构造解析失败时的错误响应
+pub fn parse_error(error: impl std::fmt::Display) -> Value {
+    to_json(RpcResponse::failure(
+        None,
+        RpcError::parse_error(format!("Invalid JSON: {error}")),
+    ))
+    .unwrap_or(Value::Null)
+}
+
+fn to_json(response: RpcResponse) -> Option<Value> {
+    serde_json::to_value(response).ok()
+}
+
+async fn call_method(
+    request: RpcRequest,
+    scope: ApiScope,
+    redaction: Redaction,
+) -> Result<Value, RpcError> {
+    match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({
+            "tools": TOOLS
+                .iter()
+                .filter(|tool| scope.allows(tool.scope))
+                .map(Tool::definition)
+                .collect::<Vec<_>>()
+        })),
+        "tools/call" => call_tool(&request.params, scope, redaction).await,
+        "resources/list" => list_resources().await,
+        "resources/templates/list" => Ok(json!({
+            "resourceTemplates": [{
+                "uriTemplate": format!("{QUOTA_URI_PREFIX}{{model}}"),
+                "name": "model-quota",
+                "title": "Quota of a model across accounts",
+                "mimeType": "application/json"
+            }]
+        })),
+        "resources/read" => {
+            let uri = request
+                .params
+                .get("uri")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::invalid_params("Missing resource uri"))?;
+            read_resource(uri, redaction).await
+        }
+        method => Err(RpcError::method_not_found(method)),
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| **version == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "subscribe": false, "listChanged": false }
+        },
+        "serverInfo": {
+            "name": "antigravity-agent",
+            "title": "Antigravity Agent",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "instructions": INSTRUCTIONS
+    })
+}
+
+// =============================================================================
+// 工具调用
+// =============================================================================
+
+async fn call_tool(
+    params: &Value,
+    scope: ApiScope,
+    redaction: Redaction,
+) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::invalid_params("Missing tool name"))?;
+    let tool = TOOLS
+        .iter()
+        .find(|tool| tool.name == name)
+        .ok_or_else(|| RpcError::invalid_params(format!("Unknown tool: {name}")))?;
+
+    if !scope.allows(tool.scope) {
+        return Err(AgentError::Forbidden(format!("Token scope does not allow '{name}'")).into());
+    }
+
+    let account = params
+        .get("arguments")
+        .and_then(|arguments| arguments.get("account"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let account = match (tool.takes_account, account) {
+        (true, Some(account)) if !account.trim().is_empty() => account,
+        (true, _) => return Err(RpcError::invalid_params("Missing argument: account")),
+        (false, _) => String::new(),
+    };
+
+    tracing::info!(target: "server::mcp", tool = name, "调用 MCP 工具");
+    let config_dir = crate::directories::get_config_directory();
+    let outcome = match tool.name {
+        "list_accounts" => list_accounts(&config_dir, redaction).await,
+        "get_account_quota" => account::get_metrics(&config_dir, account)
+            .await
+            .and_then(|metrics| to_value(redaction.metrics(metrics))),
+        "refresh_quota" => account::trigger_quota_refresh(&config_dir, account)
+            .await
+            .and_then(|result| to_value(redaction.trigger_result(result))),
+        "switch_account" => account::switch(account).await.and_then(to_value),
+        _ => unreachable!("every entry of TOOLS is handled"),
+    };
+
+    // 业务失败作为工具结果返回（isError），让模型看到原因并自行调整
+    Ok(match outcome {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "structuredContent": value,
+            "isError": false
+        }),
+        Err(error) => json!({
+            "content": [{
+                "type": "text",
+                "text": format!("{} ({})", error.message(), error.code())
+            }],
+            "isError": true
+        }),
+    })
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, AgentError> {
+    serde_json::to_value(value)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize result: {e}")))
+}
+
+fn email_of(account: &account::AntigravityAccountResponse) -> &str {
+    account
+        .antigravity_auth_status
+        .get("email")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// 账户列表只保留 ID、邮箱与是否为当前账户，避免把解码后的原始状态塞进模型上下文
+async fn list_accounts(
+    config_dir: &std::path::Path,
+    redaction: Redaction,
+) -> Result<Value, AgentError> {
+    let accounts = account::get_all(config_dir).await?;
+    let current_id = account::get_current()
+        .await
+        .ok()
+        .map(|current| current.account_id);
+
+    let accounts: Vec<Value> = redaction
+        .accounts(accounts)
+        .iter()
+        .map(|account| {
+            json!({
+                "account_id": account.account_id,
+                "email": email_of(account),
+                "is_current": current_id.as_deref() == Some(account.account_id.as_str())
+            })
+        })
+        .collect();
+    Ok(json!({ "accounts": accounts }))
+}
+
+// =============================================================================
+// 资源
+// =============================================================================
+
+/// 当前账户的配额（未登录时返回 `None`）
+async fn current_metrics(config_dir: &std::path::Path) -> Option<AccountMetrics> {
+    let current = account::get_current().await.ok()?;
+    let email = email_of(&current);
+    if email.is_empty() {
+        return None;
+    }
+    account::get_metrics(config_dir, email.to_string())
+        .await
+        .ok()
+}
+
+/// 资源列表：当前账户，以及当前账户配额中出现的每个模型
+async fn list_resources() -> Result<Value, RpcError> {
+    let config_dir = crate::directories::get_config_directory();
+    let mut resources = vec![json!({
+        "uri": CURRENT_ACCOUNT_URI,
+        "name": "current-account",
+        "title": "Current account",
+        "description": "Account currently signed in to Antigravity",
+        "mimeType": "application/json"
+    })];
+
+    for quota in current_metrics(&config_dir)
+        .await
+        .map(|metrics| metrics.quotas)
+        .unwrap_or_default()
+    {
+        resources.push(json!({
+            "uri": format!("{QUOTA_URI_PREFIX}{}", encode_segment(&quota.model_name)),
+            "name": quota.model_name,
+            "title": format!("{} quota", quota.model_name),
+            "description": "Remaining quota of this model on every backed-up account",
+            "mimeType": "application/json"
+        }));
+    }
+    Ok(json!({ "resources": resources }))
+}
+
+async fn read_resource(uri: &str, redaction: Redaction) -> Result<Value, RpcError> {
+    let config_dir = crate::directories::get_config_directory();
+    let contents = if uri == CURRENT_ACCOUNT_URI {
+        to_value(redaction.account(account::get_current().await?))?
+    } else if let Some(model) = uri.strip_prefix(QUOTA_URI_PREFIX) {
+        let model = decode_segment(model)
+            .ok_or_else(|| RpcError::invalid_params(format!("Invalid resource uri: {uri}")))?;
+        model_quota(&config_dir, &model, redaction).await?
+    } else {
+        return Err(AgentError::NotFound(format!("Resource not found: {uri}")).into());
+    };
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": contents.to_string()
+        }]
+    }))
+}
+
+/// 所有已备份账户在某个模型上的配额，查询失败的账户单独列出错误
+async fn model_quota(
+    config_dir: &std::path::Path,
+    model: &str,
+    redaction: Redaction,
+) -> Result<Value, AgentError> {
+    let accounts = account::get_all(config_dir).await?;
+    let lookups = accounts.iter().map(|account| async move {
+        let email = email_of(account).to_string();
+        (
+            account.account_id.clone(),
+            account::get_metrics(config_dir, email.clone()).await,
+            email,
+        )
+    });
+
+    let mut rows = Vec::new();
+    for (account_id, metrics, email) in futures_util::future::join_all(lookups).await {
+        let email = redaction.email(&email);
+        rows.push(match metrics {
+            Ok(metrics) => match metrics.quotas.into_iter().find(|q| q.model_name == model) {
+                Some(quota) => json!({
+                    "account_id": account_id,
+                    "email": email,
+                    "percentage": quota.percentage,
+                    "reset_text": quota.reset_text
+                }),
+                None => json!({
+                    "account_id": account_id,
+                    "email": email,
+                    "error": "Model not available for this account"
+                }),
+            },
+            Err(error) => json!({
+                "account_id": account_id,
+                "email": email,
+                "error": error.message()
+            }),
+        });
+    }
+    Ok(json!({ "model": model, "accounts": rows }))
+}
+
+/// 模型名可能包含空格等字符，在 URI 中按 RFC 3986 百分号编码
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn decode_segment(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+// =============================================================================
+// Streamable HTTP
+// =============================================================================
+
+/// MCP Streamable HTTP 端点
+///
+/// # 路由
+///
+/// ```text
+/// POST http://127.0.0.1:56789/api/mcp
+/// ```
+///
+/// 请求返回 `application/json` 响应；只含通知的消息返回 `202 Accepted`。
+/// 服务端不主动推送消息，`GET` 返回 `405`。
+#[utoipa::path(
+    post,
+    path = "/api/mcp",
+    tag = "mcp",
+    summary = "Model Context Protocol endpoint (streamable HTTP, JSON responses)",
+    request_body = Object,
+    responses(
+        (status = 200, description = "JSON-RPC response", body = Object),
+        (status = 202, description = "Notification accepted")
+    )
+)]
+pub async fn mcp_handler(
+    req: HttpRequest,
+    body: web::Bytes,
+    host: web::Data<AppHost>,
+) -> HttpResponse {
+    // 鉴权中间件已写入作用域；缺失时按只读处理，具体工具在调用时再校验
+    let scope = req
+        .extensions()
+        .get::<ApiScope>()
+        .copied()
+        .unwrap_or(ApiScope::Read);
+
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => return HttpResponse::BadRequest().json(parse_error(e)),
+    };
+
+    match handle_message(message, scope, Redaction::for_host(&host)).await {
+        Some(response) => HttpResponse::Ok().json(response),
+        None => HttpResponse::Accepted().finish(),
+    }
+}
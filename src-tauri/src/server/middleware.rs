@@ -16,6 +16,9 @@ use futures_util::stream::once;
 use futures_util::Stream; // Import Stream trait
 use serde_json::Value;
 
+/// 请求体按外部协议原样处理、不转换键名的路由（MCP 的字段本身就是 camelCase）
+const RAW_JSON_ROUTES: [&str; 1] = ["/api/mcp"];
+
 // Middleware Factory
 pub struct CamelCaseToSnakeCase;
 
@@ -67,7 +70,7 @@ where
                 .map(|v| v.to_str().unwrap_or("").contains("application/json"))
                 .unwrap_or(false);
 
-            if is_json {
+            if is_json && !RAW_JSON_ROUTES.contains(&req.path()) {
                 // Read body
                 let body = req.extract::<Bytes>().await?;
 
@@ -148,3 +151,162 @@ fn camel_to_snake(s: &str) -> String {
     }
     new_s
 }
+
+// =============================================================================
+// X-Request-Id
+// =============================================================================
+
+/// 请求 ID 头
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 客户端传入的请求 ID 最大长度
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// 当前请求的 ID
+///
+/// 由 [`RequestIdMiddleware`] 写入请求扩展，处理器可以直接作为参数提取。
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// 接受客户端提供的 ID（仅限 `[A-Za-z0-9._-]`，最长 64 个字符），否则生成新的 UUID
+    fn from_header(value: Option<&header::HeaderValue>) -> Self {
+        let accepted = value
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+            });
+
+        match accepted {
+            Some(id) => Self(id.to_string()),
+            None => Self(uuid::Uuid::new_v4().to_string()),
+        }
+    }
+}
+
+impl actix_web::FromRequest for RequestId {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _payload: &mut dev::Payload) -> Self::Future {
+        use actix_web::HttpMessage;
+
+        // 未经过中间件（不应发生）时生成一个，保证处理器总能拿到 ID
+        let id = req
+            .extensions()
+            .get::<RequestId>()
+            .cloned()
+            .unwrap_or_else(|| RequestId::from_header(None));
+        ready(Ok(id))
+    }
+}
+
+/// 请求 ID 中间件工厂
+///
+/// 为每个请求分配（或沿用客户端传入的）`X-Request-Id`，在 `request` span 中执行后续处理，
+/// 使服务层、Google API 调用和 SQLite 操作的日志都带上 `request_id`；响应中回写同名头。
+/// 请求完成时按路由模板记录延迟指标（见 [`crate::telemetry`]）。
+pub struct RequestIdTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        use actix_web::HttpMessage;
+        use tracing::Instrument;
+
+        let svc = self.service.clone();
+        let method = req.method().to_string();
+        let request_id = RequestId::from_header(req.headers().get(REQUEST_ID_HEADER));
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id.0,
+            method = %req.method(),
+            path = %req.path(),
+        );
+        req.extensions_mut().insert(request_id.clone());
+
+        Box::pin(
+            async move {
+                let started = std::time::Instant::now();
+                let result = svc.call(req).await;
+
+                match &result {
+                    Ok(res) => {
+                        let elapsed = started.elapsed();
+                        // 使用路由模板作为标签，未匹配的路径归为一类，避免标签基数失控
+                        let route = res
+                            .request()
+                            .match_pattern()
+                            .unwrap_or_else(|| "unmatched".to_string());
+                        crate::telemetry::observe_http_request(
+                            &method,
+                            &route,
+                            res.status().as_u16(),
+                            elapsed,
+                        );
+                        tracing::debug!(
+                            target: "server::request",
+                            status = res.status().as_u16(),
+                            duration_ms = elapsed.as_millis() as u64,
+                            "请求完成"
+                        )
+                    }
+                    Err(error) => tracing::warn!(
+                        target: "server::request",
+                        duration_ms = started.elapsed().as_millis() as u64,
+                        error = %error,
+                        "请求失败"
+                    ),
+                }
+
+                let mut res = result?;
+                if let Ok(value) = header::HeaderValue::from_str(&request_id.0) {
+                    res.headers_mut()
+                        .insert(header::HeaderName::from_static(REQUEST_ID_HEADER), value);
+                }
+                Ok(res)
+            }
+            .instrument(span),
+        )
+    }
+}
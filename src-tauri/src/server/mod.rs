@@ -1,515 +1,946 @@
+use crate::error::AgentError;
+use crate::host::AppHost;
+use crate::services::account::Redaction;
 use crate::AppState;
-use actix_cors::Cors;
 use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
-use serde_json::json;
-
+use middleware::RequestId;
+use schema::{
+    CryptoResponse, LanguageResponse, MessageResponse, StateResponse, SuccessResponse,
+    ValidResponse,
+};
+use std::sync::Arc;
+
+pub mod auth;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod events;
+pub mod listen;
+pub mod mcp;
 mod middleware;
+mod openapi;
+mod push;
+pub mod rpc;
+mod schema;
+pub mod tls;
 pub mod websocket;
 
 // =============================================================================
 // Account Service Endpoints
 // =============================================================================
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Whether Antigravity is running",
+    responses((status = 200, body = bool))
+)]
 #[get("/api/is_antigravity_running")]
 async fn status() -> impl Responder {
     let running = crate::services::account::is_running();
     HttpResponse::Ok().json(running)
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "List backed-up accounts",
+    responses((status = 200, body = Vec<crate::services::account::AntigravityAccountResponse>))
+)]
 #[get("/api/get_antigravity_accounts")]
-async fn get_accounts(data: web::Data<AppState>) -> impl Responder {
+async fn get_accounts(
+    host: web::Data<AppHost>,
+    data: web::Data<AppState>,
+) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::account::get_all(&config_dir).await {
-        Ok(accounts) => HttpResponse::Ok().json(accounts),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let accounts = crate::services::account::get_all(&config_dir).await?;
+    Ok(HttpResponse::Ok().json(Redaction::for_host(&host).accounts(accounts)))
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Account currently signed in to Antigravity",
+    responses((status = 200, body = crate::services::account::AntigravityAccountResponse))
+)]
 #[get("/api/get_current_antigravity_account_info")]
-async fn get_current_account() -> impl Responder {
-    match crate::services::account::get_current().await {
-        Ok(json) => HttpResponse::Ok().json(json),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_current_account(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let account = crate::services::account::get_current().await?;
+    Ok(HttpResponse::Ok().json(Redaction::for_host(&host).account(account)))
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Back up the current account",
+    responses((status = 200, body = crate::services::account::CommandResult))
+)]
 #[post("/api/save_antigravity_current_account")]
-async fn save_current_account() -> impl Responder {
-    match crate::services::account::backup_current().await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn save_current_account(request_id: RequestId) -> Result<HttpResponse, AgentError> {
+    let result = crate::services::account::backup_current()
+        .await?
+        .with_request_id(request_id.0);
+    Ok(HttpResponse::Ok().json(result))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct RestoreRequest {
+    /// 账户邮箱或 `account_id`
     account_name: String,
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Write a backed-up account into Antigravity without restarting it (refused while it is running)",
+    request_body = RestoreRequest,
+    responses((status = 200, body = crate::services::account::CommandResult))
+)]
 #[post("/api/restore_antigravity_account")]
-async fn restore_account(req: web::Json<RestoreRequest>) -> impl Responder {
-    match crate::services::account::restore(req.account_name.clone()).await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn restore_account(
+    req: web::Json<RestoreRequest>,
+    request_id: RequestId,
+) -> Result<HttpResponse, AgentError> {
+    let result = crate::services::account::restore(req.account_name.clone())
+        .await?
+        .with_request_id(request_id.0);
+    Ok(HttpResponse::Ok().json(result))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct SwitchAccountRequest {
+    /// 账户邮箱或 `account_id`
     account_name: String,
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Switch Antigravity to a backed-up account",
+    request_body = SwitchAccountRequest,
+    responses((status = 200, body = crate::services::account::CommandResult))
+)]
 #[post("/api/switch_to_antigravity_account")]
-async fn switch_account(req: web::Json<SwitchAccountRequest>) -> impl Responder {
-    match crate::services::account::switch(req.account_name.clone()).await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn switch_account(
+    req: web::Json<SwitchAccountRequest>,
+    request_id: RequestId,
+) -> Result<HttpResponse, AgentError> {
+    let result = crate::services::account::switch(req.account_name.clone())
+        .await?
+        .with_request_id(request_id.0);
+    Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Clear the current Antigravity login",
+    responses((status = 200, body = crate::services::account::CommandResult))
+)]
 #[post("/api/clear_all_antigravity_data")]
-async fn clear_data() -> impl Responder {
-    match crate::services::account::clear_all_data().await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn clear_data(request_id: RequestId) -> Result<HttpResponse, AgentError> {
+    let result = crate::services::account::clear_all_data()
+        .await?
+        .with_request_id(request_id.0);
+    Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Back up, sign out and restart Antigravity for a new login",
+    responses((status = 200, body = crate::services::account::CommandResult))
+)]
 #[post("/api/sign_in_new_antigravity_account")]
-async fn sign_in_new() -> impl Responder {
-    match crate::services::account::sign_in_new().await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn sign_in_new(request_id: RequestId) -> Result<HttpResponse, AgentError> {
+    let result = crate::services::account::sign_in_new()
+        .await?
+        .with_request_id(request_id.0);
+    Ok(HttpResponse::Ok().json(result))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct GetMetricRequest {
+    /// 账户邮箱或 `account_id`
     email: String,
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Quota metrics of an account",
+    request_body = GetMetricRequest,
+    responses((status = 200, body = crate::services::account::AccountMetrics))
+)]
 #[post("/api/get_account_metrics")]
 async fn get_metrics(
+    host: web::Data<AppHost>,
     data: web::Data<AppState>,
     req: web::Json<GetMetricRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::account::get_metrics(&config_dir, req.email.clone()).await {
-        Ok(metrics) => HttpResponse::Ok().json(metrics),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let metrics = crate::services::account::get_metrics(&config_dir, req.email.clone()).await?;
+    Ok(HttpResponse::Ok().json(Redaction::for_host(&host).metrics(metrics)))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct TriggerRefreshRequest {
+    /// 账户邮箱或 `account_id`
     email: String,
 }
 
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Trigger quota refresh for models at full quota",
+    request_body = TriggerRefreshRequest,
+    responses((status = 200, body = crate::services::account::TriggerResult))
+)]
 #[post("/api/trigger_quota_refresh")]
 async fn refresh_quota(
+    host: web::Data<AppHost>,
     data: web::Data<AppState>,
     req: web::Json<TriggerRefreshRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, AgentError> {
+    let config_dir = {
+        let state = data.inner.lock();
+        state.config_dir.clone()
+    };
+
+    let result =
+        crate::services::account::trigger_quota_refresh(&config_dir, req.email.clone()).await?;
+    Ok(HttpResponse::Ok().json(Redaction::for_host(&host).trigger_result(result)))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct RevealSecretsRequest {
+    /// 账户邮箱或 `account_id`
+    account_name: String,
+}
+
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Raw tokens of a backed-up account (admin token only)",
+    request_body = RevealSecretsRequest,
+    responses((status = 200, body = crate::services::account::AccountSecrets))
+)]
+#[post("/api/reveal_account_secrets")]
+async fn reveal_secrets(
+    data: web::Data<AppState>,
+    req: web::Json<RevealSecretsRequest>,
+) -> Result<HttpResponse, AgentError> {
+    let config_dir = {
+        let state = data.inner.lock();
+        state.config_dir.clone()
+    };
+
+    let secrets = crate::services::account::reveal_secrets(&config_dir, &req.account_name).await?;
+    Ok(HttpResponse::Ok().json(secrets))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct UpdateMetadataRequest {
+    /// 账户邮箱或 `account_id`
+    account_name: String,
+    #[serde(flatten)]
+    update: crate::services::account::AccountMetadataUpdate,
+}
+
+#[utoipa::path(
+    tag = "accounts",
+    summary = "Update display name, notes, tags, color and pinned/disabled flags of an account",
+    request_body = UpdateMetadataRequest,
+    responses((status = 200, body = crate::services::account::AccountMetadata))
+)]
+#[post("/api/update_account_metadata")]
+async fn update_metadata(
+    data: web::Data<AppState>,
+    req: web::Json<UpdateMetadataRequest>,
+) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::account::trigger_quota_refresh(&config_dir, req.email.clone()).await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let req = req.into_inner();
+    let metadata =
+        crate::services::account::update_metadata(&config_dir, req.account_name, req.update)
+            .await?;
+    Ok(HttpResponse::Ok().json(metadata))
 }
 
 // =============================================================================
 // Backup Service Endpoints
 // =============================================================================
 
+#[utoipa::path(
+    tag = "backups",
+    summary = "Export all account backups",
+    responses((status = 200, body = Vec<crate::services::backup::AccountExportedData>))
+)]
 #[get("/api/collect_account_contents")]
-async fn collect_backups(data: web::Data<AppState>) -> impl Responder {
+async fn collect_backups(data: web::Data<AppState>) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::backup::collect_contents(&config_dir).await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let data = crate::services::backup::collect_contents(&config_dir).await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
+#[utoipa::path(
+    tag = "backups",
+    summary = "Import account backups",
+    request_body = Vec<crate::services::backup::AccountExportedData>,
+    responses((status = 200, body = crate::services::backup::RestoreResult))
+)]
 #[post("/api/restore_backup_files")]
 async fn restore_backups(
     data: web::Data<AppState>,
     req: web::Json<Vec<crate::services::backup::AccountExportedData>>,
-) -> impl Responder {
+) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::backup::restore_files(&config_dir, req.into_inner()).await {
-        Ok(res) => HttpResponse::Ok().json(res),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let res = crate::services::backup::restore_files(&config_dir, req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(res))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct DeleteBackupRequest {
+    /// 账户邮箱或 `account_id`
     name: String,
 }
 
+#[utoipa::path(
+    tag = "backups",
+    summary = "Delete an account backup",
+    request_body = DeleteBackupRequest,
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/delete_backup")]
 async fn delete_backup(
     data: web::Data<AppState>,
     req: web::Json<DeleteBackupRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::backup::delete(&config_dir, req.name.clone()).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let msg = crate::services::backup::delete(&config_dir, req.name.clone()).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "backups",
+    summary = "Delete all account backups",
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/clear_all_backups")]
-async fn clear_backups(data: web::Data<AppState>) -> impl Responder {
+async fn clear_backups(data: web::Data<AppState>) -> Result<HttpResponse, AgentError> {
     let config_dir = {
         let state = data.inner.lock();
         state.config_dir.clone()
     };
 
-    match crate::services::backup::clear_all(&config_dir).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    let msg = crate::services::backup::clear_all(&config_dir).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
 // =============================================================================
 // Settings Service Endpoints
 // =============================================================================
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct BoolStateRequest {
     enabled: bool,
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "All application settings",
+    responses((status = 200, body = Object))
+)]
 #[get("/api/get_all_settings")]
-async fn get_all_settings(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::settings::get_all(&app).await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_all_settings(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let data = crate::services::settings::get_all(&host).await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Enable or disable the system tray",
+    request_body = BoolStateRequest,
+    responses((status = 200, body = StateResponse))
+)]
 #[post("/api/save_system_tray_state")]
 async fn save_tray_state(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<BoolStateRequest>,
-) -> impl Responder {
-    match crate::services::settings::save_system_tray_state(&app, req.enabled).await {
-        Ok(val) => HttpResponse::Ok().json(json!({ "success": true, "value": val })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::settings::save_system_tray_state(&host, req.enabled).await?;
+    Ok(HttpResponse::Ok().json(StateResponse::ok(val)))
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Enable or disable silent start",
+    request_body = BoolStateRequest,
+    responses((status = 200, body = StateResponse))
+)]
 #[post("/api/save_silent_start_state")]
 async fn save_silent_start(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<BoolStateRequest>,
-) -> impl Responder {
-    match crate::services::settings::save_silent_start_state(&app, req.enabled).await {
-        Ok(val) => HttpResponse::Ok().json(json!({ "success": true, "value": val })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::settings::save_silent_start_state(&host, req.enabled).await?;
+    Ok(HttpResponse::Ok().json(StateResponse::ok(val)))
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Enable or disable private mode",
+    request_body = BoolStateRequest,
+    responses((status = 200, body = StateResponse))
+)]
 #[post("/api/save_private_mode_state")]
 async fn save_private_mode(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<BoolStateRequest>,
-) -> impl Responder {
-    match crate::services::settings::save_private_mode_state(&app, req.enabled).await {
-        Ok(val) => HttpResponse::Ok().json(json!({ "success": true, "value": val })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::settings::save_private_mode_state(&host, req.enabled).await?;
+    Ok(HttpResponse::Ok().json(StateResponse::ok(val)))
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Enable or disable debug mode",
+    request_body = BoolStateRequest,
+    responses((status = 200, body = StateResponse))
+)]
 #[post("/api/save_debug_mode_state")]
 async fn save_debug_mode(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<BoolStateRequest>,
-) -> impl Responder {
-    match crate::services::settings::save_debug_mode_state(&app, req.enabled).await {
-        Ok(val) => HttpResponse::Ok().json(json!({ "success": true, "value": val })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::settings::save_debug_mode_state(&host, req.enabled).await?;
+    Ok(HttpResponse::Ok().json(StateResponse::ok(val)))
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Configure automatic backup of newly signed-in accounts",
+    request_body = crate::services::settings::AutoCaptureSettings,
+    responses((status = 200, body = crate::services::settings::AutoCaptureSettings))
+)]
+#[post("/api/save_auto_capture_settings")]
+async fn save_auto_capture(
+    host: web::Data<AppHost>,
+    req: web::Json<crate::services::settings::AutoCaptureSettings>,
+) -> Result<HttpResponse, AgentError> {
+    let saved = crate::services::settings::save_auto_capture(&host, req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(saved))
+}
+
+#[utoipa::path(
+    tag = "settings",
+    summary = "UI language",
+    responses((status = 200, body = LanguageResponse))
+)]
 #[get("/api/get_language")]
-async fn get_language(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::settings::get_language(&app).await {
-        Ok(lang) => HttpResponse::Ok().json(json!({ "language": lang })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_language(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let lang = crate::services::settings::get_language(&host).await?;
+    Ok(HttpResponse::Ok().json(LanguageResponse { language: lang }))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct SetLanguageRequest {
     language: String,
 }
 
+#[utoipa::path(
+    tag = "settings",
+    summary = "Set the UI language",
+    request_body = SetLanguageRequest,
+    responses((status = 200, body = SuccessResponse))
+)]
 #[post("/api/set_language")]
 async fn set_language(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<SetLanguageRequest>,
-) -> impl Responder {
-    match crate::services::settings::set_language(&app, req.language.clone()).await {
-        Ok(_) => HttpResponse::Ok().json(json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    crate::services::settings::set_language(&host, req.language.clone()).await?;
+    Ok(HttpResponse::Ok().json(SuccessResponse::ok()))
 }
 
 // =============================================================================
 // Platform Service Endpoints
 // =============================================================================
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Operating system information",
+    responses((status = 200, body = Object))
+)]
 #[get("/api/get_platform_info")]
-async fn get_platform_info() -> impl Responder {
-    match crate::services::platform::get_platform_info().await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_platform_info() -> Result<HttpResponse, AgentError> {
+    let data = crate::services::platform::get_platform_info().await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Candidate Antigravity installation paths",
+    responses((status = 200, body = Vec<String>))
+)]
 #[get("/api/find_antigravity_installations")]
-async fn find_installations() -> impl Responder {
-    match crate::services::platform::find_antigravity_installations().await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn find_installations() -> Result<HttpResponse, AgentError> {
+    let data = crate::services::platform::find_antigravity_installations().await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct PathRequest {
     path: String,
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Check whether a path is an Antigravity executable",
+    request_body = PathRequest,
+    responses((status = 200, body = ValidResponse))
+)]
 #[post("/api/validate_antigravity_executable")]
-async fn validate_executable(req: web::Json<PathRequest>) -> impl Responder {
-    match crate::services::platform::validate_antigravity_executable(req.path.clone()).await {
-        Ok(valid) => HttpResponse::Ok().json(json!({ "valid": valid })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn validate_executable(req: web::Json<PathRequest>) -> Result<HttpResponse, AgentError> {
+    let valid =
+        crate::services::platform::validate_antigravity_executable(req.path.clone()).await?;
+    Ok(HttpResponse::Ok().json(ValidResponse { valid }))
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Detect the Antigravity data directory",
+    responses((status = 200, body = Object))
+)]
 #[get("/api/detect_antigravity_installation")]
-async fn detect_installation() -> impl Responder {
-    match crate::services::platform::detect_antigravity_installation().await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn detect_installation() -> Result<HttpResponse, AgentError> {
+    let data = crate::services::platform::detect_antigravity_installation().await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Detect the Antigravity executable",
+    responses((status = 200, body = Object))
+)]
 #[get("/api/detect_antigravity_executable")]
-async fn detect_executable() -> impl Responder {
-    match crate::services::platform::detect_antigravity_executable().await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn detect_executable() -> Result<HttpResponse, AgentError> {
+    let data = crate::services::platform::detect_antigravity_executable().await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Save a custom Antigravity executable path",
+    request_body = PathRequest,
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/save_antigravity_executable")]
-async fn save_executable(req: web::Json<PathRequest>) -> impl Responder {
-    match crate::services::platform::save_antigravity_executable(req.path.clone()).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn save_executable(req: web::Json<PathRequest>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::platform::save_antigravity_executable(req.path.clone()).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "platform",
+    summary = "Paths currently in use",
+    responses((status = 200, body = Object))
+)]
 #[get("/api/get_current_paths")]
-async fn get_paths() -> impl Responder {
-    match crate::services::platform::get_current_paths().await {
-        Ok(data) => HttpResponse::Ok().json(data),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_paths() -> Result<HttpResponse, AgentError> {
+    let data = crate::services::platform::get_current_paths().await?;
+    Ok(HttpResponse::Ok().json(data))
 }
 
 // =============================================================================
 // Crypto Service Endpoints
 // =============================================================================
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct CryptoRequest {
     data: String, // json_data or encrypted_data
     password: String,
 }
 
+#[utoipa::path(
+    tag = "crypto",
+    summary = "Encrypt exported configuration data",
+    request_body = CryptoRequest,
+    responses((status = 200, body = CryptoResponse))
+)]
 #[post("/api/encrypt_config_data")]
-async fn encrypt_data(req: web::Json<CryptoRequest>) -> impl Responder {
-    match crate::services::crypto::encrypt_config_data(req.data.clone(), req.password.clone()).await
-    {
-        Ok(res) => HttpResponse::Ok().json(json!({ "result": res })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn encrypt_data(req: web::Json<CryptoRequest>) -> Result<HttpResponse, AgentError> {
+    let res = crate::services::crypto::encrypt_config_data(req.data.clone(), req.password.clone())
+        .await?;
+    Ok(HttpResponse::Ok().json(CryptoResponse { result: res }))
 }
 
+#[utoipa::path(
+    tag = "crypto",
+    summary = "Decrypt exported configuration data",
+    request_body = CryptoRequest,
+    responses((status = 200, body = CryptoResponse))
+)]
 #[post("/api/decrypt_config_data")]
-async fn decrypt_data(req: web::Json<CryptoRequest>) -> impl Responder {
-    match crate::services::crypto::decrypt_config_data(req.data.clone(), req.password.clone()).await
-    {
-        Ok(res) => HttpResponse::Ok().json(json!({ "result": res })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn decrypt_data(req: web::Json<CryptoRequest>) -> Result<HttpResponse, AgentError> {
+    let res = crate::services::crypto::decrypt_config_data(req.data.clone(), req.password.clone())
+        .await?;
+    Ok(HttpResponse::Ok().json(CryptoResponse { result: res }))
 }
 
 // =============================================================================
 // System Service Endpoints
 // =============================================================================
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct UpdateTrayRequest {
     accounts: Vec<String>,
     labels: Option<crate::system_tray::TrayMenuLabels>,
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Update the tray menu account list",
+    request_body = UpdateTrayRequest,
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/update_tray_menu_command")]
 async fn update_tray(
-    app: web::Data<tauri::AppHandle>,
+    host: web::Data<AppHost>,
     req: web::Json<UpdateTrayRequest>,
-) -> impl Responder {
-    match crate::services::system::tray::update_menu(&app, req.accounts.clone(), req.labels.clone())
-        .await
-    {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+) -> Result<HttpResponse, AgentError> {
+    let msg =
+        crate::services::system::tray::update_menu(&host, req.accounts.clone(), req.labels.clone())
+            .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Hide the main window to the tray",
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/minimize_to_tray")]
-async fn minimize_tray(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::system::tray::minimize(&app).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn minimize_tray(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::system::tray::minimize(&host).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Show the main window",
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/restore_from_tray")]
-async fn restore_tray(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::system::tray::restore(&app).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn restore_tray(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::system::tray::restore(&host).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Whether the database monitor is running",
+    responses((status = 200, body = bool))
+)]
 #[get("/api/is_database_monitoring_running")]
-async fn is_db_monitor(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::system::db_monitor::is_running(&app).await {
-        Ok(val) => HttpResponse::Ok().json(val),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn is_db_monitor(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::system::db_monitor::is_running(&host).await?;
+    Ok(HttpResponse::Ok().json(val))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Start the database monitor",
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/start_database_monitoring")]
-async fn start_db_monitor(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::system::db_monitor::start(&app).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn start_db_monitor(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::system::db_monitor::start(&host).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Stop the database monitor",
+    description = "Stops the Tauri `database-changed` event for the GUI window. Automatic backup and `account_changed` events keep running.",
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/stop_database_monitoring")]
-async fn stop_db_monitor(app: web::Data<tauri::AppHandle>) -> impl Responder {
-    match crate::services::system::db_monitor::stop(&app).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn stop_db_monitor(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::system::db_monitor::stop(&host).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct FileWriteRequest {
     path: String,
     content: String,
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Write the export file the user picked in the save dialog (once per pick)",
+    request_body = FileWriteRequest,
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/write_text_file")]
-async fn write_file(req: web::Json<FileWriteRequest>) -> impl Responder {
-    match crate::services::system::logging::write_text_file(req.path.clone(), req.content.clone())
-        .await
-    {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn write_file(req: web::Json<FileWriteRequest>) -> Result<HttpResponse, AgentError> {
+    let msg =
+        crate::services::system::logging::write_text_file(req.path.clone(), req.content.clone())
+            .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Write a frontend log entry",
+    request_body = Object,
+    responses((status = 200, body = SuccessResponse))
+)]
 #[post("/api/write_frontend_log")]
-async fn write_log(req: web::Json<serde_json::Value>) -> impl Responder {
+async fn write_log(req: web::Json<serde_json::Value>) -> Result<HttpResponse, AgentError> {
     // req is the raw json object
-    match crate::services::system::logging::write_frontend_log(req.into_inner()).await {
-        Ok(_) => HttpResponse::Ok().json(json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+    crate::services::system::logging::write_frontend_log(req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(SuccessResponse::ok()))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Log directory path",
+    responses((status = 200, body = String))
+)]
 #[get("/api/get_log_directory_path")]
-async fn get_log_dir() -> impl Responder {
-    match crate::services::system::logging::get_directory_path().await {
-        Ok(path) => HttpResponse::Ok().json(json!(path)), // Return pure string or wrapped? Command returned string. Adapt to json.
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn get_log_dir() -> Result<HttpResponse, AgentError> {
+    let path = crate::services::system::logging::get_directory_path().await?;
+    Ok(HttpResponse::Ok().json(path))
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Open the log directory",
+    responses((status = 200, body = SuccessResponse))
+)]
 #[post("/api/open_log_directory")]
-async fn open_log() -> impl Responder {
-    match crate::services::system::logging::open_directory().await {
-        Ok(_) => HttpResponse::Ok().json(json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn open_log() -> Result<HttpResponse, AgentError> {
+    crate::services::system::logging::open_directory().await?;
+    Ok(HttpResponse::Ok().json(SuccessResponse::ok()))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct LaunchRequest {
     url: String,
 }
 
+#[utoipa::path(
+    tag = "system",
+    summary = "Launch Antigravity and install the VS Code extension",
+    request_body = LaunchRequest,
+    responses((status = 200, body = MessageResponse))
+)]
 #[post("/api/launch_and_install_extension")]
-async fn install_ext(req: web::Json<LaunchRequest>) -> impl Responder {
-    match crate::services::system::extension::launch_and_install(req.url.clone()).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({ "success": true, "message": msg })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
-    }
+async fn install_ext(req: web::Json<LaunchRequest>) -> Result<HttpResponse, AgentError> {
+    let msg = crate::services::system::extension::launch_and_install(req.url.clone()).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse::ok(msg)))
+}
+
+// =============================================================================
+// Diagnostics Service Endpoints
+// =============================================================================
+
+#[utoipa::path(
+    tag = "diagnostics",
+    summary = "Runtime status",
+    responses((status = 200, body = crate::services::diagnostics::HealthReport))
+)]
+#[get("/api/health")]
+async fn health(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let report = crate::services::diagnostics::health(&host).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    tag = "diagnostics",
+    summary = "Environment checks",
+    responses((status = 200, body = crate::services::diagnostics::DoctorReport))
+)]
+#[get("/api/doctor")]
+async fn doctor() -> Result<HttpResponse, AgentError> {
+    let report = crate::services::diagnostics::doctor().await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Prometheus 指标（文本格式），见 [`crate::telemetry`]
+#[utoipa::path(
+    tag = "diagnostics",
+    summary = "Prometheus metrics",
+    responses((status = 200, content_type = "text/plain", body = String))
+)]
+#[get("/metrics")]
+async fn prometheus_metrics() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type(crate::telemetry::content_type())
+        .body(crate::telemetry::render())
+}
+
+// =============================================================================
+// Remote Access Endpoints
+// =============================================================================
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Remote access status (local listeners only)",
+    responses((status = 200, body = crate::services::remote::RemoteAccessStatus))
+)]
+#[get("/api/get_remote_access_status")]
+async fn get_remote_status(host: web::Data<AppHost>) -> Result<HttpResponse, AgentError> {
+    let remote_status = crate::services::remote::status(&host).await?;
+    Ok(HttpResponse::Ok().json(remote_status))
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Enable or disable the remote listener (takes effect on restart)",
+    request_body = BoolStateRequest,
+    responses((status = 200, body = StateResponse))
+)]
+#[post("/api/save_remote_access_state")]
+async fn save_remote_access(
+    host: web::Data<AppHost>,
+    req: web::Json<BoolStateRequest>,
+) -> Result<HttpResponse, AgentError> {
+    let val = crate::services::settings::save_remote_access_state(&host, req.enabled).await?;
+    Ok(HttpResponse::Ok().json(StateResponse::ok(val)))
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Create a one-time pairing code for a new device",
+    responses((status = 200, body = crate::services::remote::PairingCode))
+)]
+#[post("/api/create_remote_pairing_code")]
+async fn create_pairing_code() -> Result<HttpResponse, AgentError> {
+    let code = crate::services::remote::create_pairing_code().await?;
+    Ok(HttpResponse::Ok().json(code))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct PairDeviceRequest {
+    /// Agent 窗口中显示的 6 位配对码
+    code: String,
+    /// 设备名（展示在设备列表中）
+    device_name: String,
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Pair a device with a pairing code (remote listener only, no token)",
+    request_body = PairDeviceRequest,
+    responses((status = 200, body = crate::services::remote::PairedDevice)),
+    security(())
+)]
+#[post("/api/pair_remote_device")]
+async fn pair_device(
+    http_req: actix_web::HttpRequest,
+    req: web::Json<PairDeviceRequest>,
+) -> Result<HttpResponse, AgentError> {
+    let peer = http_req.connection_info().peer_addr().map(str::to_string);
+    tracing::info!(target: "remote", peer = ?peer, "收到远程设备配对请求");
+    let paired = crate::services::remote::pair(&req.code, &req.device_name).await?;
+    Ok(HttpResponse::Ok().json(paired))
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "List paired devices",
+    responses((status = 200, body = Vec<crate::services::remote::RemoteDevice>))
+)]
+#[get("/api/get_remote_devices")]
+async fn get_remote_devices() -> Result<HttpResponse, AgentError> {
+    let devices = crate::services::remote::list_devices().await?;
+    Ok(HttpResponse::Ok().json(devices))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct DeviceScopeRequest {
+    device_id: String,
+    scope: auth::ApiScope,
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Grant a paired device read or admin scope",
+    request_body = DeviceScopeRequest,
+    responses((status = 200, body = crate::services::remote::RemoteDevice))
+)]
+#[post("/api/set_remote_device_scope")]
+async fn set_device_scope(req: web::Json<DeviceScopeRequest>) -> Result<HttpResponse, AgentError> {
+    let device = crate::services::remote::set_device_scope(&req.device_id, req.scope).await?;
+    Ok(HttpResponse::Ok().json(device))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct RevokeDeviceRequest {
+    device_id: String,
+}
+
+#[utoipa::path(
+    tag = "remote",
+    summary = "Revoke a paired device",
+    request_body = RevokeDeviceRequest,
+    responses((status = 200, body = SuccessResponse))
+)]
+#[post("/api/revoke_remote_device")]
+async fn revoke_device(req: web::Json<RevokeDeviceRequest>) -> Result<HttpResponse, AgentError> {
+    crate::services::remote::revoke_device(&req.device_id).await?;
+    Ok(HttpResponse::Ok().json(SuccessResponse::ok()))
 }
 
 // =============================================================================
 // Server Init
 // =============================================================================
 
+/// 远程监听器的工作线程数（局域网内少量设备）
+const REMOTE_WORKERS: usize = 2;
+
 /// 启动 HTTP 服务器
-pub fn init(app_handle: tauri::AppHandle, state: AppState) {
+///
+/// 监听地址由设置决定（TCP 端口、Unix socket 与可选的远程 TLS 端口），见 [`listen`]。
+/// 所有 `/api/*` 与 `/ws` 请求都需要携带 `tokens` 中的 Token，见 [`auth`]。
+/// 返回服务线程句柄；服务停止（如没有任何地址绑定成功）时线程结束。
+pub fn init(
+    host: AppHost,
+    state: AppState,
+    tokens: Arc<auth::ApiTokens>,
+) -> std::thread::JoinHandle<()> {
+    let config = listen::ListenConfig::resolve(&host.settings().get_settings(), host.is_headless());
+
     std::thread::spawn(move || {
         let sys = actix_web::rt::System::new();
 
         sys.block_on(async move {
-            let server = HttpServer::new(move || {
-                let cors = Cors::permissive();
-
+            let push_host = host.clone();
+            let app_factory = move || {
                 App::new()
-                    .wrap(cors)
+                    // 鉴权放在最内层，CORS 放在最外层以便预检请求和 401/403 响应也带上 CORS 头
+                    .wrap(auth::ApiAuth::new(tokens.clone()))
                     // 使用中间件统一处理 camelCase -> snake_case 参数名
                     .wrap(middleware::CamelCaseToSnakeCase)
+                    // 分配 X-Request-Id 并在 request span 中执行，鉴权失败的日志也能关联到请求
+                    .wrap(middleware::RequestIdTracing)
+                    .wrap(auth::cors())
                     .app_data(web::Data::new(state.clone()))
-                    .app_data(web::Data::new(app_handle.clone()))
+                    .app_data(web::Data::new(host.clone()))
                     // Account Service
                     .service(status)
                     .service(get_accounts)
@@ -521,6 +952,8 @@ pub fn init(app_handle: tauri::AppHandle, state: AppState) {
                     .service(sign_in_new)
                     .service(get_metrics)
                     .service(refresh_quota)
+                    .service(reveal_secrets)
+                    .service(update_metadata)
                     // Backup Service
                     .service(collect_backups)
                     .service(restore_backups)
@@ -532,6 +965,7 @@ pub fn init(app_handle: tauri::AppHandle, state: AppState) {
                     .service(save_silent_start)
                     .service(save_private_mode)
                     .service(save_debug_mode)
+                    .service(save_auto_capture)
                     .service(get_language)
                     .service(set_language)
                     // Platform Service
@@ -557,22 +991,112 @@ pub fn init(app_handle: tauri::AppHandle, state: AppState) {
                     .service(get_log_dir)
                     .service(open_log)
                     .service(install_ext)
+                    // Diagnostics Service
+                    .service(health)
+                    .service(doctor)
+                    .service(prometheus_metrics)
+                    .service(openapi::openapi_json)
+                    // Remote Access
+                    .service(get_remote_status)
+                    .service(save_remote_access)
+                    .service(create_pairing_code)
+                    .service(pair_device)
+                    .service(get_remote_devices)
+                    .service(set_device_scope)
+                    .service(revoke_device)
+                    // 已连接的扩展客户端
+                    .route("/api/extensions", web::get().to(websocket::list_extensions))
+                    // SSE 事件流
+                    .route("/api/events", web::get().to(events::events_handler))
+                    // MCP（Streamable HTTP），GET 等其他方法返回 405
+                    .route("/api/mcp", web::post().to(mcp::mcp_handler))
                     // WebSocket 路由
                     .route("/ws", web::get().to(websocket::ws_handler))
-            })
-            .bind(("127.0.0.1", 56789));
-
-            match server {
-                Ok(s) => {
-                    tracing::info!("HTTP Server starting on http://127.0.0.1:56789");
-                    if let Err(e) = s.run().await {
-                        tracing::error!("HTTP Server error: {}", e);
+            };
+            let mut server = HttpServer::new(app_factory.clone());
+
+            let mut addresses = Vec::new();
+
+            if let Some(port) = config.tcp_port {
+                let bound = std::net::TcpListener::bind(("127.0.0.1", port))
+                    .and_then(|listener| server.listen(listener));
+                match bound {
+                    Ok(s) => {
+                        server = s;
+                        addresses.push(format!("http://127.0.0.1:{port}"));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to bind HTTP server port {}: {}", port, e);
+                        return;
                     }
                 }
-                Err(e) => {
-                    tracing::error!("Failed to bind HTTP server port 56789: {}", e);
+            }
+
+            #[cfg(unix)]
+            if let Some(path) = &config.unix_socket {
+                let bound =
+                    listen::bind_unix_socket(path).and_then(|listener| server.listen_uds(listener));
+                match bound {
+                    Ok(s) => {
+                        server = s;
+                        addresses.push(format!("unix:{}", path.display()));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to bind unix socket {}: {}", path.display(), e);
+                        return;
+                    }
                 }
             }
+
+            // 远程监听器使用独立的 HttpServer：证书、绑定或启动失败只影响远程访问，不影响本地 API
+            let remote_server = config.remote_port.and_then(|port| {
+                let started = tls::server_config().and_then(|tls_config| {
+                    let listener = std::net::TcpListener::bind(("0.0.0.0", port)).map_err(|e| {
+                        AgentError::from_io(e, format!("Failed to bind port {port}"))
+                    })?;
+                    HttpServer::new(app_factory)
+                        .workers(REMOTE_WORKERS)
+                        .listen_rustls_0_23(listener, tls_config)
+                        .map_err(|e| {
+                            AgentError::from_io(
+                                e,
+                                format!("Failed to start remote listener on port {port}"),
+                            )
+                        })
+                });
+                match started {
+                    Ok(remote) => {
+                        addresses.push(format!("https://0.0.0.0:{port}"));
+                        crate::services::remote::record_listening(port);
+                        Some(remote.run())
+                    }
+                    Err(e) => {
+                        tracing::error!("Remote access is unavailable: {}", e);
+                        None
+                    }
+                }
+            });
+
+            tracing::info!("HTTP Server starting on {}", addresses.join(", "));
+            crate::services::diagnostics::record_listeners(addresses);
+            #[cfg(target_os = "linux")]
+            dbus::spawn(push_host.clone());
+            push::spawn(push_host);
+            if let Some(remote_server) = remote_server {
+                actix_web::rt::spawn(async move {
+                    if let Err(e) = remote_server.await {
+                        tracing::error!("Remote listener error: {}", e);
+                    }
+                });
+            }
+            if let Err(e) = server.run().await {
+                tracing::error!("HTTP Server error: {}", e);
+            }
+
+            #[cfg(unix)]
+            if let Some(path) = &config.unix_socket {
+                let _ = std::fs::remove_file(path);
+            }
         });
-    });
+    })
 }
@@ -0,0 +1,172 @@
+//! # OpenAPI 文档
+//!
+//! 由处理器上的 `#[utoipa::path]` 与请求/响应类型的 `ToSchema` 生成 OpenAPI 3 文档，
+//! 通过 `GET /api/openapi.json` 提供，供扩展、内部工具和第三方客户端生成类型化客户端。
+//!
+//! 新增路由时需要同时在处理器上添加 `#[utoipa::path]`，并加入 [`ApiDoc`] 的 `paths(...)`。
+//!
+//! 所有接口共用的约定（Bearer 鉴权、错误响应体）由 [`ApiConventions`] 统一补充，
+//! 不必在每个处理器上重复声明。
+
+use crate::error::ErrorResponse;
+use actix_web::{get, HttpResponse};
+use utoipa::openapi::content::ContentBuilder;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{Ref, RefOr, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+/// 本地 API 的 OpenAPI 文档
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Antigravity Agent local API",
+        description = "Local API of Antigravity Agent, served on 127.0.0.1 and ~/.antigravity-agent/agent.sock.\n\n\
+            Every request needs `Authorization: Bearer <token>` (tokens are in `~/.antigravity-agent/api_tokens.json`). \
+            Request body keys are snake_case; camelCase keys are converted before reaching the handlers. \
+            Each response carries an `X-Request-Id` header that also appears in the agent logs. \
+            When remote access is enabled, the same API is served over TLS on the LAN and accepts paired device tokens only."
+    ),
+    paths(
+        // Accounts
+        super::status,
+        super::get_accounts,
+        super::get_current_account,
+        super::save_current_account,
+        super::restore_account,
+        super::switch_account,
+        super::clear_data,
+        super::sign_in_new,
+        super::get_metrics,
+        super::refresh_quota,
+        super::reveal_secrets,
+        super::update_metadata,
+        // Backups
+        super::collect_backups,
+        super::restore_backups,
+        super::delete_backup,
+        super::clear_backups,
+        // Settings
+        super::get_all_settings,
+        super::save_tray_state,
+        super::save_silent_start,
+        super::save_private_mode,
+        super::save_debug_mode,
+        super::save_auto_capture,
+        super::get_language,
+        super::set_language,
+        // Platform
+        super::get_platform_info,
+        super::find_installations,
+        super::validate_executable,
+        super::detect_installation,
+        super::detect_executable,
+        super::save_executable,
+        super::get_paths,
+        // Crypto
+        super::encrypt_data,
+        super::decrypt_data,
+        // System
+        super::update_tray,
+        super::minimize_tray,
+        super::restore_tray,
+        super::is_db_monitor,
+        super::start_db_monitor,
+        super::stop_db_monitor,
+        super::write_file,
+        super::write_log,
+        super::get_log_dir,
+        super::open_log,
+        super::install_ext,
+        // Diagnostics
+        super::health,
+        super::doctor,
+        super::prometheus_metrics,
+        openapi_json,
+        // Remote access
+        super::get_remote_status,
+        super::save_remote_access,
+        super::create_pairing_code,
+        super::pair_device,
+        super::get_remote_devices,
+        super::set_device_scope,
+        super::revoke_device,
+        // Extensions & events
+        super::websocket::list_extensions,
+        super::websocket::ws_handler,
+        super::events::events_handler,
+        // MCP
+        super::mcp::mcp_handler,
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&ApiConventions),
+    tags(
+        (name = "accounts", description = "Account backup, switching and quota"),
+        (name = "backups", description = "Export and import of account backups"),
+        (name = "settings", description = "Application settings"),
+        (name = "platform", description = "Antigravity installation detection"),
+        (name = "crypto", description = "Encryption of exported configuration"),
+        (name = "system", description = "Tray, database monitor, logs and extension installation"),
+        (name = "diagnostics", description = "Health, environment checks, metrics and this document"),
+        (name = "remote", description = "LAN remote access over TLS and device pairing"),
+        (name = "extensions", description = "VS Code extension connections"),
+        (name = "events", description = "Agent event stream"),
+        (name = "mcp", description = "Model Context Protocol server for AI agents"),
+    )
+)]
+pub struct ApiDoc;
+
+/// 所有接口共用的约定
+struct ApiConventions;
+
+impl Modify for ApiConventions {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        openapi.security = Some(vec![SecurityRequirement::new(
+            "bearer",
+            Vec::<String>::new(),
+        )]);
+
+        // 处理器返回的 AgentError 统一序列化为 ErrorResponse
+        let error = ResponseBuilder::new()
+            .description("Error (`code` identifies the failure)")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ErrorResponse")))
+                    .build(),
+            )
+            .build();
+        for item in openapi.paths.paths.values_mut() {
+            for operation in [&mut item.get, &mut item.post].into_iter().flatten() {
+                operation
+                    .responses
+                    .responses
+                    .entry("default".to_string())
+                    .or_insert_with(|| RefOr::T(error.clone()));
+            }
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 文档在进程内不会变化，只生成一次
+    static ref DOCUMENT: String = ApiDoc::openapi()
+        .to_json()
+        .expect("OpenAPI document is serializable");
+}
+
+#[utoipa::path(
+    tag = "diagnostics",
+    summary = "OpenAPI 3 description of this API",
+    responses((status = 200, body = Object))
+)]
+#[get("/api/openapi.json")]
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(DOCUMENT.as_str())
+}
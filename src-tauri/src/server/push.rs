@@ -0,0 +1,86 @@
+//! # 主题推送
+//!
+//! 把事件总线上的 [`AgentEvent`] 转发给订阅了对应主题的扩展，
+//! 并在有扩展订阅 `quotas` 时定期刷新当前账户的配额，
+//! 让状态栏和面板由 Agent 主动推送更新，而不是各自定时轮询。
+
+use super::websocket::{broadcast_event, CONNECTION_MANAGER};
+use crate::events::{AgentEvent, Topic};
+use crate::host::AppHost;
+use crate::services::account::Redaction;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// 配额刷新间隔
+const QUOTA_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动事件转发与配额刷新任务
+///
+/// 需要在 Actix System 内调用（与 HTTP Server 共用运行时）。
+pub fn spawn(host: AppHost) {
+    actix_web::rt::spawn(forward_events(host));
+    actix_web::rt::spawn(watch_quotas());
+}
+
+/// 将事件总线上的事件按主题推送给扩展（按隐私模式脱敏）
+async fn forward_events(host: AppHost) {
+    let mut receiver = crate::events::subscribe();
+    loop {
+        match receiver.recv().await {
+            Ok(event) => broadcast_event(&event, Redaction::for_host(&host)),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(target: "server::push", skipped, "扩展事件转发过慢，已丢弃事件");
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+/// 定期刷新当前账户配额，账户变化时立即刷新
+///
+/// 没有扩展订阅 `quotas` 时跳过，不产生任何网络请求。
+async fn watch_quotas() {
+    let mut receiver = crate::events::subscribe();
+    let mut interval = tokio::time::interval(QUOTA_POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            result = receiver.recv() => match result {
+                Ok(AgentEvent::AccountChanged { .. }) => interval.reset(),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        }
+
+        if !CONNECTION_MANAGER.has_subscribers(Topic::Quotas) {
+            continue;
+        }
+
+        if let Err(e) = publish_current_quota().await {
+            tracing::debug!(target: "server::push", error = %e, "刷新当前账户配额失败");
+        }
+    }
+}
+
+/// 获取当前账户配额并发布 `QuotaUpdated`（邮箱在推送给订阅者时脱敏）
+async fn publish_current_quota() -> Result<(), crate::error::AgentError> {
+    let current = crate::services::account::get_current().await?;
+    let Some(email) = current
+        .antigravity_auth_status
+        .get("email")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+    else {
+        return Ok(());
+    };
+
+    let config_dir = crate::directories::get_config_directory();
+    let metrics = crate::services::account::get_metrics(&config_dir, email.clone()).await?;
+    crate::events::publish(AgentEvent::QuotaUpdated {
+        account_id: current.account_id,
+        email,
+        metrics,
+    });
+    Ok(())
+}
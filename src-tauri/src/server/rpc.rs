@@ -0,0 +1,350 @@
+//! # JSON-RPC 2.0 协议
+//!
+//! `/ws` 连接上的所有消息都遵循 [JSON-RPC 2.0](https://www.jsonrpc.org/specification)，
+//! 同一条连接同时承载两个方向的调用：
+//!
+//! - Agent → 扩展：`reloadWindow` 等扩展注册的方法、`event` 通知
+//! - 扩展 → Agent：[`AGENT_METHODS`] 中列出的账户操作
+//!
+//! ## 消息格式
+//!
+//! ```json
+//! // 请求
+//! {"jsonrpc": "2.0", "id": 1, "method": "accounts.current"}
+//!
+//! // 成功响应
+//! {"jsonrpc": "2.0", "id": 1, "result": {...}}
+//!
+//! // 错误响应（data.code 与 HTTP API 的错误码一致）
+//! {"jsonrpc": "2.0", "id": 1, "error": {"code": -32004, "message": "...", "data": {"code": "not_found"}}}
+//!
+//! // 通知（没有 id，不需要响应）
+//! {"jsonrpc": "2.0", "method": "event", "params": {"name": "account_changed", "data": {...}}}
+//! ```
+//!
+//! 批量调用使用 JSON 数组，响应同样以数组返回（通知不产生响应）。
+
+use crate::error::AgentError;
+use crate::server::auth::ApiScope;
+use crate::services::account::Redaction;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+/// JSON-RPC 版本号
+pub const JSONRPC_VERSION: &str = "2.0";
+
+// =============================================================================
+// 消息类型
+// =============================================================================
+
+/// 请求 ID，规范允许数字或字符串
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcId {
+    Number(i64),
+    String(String),
+}
+
+/// 请求或通知
+///
+/// `id` 为空时表示通知，接收方不返回响应。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<RpcId>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    /// 构造需要响应的请求
+    pub fn new(id: RpcId, method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// 构造通知
+    pub fn notification(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: None,
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// 响应
+///
+/// `result` 与 `error` 有且只有一个。无法确定请求 ID（如解析失败）时 `id` 为 `null`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Option<RpcId>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn success(id: Option<RpcId>, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Option<RpcId>, error: RpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// `"result": null` 也是合法的成功响应，需要与字段缺失区分开
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// 错误对象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn parse_error(message: impl Into<String>) -> Self {
+        Self::new(Self::PARSE_ERROR, message)
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_REQUEST, message)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            Self::METHOD_NOT_FOUND,
+            format!("Method not found: {method}"),
+        )
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+}
+
+/// 业务错误映射到 JSON-RPC 服务端错误区间（-32000 ~ -32099），
+/// `data.code` 保留 [`AgentError::code`]，与 HTTP API 一致
+impl From<AgentError> for RpcError {
+    fn from(error: AgentError) -> Self {
+        let code = match error {
+            AgentError::InvalidInput(_) => Self::INVALID_PARAMS,
+            AgentError::Unauthorized(_) => -32001,
+            AgentError::Upstream(_) => -32002,
+            AgentError::Forbidden(_) => -32003,
+            AgentError::NotFound(_) => -32004,
+            AgentError::Timeout(_) => -32008,
+            AgentError::AntigravityRunning(_) => -32009,
+            AgentError::DbLocked(_) => -32010,
+            AgentError::Unavailable(_) => -32011,
+            AgentError::PathNotAllowed(_) => -32012,
+            AgentError::Internal(_) => Self::INTERNAL_ERROR,
+        };
+        Self {
+            code,
+            message: error.message().to_string(),
+            data: Some(json!({ "code": error.code() })),
+        }
+    }
+}
+
+/// 收到的一条消息
+#[derive(Debug)]
+pub enum RpcMessage {
+    Request(RpcRequest),
+    Response(RpcResponse),
+}
+
+impl RpcMessage {
+    /// 从 JSON 值中识别请求或响应
+    pub fn from_value(value: Value) -> Result<Self, RpcError> {
+        let Some(object) = value.as_object() else {
+            return Err(RpcError::invalid_request("Message must be a JSON object"));
+        };
+
+        if object.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+            return Err(RpcError::invalid_request(
+                "Missing or unsupported jsonrpc version",
+            ));
+        }
+
+        if object.contains_key("method") {
+            serde_json::from_value(value)
+                .map(RpcMessage::Request)
+                .map_err(|e| RpcError::invalid_request(format!("Invalid request: {e}")))
+        } else if object.contains_key("result") || object.contains_key("error") {
+            serde_json::from_value(value)
+                .map(RpcMessage::Response)
+                .map_err(|e| RpcError::invalid_request(format!("Invalid response: {e}")))
+        } else {
+            Err(RpcError::invalid_request(
+                "Message is neither a request nor a response",
+            ))
+        }
+    }
+
+    /// 请求 ID（用于给无效请求回复错误）
+    pub fn id_of(value: &Value) -> Option<RpcId> {
+        value
+            .get("id")
+            .and_then(|id| serde_json::from_value(id.clone()).ok())
+    }
+}
+
+// =============================================================================
+// Agent 方法
+// =============================================================================
+
+/// 扩展可调用的 Agent 方法及所需作用域
+pub const AGENT_METHODS: [(&str, ApiScope); 6] = [
+    ("accounts.list", ApiScope::Read),
+    ("accounts.current", ApiScope::Read),
+    ("accounts.switch", ApiScope::Admin),
+    ("accounts.backup_current", ApiScope::Admin),
+    ("metrics.get", ApiScope::Read),
+    ("quota.refresh", ApiScope::Admin),
+];
+
+#[derive(Deserialize)]
+struct AccountNameParams {
+    account_name: String,
+}
+
+#[derive(Deserialize)]
+struct EmailParams {
+    email: String,
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::invalid_params(e.to_string()))
+}
+
+/// 执行一次 Agent 方法调用
+///
+/// 通知（没有 `id`）同样会执行，但返回 `None`。返回的账户信息按 `redaction` 脱敏。
+pub async fn dispatch(
+    request: RpcRequest,
+    scope: ApiScope,
+    redaction: Redaction,
+) -> Option<RpcResponse> {
+    let id = request.id.clone();
+    let method = request.method.clone();
+
+    tracing::debug!(target: "server::rpc", method = %method, "收到扩展 RPC 调用");
+    let outcome = call_agent_method(&method, request.params, scope, redaction).await;
+    if let Err(error) = &outcome {
+        tracing::warn!(target: "server::rpc", method = %method, code = error.code, error = %error.message, "扩展 RPC 调用失败");
+    }
+
+    let id = id?;
+    Some(match outcome {
+        Ok(result) => RpcResponse::success(Some(id), result),
+        Err(error) => RpcResponse::failure(Some(id), error),
+    })
+}
+
+/// 将服务层返回值序列化为 `result`
+fn to_value<T: Serialize>(value: Result<T, AgentError>) -> Result<Value, RpcError> {
+    serde_json::to_value(value?).map_err(|e| RpcError::new(RpcError::INTERNAL_ERROR, e.to_string()))
+}
+
+async fn call_agent_method(
+    method: &str,
+    params: Value,
+    scope: ApiScope,
+    redaction: Redaction,
+) -> Result<Value, RpcError> {
+    let required = AGENT_METHODS
+        .iter()
+        .find(|(name, _)| *name == method)
+        .map(|(_, scope)| *scope)
+        .ok_or_else(|| RpcError::method_not_found(method))?;
+
+    if !scope.allows(required) {
+        return Err(AgentError::Forbidden(format!("Token scope does not allow '{method}'")).into());
+    }
+
+    let config_dir = crate::directories::get_config_directory();
+
+    match method {
+        "accounts.list" => to_value(
+            crate::services::account::get_all(&config_dir)
+                .await
+                .map(|accounts| redaction.accounts(accounts)),
+        ),
+        "accounts.current" => to_value(
+            crate::services::account::get_current()
+                .await
+                .map(|account| redaction.account(account)),
+        ),
+        "accounts.switch" => {
+            let params: AccountNameParams = parse_params(params)?;
+            to_value(crate::services::account::switch(params.account_name).await)
+        }
+        "accounts.backup_current" => to_value(crate::services::account::backup_current().await),
+        "metrics.get" => {
+            let params: EmailParams = parse_params(params)?;
+            to_value(
+                crate::services::account::get_metrics(&config_dir, params.email)
+                    .await
+                    .map(|metrics| redaction.metrics(metrics)),
+            )
+        }
+        "quota.refresh" => {
+            let params: EmailParams = parse_params(params)?;
+            to_value(
+                crate::services::account::trigger_quota_refresh(&config_dir, params.email)
+                    .await
+                    .map(|result| redaction.trigger_result(result)),
+            )
+        }
+        _ => Err(RpcError::method_not_found(method)),
+    }
+}
@@ -0,0 +1,69 @@
+//! # 通用响应体
+//!
+//! 多个接口共用的响应结构。处理器直接序列化这些类型，OpenAPI 文档也引用同一组类型，
+//! 因此文档中的响应形状与实际返回保持一致。
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// `{ "success": true }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuccessResponse {
+    pub success: bool,
+}
+
+impl SuccessResponse {
+    pub fn ok() -> Self {
+        Self { success: true }
+    }
+}
+
+/// `{ "success": true, "message": "..." }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+impl MessageResponse {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+        }
+    }
+}
+
+/// 开关类设置保存后的值：`{ "success": true, "value": true }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateResponse {
+    pub success: bool,
+    pub value: bool,
+}
+
+impl StateResponse {
+    pub fn ok(value: bool) -> Self {
+        Self {
+            success: true,
+            value,
+        }
+    }
+}
+
+/// `{ "language": "zh-CN" }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LanguageResponse {
+    pub language: String,
+}
+
+/// `{ "valid": true }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidResponse {
+    pub valid: bool,
+}
+
+/// 加密 / 解密结果：`{ "result": "..." }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CryptoResponse {
+    pub result: String,
+}
@@ -0,0 +1,84 @@
+//! # 远程监听器 TLS
+//!
+//! 首次开启远程访问时生成自签名证书，保存在配置目录的 `remote_tls_cert.pem` 与
+//! `remote_tls_key.pem`（私钥在 Unix 下权限 0600）。之后一直复用同一证书，指纹保持不变，
+//! 客户端可以固定（pin）该指纹；删除这两个文件并重启即可更换证书。
+//!
+//! 证书不由任何 CA 签发，客户端首次连接时应与 Agent 窗口中显示的 SHA-256 指纹比对。
+
+use crate::error::AgentError;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Arc;
+
+fn tls_error(context: &str, error: impl std::fmt::Display) -> AgentError {
+    AgentError::Internal(format!("{context}: {error}"))
+}
+
+/// 证书中的主机名（客户端按指纹校验，主机名仅供展示）
+fn subject_alt_names() -> Vec<String> {
+    let mut names = vec!["localhost".to_string()];
+    if let Some(host_name) = sysinfo::System::host_name() {
+        names.push(host_name);
+    }
+    names
+}
+
+/// 生成自签名证书并写入配置目录
+fn generate(cert_path: &Path, key_path: &Path) -> Result<(), AgentError> {
+    let rcgen::CertifiedKey { cert, signing_key } =
+        rcgen::generate_simple_self_signed(subject_alt_names())
+            .map_err(|e| tls_error("Failed to generate TLS certificate", e))?;
+
+    crate::utils::private_file::write(key_path, signing_key.serialize_pem())
+        .map_err(|e| AgentError::from_io(e, "Failed to write TLS private key"))?;
+    std::fs::write(cert_path, cert.pem())
+        .map_err(|e| AgentError::from_io(e, "Failed to write TLS certificate"))?;
+
+    tracing::info!(target: "server::tls", "已生成远程访问 TLS 证书");
+    Ok(())
+}
+
+/// 读取证书与私钥，不存在时生成
+fn load_or_create() -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>), AgentError> {
+    let cert_path = crate::directories::get_remote_tls_cert_file();
+    let key_path = crate::directories::get_remote_tls_key_file();
+    if !cert_path.exists() || !key_path.exists() {
+        generate(&cert_path, &key_path)?;
+    }
+
+    let cert = CertificateDer::from_pem_file(&cert_path)
+        .map_err(|e| tls_error("Invalid TLS certificate", e))?;
+    let key = PrivateKeyDer::from_pem_file(&key_path)
+        .map_err(|e| tls_error("Invalid TLS private key", e))?;
+    Ok((cert, key))
+}
+
+/// 远程监听器使用的 rustls 配置
+pub fn server_config() -> Result<rustls::ServerConfig, AgentError> {
+    let (cert, key) = load_or_create()?;
+    rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| {
+            builder
+                .with_no_client_auth()
+                .with_single_cert(vec![cert], key)
+        })
+        .map_err(|e| tls_error("Invalid TLS configuration", e))
+}
+
+/// 证书的 SHA-256 指纹（`AB:CD:...`），尚未生成证书时返回 `None`
+pub fn fingerprint() -> Option<String> {
+    let cert =
+        CertificateDer::from_pem_file(crate::directories::get_remote_tls_cert_file()).ok()?;
+    let digest = Sha256::digest(cert.as_ref());
+    Some(
+        digest
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
@@ -17,15 +17,23 @@
 //! │  自动连接 + 重连     │         │  多客户端管理               │
 //! │                     │         │                             │
 //! │  注册方法处理器      │◀───────│  RPC 调用 (reloadWindow等)   │
+//! │  调用 Agent 方法     │───────▶│  账户/配额操作 (services::*) │
 //! └─────────────────────┘         └─────────────────────────────┘
 //! ```
 //!
+//! 消息格式为 JSON-RPC 2.0，见 [`super::rpc`]。
+//!
 //! ## 核心功能
 //!
+//! - **握手与版本协商**: 连接后必须先发送 `hello`，携带扩展版本、协议版本、窗口/工作区信息和能力列表，
+//!   协议版本不兼容时以明确的关闭原因断开
 //! - **多客户端管理**: 支持多个 VSCode 实例同时连接，通过 `ConnectionManager` 统一管理
-//! - **RPC 调用**: Rust 可主动调用扩展注册的方法（如 `reloadWindow`）
+//! - **RPC 调用**: Rust 可主动调用扩展注册的方法（如 `reloadWindow`），
+//!   既支持发射后不管，也支持等待响应（`call_one` / `call_all_collect`）
+//! - **反向调用**: 扩展可以调用 Agent 方法（列出账户、切换、配额等），支持批量调用
 //! - **心跳检测**: 自动检测客户端断开，防止僵尸连接
 //! - **广播机制**: 一次调用可推送到所有连接的 VSCode 实例
+//! - **主题订阅**: 扩展通过 `subscribe` 订阅事件主题（[`Topic`]），只接收对应的 `event` 通知
 //!
 //! ## 使用示例
 //!
@@ -37,17 +45,30 @@
 //!     // 调用所有扩展的 reloadWindow 方法
 //!     call_all_extensions("reloadWindow", serde_json::json!({}));
 //! }
+//!
+//! // 等待每个扩展确认 reloadWindow 已执行
+//! let replies = CONNECTION_MANAGER
+//!     .call_all_collect("reloadWindow", serde_json::json!({}))
+//!     .await;
 //! ```
 
+use super::auth::{ApiScope, RemoteDeviceId};
+use super::rpc::{self, RpcError, RpcId, RpcMessage, RpcRequest, RpcResponse};
+use crate::error::AgentError;
+use crate::events::{AgentEvent, Topic};
+use crate::host::AppHost;
+use crate::services::account::Redaction;
 use actix::{Actor, ActorContext, Addr, AsyncContext, Handler, Message, StreamHandler};
-use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 // =============================================================================
@@ -64,89 +85,106 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 /// 如果客户端在此时间内没有响应心跳，则认为连接已断开。
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// 握手超时时间（秒）
+///
+/// 连接建立后必须在此时间内收到 `hello`，否则断开。
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 当前 WebSocket 协议版本
+///
+/// 消息格式发生不兼容变化时递增。
+///
+/// - 1: 自定义 `{"type": ...}` 消息
+/// - 2: JSON-RPC 2.0，支持扩展调用 Agent 方法
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// 仍然兼容的最低协议版本
+pub const MIN_PROTOCOL_VERSION: u32 = 2;
+
+/// 协议版本不兼容时使用的关闭码（4000-4999 为应用自定义区间）
+const CLOSE_CODE_INCOMPATIBLE_PROTOCOL: u16 = 4001;
+
+/// 未完成握手时使用的关闭码
+const CLOSE_CODE_HANDSHAKE_REQUIRED: u16 = 4002;
+
+/// 远程设备已被撤销时使用的关闭码
+const CLOSE_CODE_DEVICE_REVOKED: u16 = 4003;
+
+/// RPC 调用默认超时时间（秒）
+///
+/// `call_one` / `call_all_collect` 等待扩展响应的最长时间。
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 由 Session 自身处理的方法（需要知道调用方是哪个客户端）
+const SESSION_METHODS: [&str; 2] = ["subscribe", "unsubscribe"];
+
 // =============================================================================
 // 消息协议定义
 // =============================================================================
 
-/// RPC 请求消息
-///
-/// 从 Rust 发送到 VSCode 扩展，请求执行指定方法。
+/// 握手消息
 ///
-/// # 字段
+/// 扩展连接后发送的第一条消息（`hello` 请求的 `params`），描述连接背后的 Antigravity 窗口。
 ///
-/// - `id`: 请求唯一标识符，用于匹配响应
-/// - `method`: 要调用的方法名（如 "reloadWindow"）
-/// - `params`: 方法参数（JSON 格式）
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RpcRequest {
-    /// 请求唯一 ID（UUID v4）
-    pub id: String,
-    /// 方法名
-    pub method: String,
-    /// 方法参数
+/// ```json
+/// {"jsonrpc": "2.0", "id": 1, "method": "hello", "params": {"extension_version": "1.7.2", "protocol_version": 2, "window_id": "...", "capabilities": ["reloadWindow"]}}
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HelloMessage {
+    /// 扩展版本（`package.json` 中的 version）
+    pub extension_version: String,
+    /// 扩展实现的协议版本
+    pub protocol_version: u32,
+    /// 窗口标识（重载窗口后保持不变；同一工作区的多个窗口可能相同）
+    pub window_id: String,
+    /// 宿主应用名称（如 "Antigravity"）
+    #[serde(default)]
+    pub app_name: Option<String>,
+    /// 宿主应用版本
+    #[serde(default)]
+    pub app_version: Option<String>,
+    /// 工作区名称
     #[serde(default)]
-    pub params: Value,
+    pub workspace_name: Option<String>,
+    /// 工作区文件夹路径
+    #[serde(default)]
+    pub workspace_folders: Vec<String>,
+    /// 扩展支持的能力（已注册的 RPC 方法等）
+    #[serde(default)]
+    pub capabilities: Vec<String>,
 }
 
-/// RPC 响应消息
-///
-/// 从 VSCode 扩展返回到 Rust，包含方法执行结果。
-///
-/// # 字段
-///
-/// - `id`: 对应请求的 ID
-/// - `result`: 成功时的返回值
-/// - `error`: 失败时的错误信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RpcResponse {
-    /// 对应请求的 ID
-    pub id: String,
-    /// 成功时的返回值
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub result: Option<Value>,
-    /// 失败时的错误信息
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+impl HelloMessage {
+    /// 是否为声明了 `reloadWindow` 能力的 Antigravity 窗口
+    pub fn is_reloadable_antigravity_window(&self) -> bool {
+        let is_antigravity = self
+            .app_name
+            .as_deref()
+            .is_some_and(|name| name.to_ascii_lowercase().contains("antigravity"));
+        is_antigravity && self.capabilities.iter().any(|c| c == "reloadWindow")
+    }
 }
 
-/// WebSocket 消息类型枚举
-///
-/// 所有 WebSocket 通信使用此统一格式，通过 `type` 字段区分消息类型。
-///
-/// # JSON 格式示例
+/// 握手结果（`hello` 请求的 `result`）
+#[derive(Debug, Clone, Serialize)]
+pub struct WelcomeResult {
+    pub client_id: String,
+    pub protocol_version: u32,
+    pub agent_version: String,
+    /// 扩展可调用的 Agent 方法
+    pub methods: Vec<&'static str>,
+    /// 可订阅的事件主题
+    pub topics: Vec<Topic>,
+}
+
+/// `subscribe` / `unsubscribe` 的参数与返回值
 ///
 /// ```json
-/// // RPC 请求
-/// {"type": "rpc_request", "id": "xxx", "method": "reloadWindow", "params": {}}
-///
-/// // RPC 响应
-/// {"type": "rpc_response", "id": "xxx", "result": null}
-///
-/// // 事件通知
-/// {"type": "event", "name": "account_changed", "data": {"email": "..."}}
+/// {"jsonrpc": "2.0", "id": 2, "method": "subscribe", "params": {"topics": ["accounts", "quotas"]}}
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum WsMessage {
-    /// RPC 请求（Rust → 扩展）
-    #[serde(rename = "rpc_request")]
-    RpcRequest(RpcRequest),
-
-    /// RPC 响应（扩展 → Rust）
-    #[serde(rename = "rpc_response")]
-    RpcResponse(RpcResponse),
-
-    /// 事件通知（单向广播，不需要响应）
-    #[serde(rename = "event")]
-    Event { name: String, data: Value },
-
-    /// 心跳 Ping
-    #[serde(rename = "ping")]
-    Ping,
-
-    /// 心跳 Pong
-    #[serde(rename = "pong")]
-    Pong,
+pub struct TopicsParams {
+    pub topics: Vec<Topic>,
 }
 
 // =============================================================================
@@ -159,15 +197,38 @@ pub enum WsMessage {
 #[derive(Clone)]
 pub struct ExtensionClient {
     /// 客户端唯一标识符
-    #[allow(dead_code)]
     pub id: String,
     /// Actor 地址，用于发送消息
     pub addr: Addr<WsSession>,
+    /// 握手时上报的信息
+    pub hello: HelloMessage,
+    /// 握手完成时间
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+    /// 已订阅的事件主题
+    pub topics: HashSet<Topic>,
+}
+
+/// 对外暴露的扩展客户端信息（`GET /api/extensions`）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExtensionClientInfo {
+    pub client_id: String,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+    pub topics: Vec<Topic>,
+    #[serde(flatten)]
+    pub hello: HelloMessage,
+}
+
+/// 等待响应的 RPC 调用
+struct PendingCall {
+    /// 目标客户端 ID，客户端断开时据此清理
+    client_id: String,
+    /// 收到响应后通过此通道唤醒调用方
+    sender: oneshot::Sender<RpcResponse>,
 }
 
 /// 全局连接管理器
 ///
-/// 负责管理所有已连接的 VSCode 扩展客户端。
+/// 负责管理所有已连接的 VSCode 扩展客户端，以及等待响应的 RPC 调用。
 /// 使用 `RwLock` 保证线程安全，支持并发读取和互斥写入。
 ///
 /// # 设计说明
@@ -178,6 +239,8 @@ pub struct ExtensionClient {
 pub struct ConnectionManager {
     /// 客户端映射表：client_id -> ExtensionClient
     clients: RwLock<HashMap<String, ExtensionClient>>,
+    /// 等待响应的调用：request_id -> PendingCall
+    pending: Mutex<HashMap<String, PendingCall>>,
 }
 
 impl ConnectionManager {
@@ -185,26 +248,63 @@ impl ConnectionManager {
     pub fn new() -> Self {
         Self {
             clients: RwLock::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
         }
     }
 
     /// 注册新客户端
     ///
-    /// 当 VSCode 扩展建立 WebSocket 连接时调用。
+    /// 当 VSCode 扩展完成 `hello` 握手后调用。
     ///
     /// # 参数
     ///
     /// - `id`: 客户端唯一标识符（UUID）
     /// - `addr`: WebSocket Session Actor 的地址
-    pub fn register(&self, id: String, addr: Addr<WsSession>) {
+    /// - `hello`: 握手信息
+    pub fn register(&self, id: String, addr: Addr<WsSession>, hello: HelloMessage) {
         let mut clients = self.clients.write();
-        clients.insert(id.clone(), ExtensionClient { id, addr });
+        tracing::info!(
+            client_id = %id,
+            extension_version = %hello.extension_version,
+            protocol_version = hello.protocol_version,
+            window_id = %hello.window_id,
+            workspace = ?hello.workspace_name,
+            "扩展握手完成"
+        );
+        clients.insert(
+            id.clone(),
+            ExtensionClient {
+                id,
+                addr,
+                hello,
+                connected_at: chrono::Utc::now(),
+                topics: HashSet::new(),
+            },
+        );
         tracing::info!(client_count = clients.len(), "WebSocket 客户端已连接");
+        crate::telemetry::record_websocket_connected(clients.len());
+    }
+
+    /// 列出所有已完成握手的客户端
+    pub fn list_clients(&self) -> Vec<ExtensionClientInfo> {
+        let mut list: Vec<ExtensionClientInfo> = self
+            .clients
+            .read()
+            .values()
+            .map(|client| ExtensionClientInfo {
+                client_id: client.id.clone(),
+                connected_at: client.connected_at,
+                topics: sorted_topics(&client.topics),
+                hello: client.hello.clone(),
+            })
+            .collect();
+        list.sort_by_key(|client| client.connected_at);
+        list
     }
 
     /// 移除客户端
     ///
-    /// 当 WebSocket 连接断开时调用。
+    /// 当 WebSocket 连接断开时调用。发往该客户端且仍在等待的调用会立即失败。
     ///
     /// # 参数
     ///
@@ -213,6 +313,63 @@ impl ConnectionManager {
         let mut clients = self.clients.write();
         clients.remove(id);
         tracing::info!(client_count = clients.len(), "WebSocket 客户端已断开");
+        crate::telemetry::record_websocket_disconnected(clients.len());
+        drop(clients);
+
+        // 丢弃 sender 即可让等待方收到 RecvError
+        self.pending.lock().retain(|_, call| call.client_id != id);
+    }
+
+    /// 修改客户端的主题订阅，返回修改后的订阅列表
+    ///
+    /// # 参数
+    ///
+    /// - `id`: 客户端 ID
+    /// - `topics`: 要订阅或取消订阅的主题
+    /// - `subscribe`: `true` 为订阅，`false` 为取消订阅
+    pub fn update_topics(
+        &self,
+        id: &str,
+        topics: &[Topic],
+        subscribe: bool,
+    ) -> Result<Vec<Topic>, AgentError> {
+        let mut clients = self.clients.write();
+        let client = clients
+            .get_mut(id)
+            .ok_or_else(|| AgentError::NotFound(format!("Extension client {id} not found")))?;
+        for topic in topics {
+            if subscribe {
+                client.topics.insert(*topic);
+            } else {
+                client.topics.remove(topic);
+            }
+        }
+        tracing::debug!(client_id = %id, topics = ?client.topics, "扩展主题订阅已更新");
+        Ok(sorted_topics(&client.topics))
+    }
+
+    /// 是否有客户端订阅了指定主题
+    pub fn has_subscribers(&self, topic: Topic) -> bool {
+        self.clients
+            .read()
+            .values()
+            .any(|client| client.topics.contains(&topic))
+    }
+
+    /// 发送消息到订阅了指定主题的客户端
+    pub fn publish(&self, topic: Topic, message: &RpcRequest) {
+        let clients = self.clients.read();
+        let json = serde_json::to_string(message).unwrap();
+        for client in clients.values() {
+            if client.topics.contains(&topic) {
+                client.addr.do_send(TextMessage(json.clone()));
+            }
+        }
+    }
+
+    /// 获取所有已连接客户端的 ID
+    pub fn client_ids(&self) -> Vec<String> {
+        self.clients.read().keys().cloned().collect()
     }
 
     /// 获取当前连接的客户端数量
@@ -227,14 +384,47 @@ impl ConnectionManager {
         !self.clients.read().is_empty()
     }
 
+    /// 可热切换的窗口：声明了 `reloadWindow` 能力的 Antigravity 窗口，返回 `(client_id, window_id)`
+    pub fn reloadable_windows(&self) -> Vec<(String, String)> {
+        self.clients
+            .read()
+            .values()
+            .filter(|client| client.hello.is_reloadable_antigravity_window())
+            .map(|client| (client.id.clone(), client.hello.window_id.clone()))
+            .collect()
+    }
+
+    /// 指定窗口是否都在 `since` 之后重新完成了握手
+    ///
+    /// 同一工作区的窗口可能上报相同的 `window_id`，因此按次数匹配：
+    /// `window_ids` 中每出现一次，就需要一个 `since` 之后握手的不同连接。
+    pub fn windows_reconnected_since(
+        &self,
+        window_ids: &[String],
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        let mut expected: HashMap<&str, usize> = HashMap::new();
+        for window_id in window_ids {
+            *expected.entry(window_id.as_str()).or_default() += 1;
+        }
+        let clients = self.clients.read();
+        expected.into_iter().all(|(window_id, count)| {
+            clients
+                .values()
+                .filter(|client| client.hello.window_id == window_id && client.connected_at > since)
+                .count()
+                >= count
+        })
+    }
+
     /// 广播消息到所有已连接的客户端
     ///
     /// # 参数
     ///
-    /// - `message`: 要广播的 WebSocket 消息
-    pub fn broadcast(&self, message: WsMessage) {
+    /// - `message`: 要广播的请求或通知
+    pub fn broadcast(&self, message: &RpcRequest) {
         let clients = self.clients.read();
-        let json = serde_json::to_string(&message).unwrap();
+        let json = serde_json::to_string(message).unwrap();
         for client in clients.values() {
             client.addr.do_send(TextMessage(json.clone()));
         }
@@ -256,12 +446,154 @@ impl ConnectionManager {
     /// manager.call_all("reloadWindow", serde_json::json!({}));
     /// ```
     pub fn call_all(&self, method: &str, params: Value) {
-        let request = RpcRequest {
-            id: Uuid::new_v4().to_string(),
-            method: method.to_string(),
-            params,
+        let request = RpcRequest::new(RpcId::String(Uuid::new_v4().to_string()), method, params);
+        self.broadcast(&request);
+    }
+
+    /// 调用指定客户端的方法并等待响应（使用 [`DEFAULT_RPC_TIMEOUT`]）
+    ///
+    /// # 示例
+    ///
+    /// ```rust,ignore
+    /// let result = manager.call_one(&client_id, "reloadWindow", serde_json::json!({})).await?;
+    /// ```
+    pub async fn call_one(
+        &self,
+        client_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, AgentError> {
+        self.call_one_with_timeout(client_id, method, params, DEFAULT_RPC_TIMEOUT)
+            .await
+    }
+
+    /// 调用指定客户端的方法并等待响应
+    ///
+    /// # 错误
+    ///
+    /// - `NotFound`: 客户端未连接
+    /// - `Timeout`: 超时未收到响应
+    /// - `Upstream`: 扩展返回错误，或等待期间连接断开
+    pub async fn call_one_with_timeout(
+        &self,
+        client_id: &str,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, AgentError> {
+        let addr = self
+            .clients
+            .read()
+            .get(client_id)
+            .map(|client| client.addr.clone())
+            .ok_or_else(|| {
+                AgentError::NotFound(format!("Extension client '{client_id}' is not connected"))
+            })?;
+
+        let request_id = Uuid::new_v4().to_string();
+        let request = RpcRequest::new(RpcId::String(request_id.clone()), method, params);
+
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().insert(
+            request_id.clone(),
+            PendingCall {
+                client_id: client_id.to_string(),
+                sender,
+            },
+        );
+
+        let json = serde_json::to_string(&request)
+            .map_err(|e| AgentError::Internal(format!("Failed to encode RPC request: {e}")))?;
+        addr.do_send(TextMessage(json));
+
+        tracing::debug!(client_id = %client_id, request_id = %request_id, method = %method, "等待 RPC 响应");
+
+        let outcome = tokio::time::timeout(timeout, receiver).await;
+        // 超时或出错时确保清理等待表
+        self.pending.lock().remove(&request_id);
+
+        match outcome {
+            Err(_) => Err(AgentError::Timeout(format!(
+                "Extension client '{client_id}' did not answer '{method}' within {}s",
+                timeout.as_secs()
+            ))),
+            Ok(Err(_)) => Err(AgentError::Upstream(format!(
+                "Extension client '{client_id}' disconnected before answering '{method}'"
+            ))),
+            Ok(Ok(RpcResponse {
+                error: Some(error), ..
+            })) => Err(AgentError::Upstream(format!(
+                "Extension client '{client_id}' failed '{method}': {} ({})",
+                error.message, error.code
+            ))),
+            Ok(Ok(response)) => Ok(response.result.unwrap_or(Value::Null)),
+        }
+    }
+
+    /// 调用所有客户端的方法并收集每个客户端的结果（使用 [`DEFAULT_RPC_TIMEOUT`]）
+    pub async fn call_all_collect(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Vec<(String, Result<Value, AgentError>)> {
+        self.call_all_collect_with_timeout(method, params, DEFAULT_RPC_TIMEOUT)
+            .await
+    }
+
+    /// 调用所有客户端的方法并收集每个客户端的结果
+    ///
+    /// 所有调用并发进行，总耗时不超过 `timeout`。返回 `(client_id, 结果)` 列表，
+    /// 某个客户端失败不影响其他客户端。
+    pub async fn call_all_collect_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Vec<(String, Result<Value, AgentError>)> {
+        let calls = self.client_ids().into_iter().map(|client_id| {
+            let params = params.clone();
+            async move {
+                let result = self
+                    .call_one_with_timeout(&client_id, method, params, timeout)
+                    .await;
+                (client_id, result)
+            }
+        });
+        futures_util::future::join_all(calls).await
+    }
+
+    /// 将收到的响应交给等待中的调用方
+    ///
+    /// 只接受请求发往的客户端（`client_id`）的响应；其他客户端用同一 ID 回复时丢弃，调用保持等待。
+    /// 未知 ID（发射后不管的调用或已超时的调用）直接忽略。
+    fn resolve(&self, client_id: &str, response: RpcResponse) {
+        let call = {
+            let mut pending = self.pending.lock();
+            match &response.id {
+                Some(RpcId::String(id)) => match pending.get(id) {
+                    Some(call) if call.client_id == client_id => pending.remove(id),
+                    Some(call) => {
+                        tracing::warn!(
+                            client_id = %client_id,
+                            expected_client_id = %call.client_id,
+                            request_id = %id,
+                            "丢弃来自其他客户端的 RPC 响应"
+                        );
+                        return;
+                    }
+                    None => None,
+                },
+                _ => None,
+            }
         };
-        self.broadcast(WsMessage::RpcRequest(request));
+        match call {
+            Some(call) => {
+                let _ = call.sender.send(response);
+            }
+            None => {
+                tracing::debug!(request_id = ?response.id, "收到无人等待的 RPC 响应");
+            }
+        }
     }
 }
 
@@ -297,23 +629,262 @@ pub struct TextMessage(pub String);
 ///
 /// # 生命周期
 ///
-/// 1. 客户端连接 → `started()` → 注册到 `ConnectionManager`
-/// 2. 收发消息 → `handle()` 处理各种消息类型
-/// 3. 连接断开 → `stopped()` → 从 `ConnectionManager` 移除
+/// 1. 客户端连接 → `started()` → 启动心跳与握手超时
+/// 2. 收到 `hello` 请求 → 校验协议版本 → 注册到 `ConnectionManager`，返回 [`WelcomeResult`]
+/// 3. 收发消息 → `handle()` 分发请求、响应与批量消息
+/// 4. 连接断开 → `stopped()` → 从 `ConnectionManager` 移除
 pub struct WsSession {
     /// 客户端唯一 ID（UUID v4）
     id: String,
     /// 最后一次收到消息的时间戳，用于心跳超时检测
     hb: Instant,
+    /// 是否已完成握手
+    registered: bool,
+    /// 连接所用 Token 的作用域，决定可调用的 Agent 方法
+    scope: ApiScope,
+    /// 宿主环境，调用时读取隐私模式以脱敏返回的账户信息
+    host: AppHost,
+    /// 通过远程监听器连接的设备，心跳时重新读取其作用域
+    device_id: Option<String>,
 }
 
 impl WsSession {
     /// 创建新的 WebSocket Session
-    pub fn new() -> Self {
+    pub fn new(scope: ApiScope, host: AppHost) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
             hb: Instant::now(),
+            registered: false,
+            scope,
+            host,
+            device_id: None,
+        }
+    }
+
+    /// 标记为远程设备的连接
+    pub fn with_device(mut self, device_id: Option<String>) -> Self {
+        self.device_id = device_id;
+        self
+    }
+
+    /// 以指定关闭码和原因关闭连接
+    fn close_with(ctx: &mut ws::WebsocketContext<Self>, code: u16, description: String) {
+        ctx.close(Some(ws::CloseReason {
+            code: ws::CloseCode::Other(code),
+            description: Some(description),
+        }));
+        ctx.stop();
+    }
+
+    /// 发送可序列化的消息
+    fn send_json<T: Serialize>(ctx: &mut ws::WebsocketContext<Self>, message: &T) {
+        match serde_json::to_string(message) {
+            Ok(json) => ctx.text(json),
+            Err(e) => tracing::error!(error = %e, "无法序列化 WebSocket 消息"),
+        }
+    }
+
+    /// 协议版本不兼容时的关闭原因
+    fn incompatible_reason(protocol_version: u32) -> String {
+        format!(
+            "Incompatible protocol version {} (agent supports {}..={}); please update the {}",
+            protocol_version,
+            MIN_PROTOCOL_VERSION,
+            PROTOCOL_VERSION,
+            if protocol_version > PROTOCOL_VERSION {
+                "Antigravity Agent"
+            } else {
+                "extension"
+            }
+        )
+    }
+
+    /// 处理握手请求
+    fn handle_hello(&mut self, request: RpcRequest, ctx: &mut ws::WebsocketContext<Self>) {
+        if self.registered {
+            tracing::warn!(client_id = %self.id, "重复的 hello 消息，已忽略");
+            return;
+        }
+
+        let hello: HelloMessage = match serde_json::from_value(request.params) {
+            Ok(hello) => hello,
+            Err(e) => {
+                Self::send_json(
+                    ctx,
+                    &RpcResponse::failure(
+                        request.id,
+                        RpcError::invalid_params(format!("Invalid hello params: {e}")),
+                    ),
+                );
+                Self::close_with(
+                    ctx,
+                    CLOSE_CODE_HANDSHAKE_REQUIRED,
+                    "Handshake failed: invalid hello params".to_string(),
+                );
+                return;
+            }
+        };
+
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&hello.protocol_version) {
+            let reason = Self::incompatible_reason(hello.protocol_version);
+            tracing::warn!(
+                client_id = %self.id,
+                extension_version = %hello.extension_version,
+                protocol_version = hello.protocol_version,
+                "拒绝不兼容的扩展协议版本"
+            );
+            Self::send_json(
+                ctx,
+                &RpcResponse::failure(
+                    request.id,
+                    RpcError::new(RpcError::INVALID_REQUEST, reason.clone()),
+                ),
+            );
+            Self::close_with(ctx, CLOSE_CODE_INCOMPATIBLE_PROTOCOL, reason);
+            return;
+        }
+
+        self.registered = true;
+        CONNECTION_MANAGER.register(self.id.clone(), ctx.address(), hello);
+
+        let welcome = WelcomeResult {
+            client_id: self.id.clone(),
+            protocol_version: PROTOCOL_VERSION,
+            agent_version: env!("CARGO_PKG_VERSION").to_string(),
+            methods: rpc::AGENT_METHODS
+                .iter()
+                .map(|(name, _)| *name)
+                .chain(SESSION_METHODS)
+                .collect(),
+            topics: Topic::ALL.to_vec(),
+        };
+        if let Some(id) = request.id {
+            let result = serde_json::to_value(welcome).unwrap_or(Value::Null);
+            Self::send_json(ctx, &RpcResponse::success(Some(id), result));
+        }
+    }
+
+    /// 处理握手前收到的消息
+    ///
+    /// 只接受 `hello` 请求；v1 客户端的 `{"type": "hello"}` 会收到明确的版本不兼容关闭原因。
+    fn handle_unregistered(&mut self, value: Value, ctx: &mut ws::WebsocketContext<Self>) {
+        if value.get("type").and_then(Value::as_str) == Some("hello") {
+            let version = value
+                .get("protocol_version")
+                .and_then(Value::as_u64)
+                .unwrap_or(1) as u32;
+            tracing::warn!(client_id = %self.id, protocol_version = version, "拒绝旧版协议的扩展");
+            Self::close_with(
+                ctx,
+                CLOSE_CODE_INCOMPATIBLE_PROTOCOL,
+                Self::incompatible_reason(version),
+            );
+            return;
+        }
+
+        match RpcMessage::from_value(value) {
+            Ok(RpcMessage::Request(request)) if request.method == "hello" => {
+                self.handle_hello(request, ctx);
+            }
+            _ => {
+                tracing::warn!(client_id = %self.id, "握手前收到业务消息，断开连接");
+                Self::close_with(
+                    ctx,
+                    CLOSE_CODE_HANDSHAKE_REQUIRED,
+                    "Handshake required: send a hello request first".to_string(),
+                );
+            }
+        }
+    }
+
+    /// 处理单条或批量 JSON-RPC 消息
+    ///
+    /// 响应交给 `ConnectionManager` 唤醒等待方；请求异步执行后按原样（单条或数组）回复。
+    fn handle_rpc(&mut self, value: Value, ctx: &mut ws::WebsocketContext<Self>) {
+        let (items, is_batch) = match value {
+            Value::Array(items) if items.is_empty() => {
+                Self::send_json(
+                    ctx,
+                    &RpcResponse::failure(None, RpcError::invalid_request("Empty batch")),
+                );
+                return;
+            }
+            Value::Array(items) => (items, true),
+            single => (vec![single], false),
+        };
+
+        let mut requests = Vec::new();
+        // 无需异步执行、可以立即回复的响应（无效消息、Session 方法）
+        let mut immediate = Vec::new();
+        for item in items {
+            let id = RpcMessage::id_of(&item);
+            match RpcMessage::from_value(item) {
+                Ok(RpcMessage::Response(response)) => {
+                    tracing::debug!(request_id = ?response.id, "收到 RPC 响应");
+                    CONNECTION_MANAGER.resolve(&self.id, response);
+                }
+                Ok(RpcMessage::Request(request))
+                    if SESSION_METHODS.contains(&request.method.as_str()) =>
+                {
+                    immediate.extend(self.handle_session_method(request));
+                }
+                Ok(RpcMessage::Request(request)) => requests.push(request),
+                Err(error) => immediate.push(RpcResponse::failure(id, error)),
+            }
+        }
+
+        if requests.is_empty() && immediate.is_empty() {
+            return;
         }
+
+        let scope = self.scope;
+        let redaction = Redaction::for_host(&self.host);
+        let addr = ctx.address();
+        actix_web::rt::spawn(async move {
+            let calls = requests
+                .into_iter()
+                .map(|request| rpc::dispatch(request, scope, redaction));
+            let mut responses = immediate;
+            responses.extend(
+                futures_util::future::join_all(calls)
+                    .await
+                    .into_iter()
+                    .flatten(),
+            );
+
+            let json = if is_batch {
+                if responses.is_empty() {
+                    return;
+                }
+                serde_json::to_string(&responses)
+            } else {
+                match responses.pop() {
+                    Some(response) => serde_json::to_string(&response),
+                    None => return,
+                }
+            };
+            match json {
+                Ok(json) => addr.do_send(TextMessage(json)),
+                Err(e) => tracing::error!(error = %e, "无法序列化 RPC 响应"),
+            }
+        });
+    }
+
+    /// 处理 `subscribe` / `unsubscribe`
+    fn handle_session_method(&self, request: RpcRequest) -> Option<RpcResponse> {
+        let outcome = serde_json::from_value::<TopicsParams>(request.params)
+            .map_err(|e| RpcError::invalid_params(e.to_string()))
+            .and_then(|params| {
+                CONNECTION_MANAGER
+                    .update_topics(&self.id, &params.topics, request.method == "subscribe")
+                    .map_err(RpcError::from)
+            });
+
+        let id = request.id?;
+        Some(match outcome {
+            Ok(topics) => RpcResponse::success(Some(id), serde_json::json!({ "topics": topics })),
+            Err(error) => RpcResponse::failure(Some(id), error),
+        })
     }
 
     /// 启动心跳检测定时器
@@ -327,6 +898,21 @@ impl WsSession {
                 ctx.stop();
                 return;
             }
+            // 远程设备被撤销或作用域变化时，对已建立的连接同样生效
+            if let Some(device_id) = &act.device_id {
+                match crate::services::remote::device_scope(device_id) {
+                    Some(scope) => act.scope = scope,
+                    None => {
+                        tracing::warn!(client_id = %act.id, device_id = %device_id, "远程设备已撤销，断开 WebSocket");
+                        Self::close_with(
+                            ctx,
+                            CLOSE_CODE_DEVICE_REVOKED,
+                            "Device access has been revoked".to_string(),
+                        );
+                        return;
+                    }
+                }
+            }
             ctx.ping(b"");
         });
     }
@@ -339,15 +925,26 @@ impl Actor for WsSession {
     fn started(&mut self, ctx: &mut Self::Context) {
         // 启动心跳检测
         self.hb(ctx);
-        // 注册到全局连接管理器
-        CONNECTION_MANAGER.register(self.id.clone(), ctx.address());
+        // 握手超时：注册到全局连接管理器前必须先收到 hello
+        ctx.run_later(HELLO_TIMEOUT, |act, ctx| {
+            if !act.registered {
+                tracing::warn!(client_id = %act.id, "WebSocket 客户端未在规定时间内完成握手");
+                Self::close_with(
+                    ctx,
+                    CLOSE_CODE_HANDSHAKE_REQUIRED,
+                    "Handshake timeout: send a hello request first".to_string(),
+                );
+            }
+        });
         tracing::debug!(client_id = %self.id, "WebSocket Session 启动");
     }
 
     /// Session 停止时调用
     fn stopped(&mut self, _: &mut Self::Context) {
         // 从全局连接管理器移除
-        CONNECTION_MANAGER.unregister(&self.id);
+        if self.registered {
+            CONNECTION_MANAGER.unregister(&self.id);
+        }
         tracing::debug!(client_id = %self.id, "WebSocket Session 停止");
     }
 }
@@ -364,27 +961,25 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
             Ok(ws::Message::Pong(_)) => {
                 self.hb = Instant::now();
             }
-            // 文本消息（JSON 格式）
+            // 文本消息（JSON-RPC 2.0）
             Ok(ws::Message::Text(text)) => {
                 self.hb = Instant::now();
-                // 解析 JSON 消息
-                match serde_json::from_str::<WsMessage>(&text) {
-                    Ok(WsMessage::Pong) => {
-                        // 应用层心跳响应，已更新 hb
-                    }
-                    Ok(WsMessage::RpcResponse(response)) => {
-                        tracing::debug!(
-                            request_id = %response.id,
-                            "收到 RPC 响应"
-                        );
-                        // TODO: 如果需要同步等待响应，可在此处理
-                    }
-                    Ok(msg) => {
-                        tracing::debug!(?msg, "收到 WebSocket 消息");
-                    }
+                let value = match serde_json::from_str::<Value>(&text) {
+                    Ok(value) => value,
                     Err(e) => {
                         tracing::warn!(error = %e, "无法解析 WebSocket 消息");
+                        Self::send_json(
+                            ctx,
+                            &RpcResponse::failure(None, RpcError::parse_error(e.to_string())),
+                        );
+                        return;
                     }
+                };
+
+                if self.registered {
+                    self.handle_rpc(value, ctx);
+                } else {
+                    self.handle_unregistered(value, ctx);
                 }
             }
             Ok(ws::Message::Binary(_)) => {
@@ -425,12 +1020,67 @@ impl Handler<TextMessage> for WsSession {
 /// ```text
 /// GET ws://127.0.0.1:56789/ws
 /// ```
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "extensions",
+    summary = "JSON-RPC 2.0 WebSocket used by the VS Code extension",
+    params(("access_token" = Option<String>, Query, description = "API token (alternative to the Authorization header)")),
+    responses((status = 101, description = "Switching to the WebSocket protocol"))
+)]
 pub async fn ws_handler(
     req: HttpRequest,
     stream: web::Payload,
+    host: web::Data<AppHost>,
 ) -> Result<HttpResponse, actix_web::Error> {
     tracing::info!("新的 WebSocket 连接请求");
-    ws::start(WsSession::new(), &req, stream)
+    // 鉴权中间件已写入作用域；缺失时按只读处理
+    let scope = req
+        .extensions()
+        .get::<ApiScope>()
+        .copied()
+        .unwrap_or(ApiScope::Read);
+    let device_id = req
+        .extensions()
+        .get::<RemoteDeviceId>()
+        .map(|device| device.0.clone());
+    ws::start(
+        WsSession::new(scope, host.get_ref().clone()).with_device(device_id),
+        &req,
+        stream,
+    )
+}
+
+/// 列出已连接的扩展客户端
+///
+/// # 路由
+///
+/// ```text
+/// GET http://127.0.0.1:56789/api/extensions
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/extensions",
+    tag = "extensions",
+    summary = "Connected extension clients",
+    responses((status = 200, body = ExtensionListResponse))
+)]
+pub async fn list_extensions() -> HttpResponse {
+    HttpResponse::Ok().json(ExtensionListResponse {
+        protocol_version: PROTOCOL_VERSION,
+        min_protocol_version: MIN_PROTOCOL_VERSION,
+        clients: CONNECTION_MANAGER.list_clients(),
+    })
+}
+
+/// `GET /api/extensions` 的响应
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExtensionListResponse {
+    /// 服务端实现的协议版本
+    pub protocol_version: u32,
+    /// 服务端接受的最低协议版本
+    pub min_protocol_version: u32,
+    pub clients: Vec<ExtensionClientInfo>,
 }
 
 // =============================================================================
@@ -494,26 +1144,61 @@ pub fn call_all_extensions(method: &str, params: Value) {
     CONNECTION_MANAGER.call_all(method, params);
 }
 
-/// 广播事件到所有已连接的扩展
+/// 推送事件到订阅了对应主题的扩展
 ///
-/// 用于发送不需要响应的单向通知，如账户变更事件。
+/// 以 `event` 通知的形式发送，不需要响应：
 ///
-/// # 参数
+/// ```json
+/// {"jsonrpc": "2.0", "method": "event", "params": {"topic": "accounts", "name": "account_changed", "data": {...}}}
+/// ```
+pub fn broadcast_event(event: &AgentEvent, redaction: Redaction) {
+    let topic = event.topic();
+    CONNECTION_MANAGER.publish(
+        topic,
+        &RpcRequest::notification(
+            "event",
+            serde_json::json!({ "topic": topic, "name": event.name(), "data": redaction.event(event) }),
+        ),
+    );
+}
+
+/// 按固定顺序返回主题列表
+fn sorted_topics(topics: &HashSet<Topic>) -> Vec<Topic> {
+    Topic::ALL
+        .into_iter()
+        .filter(|topic| topics.contains(topic))
+        .collect()
+}
+
+/// 调用指定扩展的方法并等待响应
 ///
-/// - `name`: 事件名称
-/// - `data`: 事件数据（JSON 格式）
+/// 用于向某个 VS Code 窗口查询数据，或确认某个操作确实已执行。
 ///
 /// # 示例
 ///
 /// ```rust,ignore
-/// broadcast_event("account_changed", serde_json::json!({
-///     "email": "user@example.com"
-/// }));
+/// let result = call_extension(&client_id, "reloadWindow", serde_json::json!({})).await?;
 /// ```
-#[allow(dead_code)]
-pub fn broadcast_event(name: &str, data: Value) {
-    CONNECTION_MANAGER.broadcast(WsMessage::Event {
-        name: name.to_string(),
-        data,
-    });
+pub async fn call_extension(
+    client_id: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, AgentError> {
+    CONNECTION_MANAGER.call_one(client_id, method, params).await
+}
+
+/// 可热切换的 Antigravity 窗口，返回 `(client_id, window_id)`
+///
+/// 只包含宿主为 Antigravity 且声明了 `reloadWindow` 能力的扩展，
+/// 其他编辑器中的扩展不参与账户切换。
+pub fn reloadable_antigravity_windows() -> Vec<(String, String)> {
+    CONNECTION_MANAGER.reloadable_windows()
+}
+
+/// 指定窗口是否都在 `since` 之后重新连接并完成握手（相同 `window_id` 的窗口按次数匹配）
+pub fn windows_reconnected_since(
+    window_ids: &[String],
+    since: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    CONNECTION_MANAGER.windows_reconnected_since(window_ids, since)
 }
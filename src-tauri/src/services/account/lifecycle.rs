@@ -1,20 +1,29 @@
+use super::metadata;
+use super::redaction::strip_secrets;
 use super::storage::{
-    backup_file_modified_time, list_backup_json_files, load_current_raw_account_fields,
-    parse_backup_file, write_backup_file, RawAccountFields,
+    account_id, backup_file_modified_time, list_backup_json_files, load_current_raw_account_fields,
+    parse_backup_file, resolve_account_name, write_backup_file, RawAccountFields,
 };
 use super::types::{
     decode_oauth_token_to_struct, decode_user_status_to_struct, parse_auth_status_to_value,
     AntigravityAccountResponse, CommandResult,
 };
+use crate::antigravity::readiness;
+use crate::antigravity::snapshot::AuthSnapshot;
+use crate::error::AgentError;
+use crate::events::{self, AgentEvent, CaptureReason, SwitchStage};
+use crate::telemetry::SwitchResult;
 use serde_json::json;
 use std::path::Path;
+use std::time::Duration;
 
 fn parse_account_response(
     fields: &RawAccountFields,
     context: &str,
-) -> Result<AntigravityAccountResponse, String> {
-    let antigravity_auth_status = parse_auth_status_to_value(&fields.auth_status)
-        .map_err(|e| format!("Failed to parse auth status for {context}: {e}"))?;
+) -> Result<AntigravityAccountResponse, AgentError> {
+    let mut antigravity_auth_status = parse_auth_status_to_value(&fields.auth_status)
+        .map_err(|e| e.context(format!("Failed to parse auth status for {context}")))?;
+    strip_secrets(&mut antigravity_auth_status);
 
     let oauth_token = fields
         .oauth_token
@@ -23,7 +32,7 @@ fn parse_account_response(
         .filter(|raw| !raw.is_empty())
         .map(decode_oauth_token_to_struct)
         .transpose()
-        .map_err(|e| format!("Failed to decode oauth token for {context}: {e}"))?;
+        .map_err(|e| e.context(format!("Failed to decode oauth token for {context}")))?;
 
     let user_status = fields
         .user_status
@@ -32,29 +41,31 @@ fn parse_account_response(
         .filter(|raw| !raw.is_empty())
         .map(decode_user_status_to_struct)
         .transpose()
-        .map_err(|e| format!("Failed to decode user status for {context}: {e}"))?;
+        .map_err(|e| e.context(format!("Failed to decode user status for {context}")))?;
 
     Ok(AntigravityAccountResponse {
+        account_id: String::new(),
         antigravity_auth_status,
         oauth_token,
         user_status,
+        metadata: Default::default(),
     })
 }
 
-fn tolerant_kill_antigravity_processes() -> Result<String, String> {
+fn tolerant_kill_antigravity_processes() -> Result<String, AgentError> {
     match crate::platform::kill_antigravity_processes() {
         Ok(result) => Ok(result),
-        Err(error) => {
-            if error.contains("not found") || error.contains("未找到") {
-                Ok("Antigravity process not running".to_string())
-            } else {
-                Err(format!("Failed to stop Antigravity process: {error}"))
-            }
-        }
+        Err(AgentError::NotFound(_)) => Ok("Antigravity process not running".to_string()),
+        Err(error) => Err(error.context("Failed to stop Antigravity process")),
     }
 }
 
-pub async fn get_all(config_dir: &Path) -> Result<Vec<AntigravityAccountResponse>, String> {
+/// 停止进程后等待 Antigravity 完全退出、数据库解锁，避免在其运行时改写数据库
+async fn wait_for_antigravity_exit() -> Result<(), AgentError> {
+    readiness::wait_until_stopped(readiness::configured_timeout()).await
+}
+
+pub async fn get_all(config_dir: &Path) -> Result<Vec<AntigravityAccountResponse>, AgentError> {
     tracing::debug!("Starting account list load");
 
     let mut accounts_with_modified_time = Vec::new();
@@ -67,11 +78,24 @@ pub async fn get_all(config_dir: &Path) -> Result<Vec<AntigravityAccountResponse
             .unwrap_or("unknown")
             .to_string();
         let fields = parse_backup_file(&path)?;
-        let account = parse_account_response(&fields, &context)?;
+        let mut account = parse_account_response(&fields, &context)?;
+        // 备份文件名即账户名，切换、删除等接口按它定位备份
+        let account_name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        account.account_id = account_id(account_name);
+        account.metadata = metadata::load(config_dir, account_name);
         accounts_with_modified_time.push((backup_file_modified_time(&path), account));
     }
 
-    accounts_with_modified_time.sort_by(|a, b| b.0.cmp(&a.0));
+    // 置顶的账户在前，其余按备份时间倒序
+    accounts_with_modified_time.sort_by_key(|(modified, account)| {
+        (
+            std::cmp::Reverse(account.metadata.pinned),
+            std::cmp::Reverse(*modified),
+        )
+    });
 
     Ok(accounts_with_modified_time
         .into_iter()
@@ -79,13 +103,25 @@ pub async fn get_all(config_dir: &Path) -> Result<Vec<AntigravityAccountResponse
         .collect())
 }
 
-pub async fn get_current() -> Result<AntigravityAccountResponse, String> {
+pub async fn get_current() -> Result<AntigravityAccountResponse, AgentError> {
     tracing::debug!("Loading current account from database");
     let fields = load_current_raw_account_fields()?;
-    parse_account_response(&fields, "current database state")
+    let mut account = parse_account_response(&fields, "current database state")?;
+    // 与 `backup_current` 一致：当前账户备份后以邮箱为文件名
+    let email = account
+        .antigravity_auth_status
+        .get("email")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .unwrap_or_default();
+    account.account_id = account_id(email);
+    if !email.is_empty() {
+        account.metadata = metadata::load(&crate::directories::get_config_directory(), email);
+    }
+    Ok(account)
 }
 
-pub async fn backup_current() -> Result<CommandResult, String> {
+pub async fn backup_current() -> Result<CommandResult, AgentError> {
     tracing::info!("Backing up current Antigravity account");
     let fields = load_current_raw_account_fields()?;
     let auth_status = parse_auth_status_to_value(&fields.auth_status)?;
@@ -95,10 +131,23 @@ pub async fn backup_current() -> Result<CommandResult, String> {
         .and_then(|v| v.as_str())
         .map(str::trim)
         .filter(|email| !email.is_empty())
-        .ok_or_else(|| "Email is missing in antigravityAuthStatus".to_string())?;
+        .ok_or_else(|| {
+            AgentError::InvalidInput("Email is missing in antigravityAuthStatus".to_string())
+        })?;
 
     let account_file = write_backup_file(account_file_name, &fields)?;
     let message = format!("Account backup saved to {}", account_file.display());
+    if let Err(error) = metadata::record_created(
+        &crate::directories::get_config_directory(),
+        account_file_name,
+    ) {
+        tracing::warn!(error = %error.message(), "Failed to record account creation time");
+    }
+
+    events::publish(AgentEvent::BackupWritten {
+        account_name: account_file_name.to_string(),
+        file_path: account_file.display().to_string(),
+    });
 
     Ok(
         CommandResult::success("backup_saved", message).with_details(json!({
@@ -108,10 +157,56 @@ pub async fn backup_current() -> Result<CommandResult, String> {
     )
 }
 
-pub async fn clear_all_data() -> Result<CommandResult, String> {
+/// 数据库监控检测到变化后调用：当前邮箱没有备份、或登录令牌与备份不同时备份当前账户
+///
+/// 未登录、邮箱在排除列表中或备份已是最新时返回 `Ok(None)`。
+pub async fn auto_capture(
+    settings: &crate::app_settings::AppSettings,
+) -> Result<Option<(String, CaptureReason)>, AgentError> {
+    let fields = match load_current_raw_account_fields() {
+        Ok(fields) => fields,
+        Err(AgentError::NotFound(_)) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let auth_status = parse_auth_status_to_value(&fields.auth_status)?;
+    let Some(email) = auth_status
+        .get("email")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+    else {
+        return Ok(None);
+    };
+    if settings.is_auto_capture_excluded(email) {
+        tracing::debug!(target: "account::capture", "邮箱在排除列表中，跳过自动备份");
+        return Ok(None);
+    }
+
+    let backup =
+        crate::path_policy::account_file(&crate::directories::get_accounts_directory(), email)?;
+    let reason = if !backup.exists() {
+        CaptureReason::New
+    } else {
+        let saved = parse_backup_file(&backup)?;
+        if saved.auth_status == fields.auth_status && saved.oauth_token == fields.oauth_token {
+            return Ok(None);
+        }
+        CaptureReason::Refreshed
+    };
+
+    backup_current().await?;
+    tracing::info!(target: "account::capture", reason = ?reason, "已自动备份当前账户");
+    events::publish(AgentEvent::AccountCaptured {
+        account_name: email.to_string(),
+        reason,
+    });
+    Ok(Some((email.to_string(), reason)))
+}
+
+pub async fn clear_all_data() -> Result<CommandResult, AgentError> {
     let message = crate::antigravity::cleanup::clear_all_antigravity_data()
         .await
-        .map_err(|e| format!("Failed to clear Antigravity data: {e}"))?;
+        .map_err(|e| e.context("Failed to clear Antigravity data"))?;
 
     Ok(
         CommandResult::success("clear_all_data_success", "Cleared Antigravity data")
@@ -119,15 +214,37 @@ pub async fn clear_all_data() -> Result<CommandResult, String> {
     )
 }
 
-pub async fn restore(account_name: String) -> Result<CommandResult, String> {
-    tracing::info!(account_name = %account_name, "Restoring account backup");
-    let account_file =
-        crate::directories::get_accounts_directory().join(format!("{account_name}.json"));
+/// 将备份写入 Antigravity 数据库（不重启 Antigravity）
+///
+/// Antigravity 退出时会用内存中的状态覆盖数据库，运行中写入的账户会丢失，因此运行时直接拒绝；
+/// 需要在运行中切换账户时使用 [`switch`]。
+pub async fn restore(account_name: String) -> Result<CommandResult, AgentError> {
+    let account_name =
+        resolve_account_name(&crate::directories::get_config_directory(), &account_name);
+    if crate::platform::is_antigravity_running() {
+        return Err(AgentError::AntigravityRunning(
+            "Antigravity is running and would overwrite the restored account when it exits; close it first or use switch"
+                .to_string(),
+        ));
+    }
 
-    let restore_message =
-        crate::antigravity::restore::save_antigravity_account_to_file(account_file)
+    tracing::info!(account_name = %account_name, "Restoring account backup");
+    let account_file = crate::path_policy::account_file(
+        &crate::directories::get_accounts_directory(),
+        &account_name,
+    )?;
+
+    let snapshot = AuthSnapshot::capture()
+        .map_err(|e| e.context("Failed to snapshot current login before restore"))?;
+    let restored = async {
+        let message = crate::antigravity::restore::save_antigravity_account_to_file(account_file)
             .await
-            .map_err(|e| format!("Failed to restore account '{account_name}': {e}"))?;
+            .map_err(|e| e.context(format!("Failed to restore account '{account_name}'")))?;
+        verify_restored()?;
+        Ok(message)
+    }
+    .await;
+    let restore_message = restored.map_err(|error| roll_back(&snapshot, error))?;
 
     Ok(CommandResult::success(
         "restore_success",
@@ -136,51 +253,297 @@ pub async fn restore(account_name: String) -> Result<CommandResult, String> {
     .with_details(json!({ "restore_message": restore_message, "account_name": account_name })))
 }
 
-pub async fn switch(account_name: String) -> Result<CommandResult, String> {
-    tracing::info!(
-        target: "account::switch",
-        account_name = %account_name,
-        "Switching account using fixed process mode (scenario 3)"
+/// 确认账户数据已写入主库（恢复过程中单个键写入失败只记录日志）
+fn verify_restored() -> Result<(), AgentError> {
+    load_current_raw_account_fields()
+        .map(|_| ())
+        .map_err(|e| e.context("Account data was not written to the Antigravity database"))
+}
+
+/// 回滚到快照，返回带回滚结果说明的原始错误
+fn roll_back(snapshot: &AuthSnapshot, error: AgentError) -> AgentError {
+    match snapshot.restore() {
+        Ok(()) => error.context("Previous login was restored"),
+        Err(rollback_error) => {
+            tracing::error!(
+                target: "account::switch",
+                error = %rollback_error.message(),
+                "回滚登录数据失败"
+            );
+            error.context(format!(
+                "Rolling back to the previous login also failed ({})",
+                rollback_error.message()
+            ))
+        }
+    }
+}
+
+/// 发布切换进度事件
+fn report_switch_progress(account_name: &str, stage: SwitchStage, message: impl Into<String>) {
+    events::publish(AgentEvent::SwitchProgress {
+        account_name: account_name.to_string(),
+        stage,
+        message: message.into(),
+    });
+}
+
+#[tracing::instrument(name = "account_switch", skip_all, fields(account_name = %account_name))]
+pub async fn switch(account_name: String) -> Result<CommandResult, AgentError> {
+    let account_name =
+        resolve_account_name(&crate::directories::get_config_directory(), &account_name);
+    let windows = crate::server::websocket::reloadable_antigravity_windows();
+    let outcome = if !windows.is_empty() {
+        tracing::info!(
+            target: "account::switch",
+            account_name = %account_name,
+            window_count = windows.len(),
+            "Switching account through connected extensions (scenario 1)"
+        );
+        match hot_switch(&account_name, windows).await {
+            Ok(result) => Ok(result),
+            Err(error) => {
+                tracing::warn!(
+                    target: "account::switch",
+                    error = %error.message(),
+                    "热切换未完成，改为重启 Antigravity"
+                );
+                run_switch(&account_name).await
+            }
+        }
+    } else {
+        tracing::info!(
+            target: "account::switch",
+            account_name = %account_name,
+            "Switching account using fixed process mode (scenario 3)"
+        );
+        run_switch(&account_name).await
+    };
+
+    match outcome {
+        Ok(result) => {
+            if let Err(error) = metadata::record_switched(
+                &crate::directories::get_config_directory(),
+                &account_name,
+            ) {
+                tracing::warn!(error = %error.message(), "Failed to record account switch time");
+            }
+            report_switch_progress(&account_name, SwitchStage::Completed, &result.message);
+            crate::telemetry::record_account_switch(SwitchResult::Success);
+            Ok(result)
+        }
+        Err(error) => {
+            report_switch_progress(&account_name, SwitchStage::Failed, error.message());
+            crate::telemetry::record_account_switch(SwitchResult::Failure);
+            Err(error)
+        }
+    }
+}
+
+/// 场景 1：写入新账户后让每个可热切换的 Antigravity 窗口重新加载，打开的编辑器、终端和窗口布局保持不变
+///
+/// `windows` 为声明了 `reloadWindow` 能力的 Antigravity 窗口 `(client_id, window_id)`。
+/// 只有这些窗口都确认重载、在 [`RELOAD_RECONNECT_TIMEOUT`] 内重新连接，且重新加载后数据库中的
+/// 当前账户确实是目标账户时才算成功；否则回滚写入并返回错误，由调用方改用重启流程。
+async fn hot_switch(
+    account_name: &str,
+    windows: Vec<(String, String)>,
+) -> Result<CommandResult, AgentError> {
+    let account_file = crate::path_policy::account_file(
+        &crate::directories::get_accounts_directory(),
+        account_name,
+    )?;
+    let snapshot = AuthSnapshot::capture()
+        .map_err(|e| e.context("Failed to snapshot current login before switch"))?;
+
+    report_switch_progress(
+        account_name,
+        SwitchStage::Restoring,
+        "Restoring account data",
+    );
+    let injected = async {
+        let message = crate::antigravity::restore::save_antigravity_account_to_file(account_file)
+            .await
+            .map_err(|e| e.context(format!("Failed to restore account '{account_name}'")))?;
+        verify_restored()?;
+        Ok(message)
+    }
+    .await;
+    let restore_message = injected.map_err(|error| roll_back(&snapshot, error))?;
+
+    report_switch_progress(
+        account_name,
+        SwitchStage::Reloading,
+        "Reloading Antigravity windows",
     );
+    let reload_started = chrono::Utc::now();
+    if let Err(error) = reload_windows(account_name, &windows, reload_started).await {
+        report_switch_progress(
+            account_name,
+            SwitchStage::RollingBack,
+            "Restoring previous login",
+        );
+        return Err(roll_back(&snapshot, error));
+    }
+
+    Ok(CommandResult::success(
+        "switch_success",
+        format!("Account switched to {account_name}"),
+    )
+    .with_details(json!({
+        "account_name": account_name,
+        "mode": "reload_window",
+        "reloaded_windows": windows.len(),
+        "restore_message": restore_message
+    })))
+}
+
+/// 热切换时等待窗口重新加载并重新连接的最长时间
+const RELOAD_RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// 检查窗口是否已重新连接的间隔
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 让窗口重新加载，等待它们重新连接后确认当前账户已是目标账户
+async fn reload_windows(
+    account_name: &str,
+    windows: &[(String, String)],
+    reload_started: chrono::DateTime<chrono::Utc>,
+) -> Result<(), AgentError> {
+    let replies = futures_util::future::join_all(windows.iter().map(|(client_id, _)| async move {
+        let result =
+            crate::server::websocket::call_extension(client_id, "reloadWindow", json!({})).await;
+        (client_id, result)
+    }))
+    .await;
+    let failures: Vec<String> = replies
+        .iter()
+        .filter_map(|(client_id, result)| {
+            let error = result.as_ref().err()?;
+            Some(format!("{client_id}: {}", error.message()))
+        })
+        .collect();
+    if !failures.is_empty() {
+        return Err(AgentError::Unavailable(format!(
+            "Not every Antigravity window acknowledged reloadWindow ({})",
+            failures.join("; ")
+        )));
+    }
+
+    let window_ids: Vec<String> = windows
+        .iter()
+        .map(|(_, window_id)| window_id.clone())
+        .collect();
+    let reconnected = tokio::time::timeout(RELOAD_RECONNECT_TIMEOUT, async {
+        while !crate::server::websocket::windows_reconnected_since(&window_ids, reload_started) {
+            tokio::time::sleep(RELOAD_POLL_INTERVAL).await;
+        }
+    })
+    .await;
+    if reconnected.is_err() {
+        return Err(AgentError::Timeout(format!(
+            "Not every Antigravity window reconnected within {}s after reloading",
+            RELOAD_RECONNECT_TIMEOUT.as_secs()
+        )));
+    }
+
+    let current = get_current()
+        .await
+        .map_err(|e| e.context("Failed to read the current account after reloading"))?;
+    if current.account_id != account_id(account_name) {
+        return Err(AgentError::Upstream(format!(
+            "Antigravity is not signed in as '{account_name}' after reloading"
+        )));
+    }
+    Ok(())
+}
 
+/// 场景 3：关闭 Antigravity，写入新账户后重新启动
+async fn run_switch(account_name: &str) -> Result<CommandResult, AgentError> {
+    report_switch_progress(account_name, SwitchStage::Stopping, "Stopping Antigravity");
     let kill_result = tolerant_kill_antigravity_processes()?;
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    wait_for_antigravity_exit().await?;
+
+    // 修改数据库之前记录快照，之后任一步骤失败都回滚到切换前的登录状态
+    let snapshot = AuthSnapshot::capture()
+        .map_err(|e| e.context("Failed to snapshot current login before switch"))?;
+    let mut reached = SwitchStage::Clearing;
+    let mut details = match apply_switch(account_name, &mut reached).await {
+        Ok(details) => details,
+        Err(error) => {
+            report_switch_progress(
+                account_name,
+                SwitchStage::RollingBack,
+                "Restoring previous login",
+            );
+            let error = roll_back(&snapshot, error);
+            // 启动之前失败时重新启动 Antigravity，让用户回到切换前的账户
+            if reached != SwitchStage::Starting {
+                if let Err(e) = crate::antigravity::starter::start_antigravity() {
+                    tracing::warn!(target: "account::switch", error = %e.message(), "回滚后重新启动 Antigravity 失败");
+                }
+            }
+            return Err(error);
+        }
+    };
+
+    details["account_name"] = json!(account_name);
+    details["mode"] = json!("restart");
+    details["kill_result"] = json!(kill_result);
+    Ok(CommandResult::success(
+        "switch_success",
+        format!("Account switched to {account_name}"),
+    )
+    .with_details(details))
+}
 
+/// 清理、写入并启动；`reached` 记录已进入的阶段
+async fn apply_switch(
+    account_name: &str,
+    reached: &mut SwitchStage,
+) -> Result<serde_json::Value, AgentError> {
+    report_switch_progress(
+        account_name,
+        SwitchStage::Clearing,
+        "Clearing current login",
+    );
     let clear_message = crate::antigravity::cleanup::clear_all_antigravity_data()
         .await
-        .map_err(|e| format!("Failed to clear Antigravity data before switch: {e}"))?;
+        .map_err(|e| e.context("Failed to clear Antigravity data before switch"))?;
 
-    let account_file =
-        crate::directories::get_accounts_directory().join(format!("{account_name}.json"));
+    *reached = SwitchStage::Restoring;
+    report_switch_progress(
+        account_name,
+        SwitchStage::Restoring,
+        "Restoring account data",
+    );
+    let account_file = crate::path_policy::account_file(
+        &crate::directories::get_accounts_directory(),
+        account_name,
+    )?;
     let restore_message =
         crate::antigravity::restore::save_antigravity_account_to_file(account_file)
             .await
-            .map_err(|e| format!("Failed to restore account '{account_name}': {e}"))?;
-
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+            .map_err(|e| e.context(format!("Failed to restore account '{account_name}'")))?;
+    verify_restored()?;
 
+    *reached = SwitchStage::Starting;
+    report_switch_progress(account_name, SwitchStage::Starting, "Starting Antigravity");
     let start_message = crate::antigravity::starter::start_antigravity().map_err(|e| {
-        format!("Account restored but failed to start Antigravity for '{account_name}': {e}")
+        e.context(format!(
+            "Account restored but failed to start Antigravity for '{account_name}'"
+        ))
     })?;
 
-    Ok(CommandResult::success(
-        "switch_success",
-        format!("Account switched to {account_name}"),
-    )
-    .with_details(json!({
-        "account_name": account_name,
-        "kill_result": kill_result,
+    Ok(json!({
         "clear_message": clear_message,
         "restore_message": restore_message,
         "start_message": start_message
-    })))
+    }))
 }
 
-pub async fn sign_in_new() -> Result<CommandResult, String> {
+pub async fn sign_in_new() -> Result<CommandResult, AgentError> {
     tracing::info!("Starting sign-in-new flow (backup + clear + restart)");
     let kill_result = tolerant_kill_antigravity_processes()?;
-
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    wait_for_antigravity_exit().await?;
 
     let backup_result = match backup_current().await {
         Ok(result) => Some(result),
@@ -192,11 +555,10 @@ pub async fn sign_in_new() -> Result<CommandResult, String> {
 
     let clear_result = crate::antigravity::cleanup::clear_all_antigravity_data()
         .await
-        .map_err(|error| format!("Failed to clear Antigravity data during sign_in_new: {error}"))?;
+        .map_err(|error| error.context("Failed to clear Antigravity data during sign_in_new"))?;
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
     let start_message = crate::antigravity::starter::start_antigravity()
-        .map_err(|error| format!("Failed to start Antigravity during sign_in_new: {error}"))?;
+        .map_err(|error| error.context("Failed to start Antigravity during sign_in_new"))?;
 
     Ok(
         CommandResult::success("sign_in_new_completed", "Sign-in-new flow completed").with_details(
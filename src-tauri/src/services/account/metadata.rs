@@ -0,0 +1,281 @@
+use super::storage::{account_id, resolve_account_name};
+use crate::error::AgentError;
+use crate::events::{self, AgentEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// 元数据目录（位于账户备份目录下，不会被当作备份文件列出）
+const METADATA_DIR: &str = "metadata";
+
+const MAX_DISPLAY_NAME_CHARS: usize = 64;
+const MAX_NOTES_CHARS: usize = 2000;
+const MAX_TAG_CHARS: usize = 32;
+const MAX_TAGS: usize = 20;
+
+/// 账户元数据
+///
+/// 与备份分开保存在 `antigravity-accounts/metadata/<账户名>.json`，备份文件仍只包含数据库中的三个键。
+/// 没有元数据文件的账户返回默认值。
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+#[serde(default)]
+pub struct AccountMetadata {
+    /// 显示名称，未设置时界面显示邮箱
+    pub display_name: Option<String>,
+    /// 备注
+    pub notes: String,
+    pub tags: Vec<String>,
+    /// `#rrggbb` 格式的颜色
+    pub color: Option<String>,
+    /// 置顶的账户排在列表最前
+    pub pinned: bool,
+    /// 停用的账户不出现在托盘菜单中，也不参与自动轮换
+    pub disabled: bool,
+    /// 首次备份时间（早于元数据功能的备份为空）
+    pub created_at: Option<DateTime<Utc>>,
+    /// 最近一次切换到该账户的时间
+    pub last_switched: Option<DateTime<Utc>>,
+}
+
+/// 元数据的部分更新，只修改提供的字段；`display_name`、`color` 传空字符串时清除
+#[derive(Debug, Deserialize, Clone, Default, ToSchema)]
+#[serde(default)]
+pub struct AccountMetadataUpdate {
+    pub display_name: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub color: Option<String>,
+    pub pinned: Option<bool>,
+    pub disabled: Option<bool>,
+}
+
+fn metadata_file(config_dir: &Path, account_name: &str) -> Result<PathBuf, AgentError> {
+    crate::path_policy::nested_account_file(
+        &config_dir.join("antigravity-accounts"),
+        METADATA_DIR,
+        account_name,
+    )
+}
+
+/// 读取账户元数据，文件不存在或损坏时返回默认值
+pub fn load(config_dir: &Path, account_name: &str) -> AccountMetadata {
+    let Ok(path) = metadata_file(config_dir, account_name) else {
+        return AccountMetadata::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return AccountMetadata::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!(target: "account::metadata", account_name, error = %e, "元数据文件损坏，使用默认值");
+        AccountMetadata::default()
+    })
+}
+
+fn save(
+    config_dir: &Path,
+    account_name: &str,
+    metadata: &AccountMetadata,
+) -> Result<(), AgentError> {
+    let path = metadata_file(config_dir, account_name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| AgentError::from_io(e, "Failed to create account metadata directory"))?;
+    }
+
+    let serialized = serde_json::to_string_pretty(metadata)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize account metadata: {e}")))?;
+    fs::write(&path, serialized).map_err(|e| {
+        AgentError::from_io(
+            e,
+            format!("Failed to write account metadata ({})", path.display()),
+        )
+    })
+}
+
+/// 删除账户元数据（不存在时忽略）
+pub fn remove(config_dir: &Path, account_name: &str) -> Result<(), AgentError> {
+    let path = metadata_file(config_dir, account_name)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AgentError::from_io(
+            e,
+            format!("Failed to delete account metadata for '{account_name}'"),
+        )),
+    }
+}
+
+/// 删除所有账户元数据
+pub fn remove_all(config_dir: &Path) -> Result<(), AgentError> {
+    let dir = config_dir.join("antigravity-accounts").join(METADATA_DIR);
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AgentError::from_io(
+            e,
+            "Failed to delete account metadata directory",
+        )),
+    }
+}
+
+/// 账户是否已停用
+pub fn is_disabled(config_dir: &Path, account_name: &str) -> bool {
+    load(config_dir, account_name).disabled
+}
+
+/// 首次备份时记录创建时间
+pub fn record_created(config_dir: &Path, account_name: &str) -> Result<(), AgentError> {
+    let mut metadata = load(config_dir, account_name);
+    if metadata.created_at.is_some() {
+        return Ok(());
+    }
+    metadata.created_at = Some(Utc::now());
+    save(config_dir, account_name, &metadata)
+}
+
+/// 切换成功后记录切换时间
+pub fn record_switched(config_dir: &Path, account_name: &str) -> Result<(), AgentError> {
+    let mut metadata = load(config_dir, account_name);
+    metadata.last_switched = Some(Utc::now());
+    save(config_dir, account_name, &metadata)
+}
+
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn check_length(field: &str, value: &str, max: usize) -> Result<(), AgentError> {
+    if value.chars().count() > max {
+        return Err(AgentError::InvalidInput(format!(
+            "{field} must be at most {max} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_color(color: String) -> Result<Option<String>, AgentError> {
+    let Some(color) = non_empty(color) else {
+        return Ok(None);
+    };
+    let is_hex = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hex {
+        return Err(AgentError::InvalidInput(format!(
+            "Color must be in #rrggbb format, got '{color}'"
+        )));
+    }
+    Ok(Some(color.to_ascii_lowercase()))
+}
+
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, AgentError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.into_iter().filter_map(non_empty) {
+        check_length("Tag", &tag, MAX_TAG_CHARS)?;
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(AgentError::InvalidInput(format!(
+            "An account can have at most {MAX_TAGS} tags"
+        )));
+    }
+    Ok(normalized)
+}
+
+/// 修改账户元数据（账户名或 `account_id`），返回修改后的完整元数据
+///
+/// 只能修改已备份的账户；创建与切换时间由 Agent 维护，不接受客户端写入。
+pub async fn update_metadata(
+    config_dir: &Path,
+    account: String,
+    update: AccountMetadataUpdate,
+) -> Result<AccountMetadata, AgentError> {
+    let account_name = resolve_account_name(config_dir, &account);
+    let account_name = account_name.as_str();
+    let backup =
+        crate::path_policy::account_file(&config_dir.join("antigravity-accounts"), account_name)?;
+    if !backup.exists() {
+        return Err(AgentError::NotFound(format!(
+            "Account backup '{account_name}' not found"
+        )));
+    }
+
+    let mut metadata = load(config_dir, account_name);
+    if let Some(display_name) = update.display_name {
+        let display_name = non_empty(display_name);
+        if let Some(name) = &display_name {
+            check_length("Display name", name, MAX_DISPLAY_NAME_CHARS)?;
+        }
+        metadata.display_name = display_name;
+    }
+    if let Some(notes) = update.notes {
+        check_length("Notes", &notes, MAX_NOTES_CHARS)?;
+        metadata.notes = notes;
+    }
+    if let Some(tags) = update.tags {
+        metadata.tags = normalize_tags(tags)?;
+    }
+    if let Some(color) = update.color {
+        metadata.color = normalize_color(color)?;
+    }
+    if let Some(pinned) = update.pinned {
+        metadata.pinned = pinned;
+    }
+    if let Some(disabled) = update.disabled {
+        metadata.disabled = disabled;
+    }
+
+    save(config_dir, account_name, &metadata)?;
+    tracing::info!(target: "account::metadata", account_name, "账户元数据已更新");
+    events::publish(AgentEvent::AccountMetadataUpdated {
+        account_id: account_id(account_name),
+        metadata: metadata.clone(),
+    });
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 全新安装：账户目录已存在，`metadata/` 尚未创建
+    fn fresh_config_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agent-metadata-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("antigravity-accounts")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn writes_metadata_before_metadata_directory_exists() {
+        let config_dir = fresh_config_dir();
+
+        record_created(&config_dir, "user@example.com").unwrap();
+        record_switched(&config_dir, "user@example.com").unwrap();
+
+        let metadata = load(&config_dir, "user@example.com");
+        assert!(metadata.created_at.is_some());
+        assert!(metadata.last_switched.is_some());
+        fs::remove_dir_all(config_dir).unwrap();
+    }
+
+    #[test]
+    fn removing_missing_metadata_succeeds() {
+        let config_dir = fresh_config_dir();
+
+        remove(&config_dir, "user@example.com").unwrap();
+        fs::remove_dir_all(config_dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_account_names_with_path_separators() {
+        let config_dir = fresh_config_dir();
+
+        assert!(record_created(&config_dir, "../escape").is_err());
+        fs::remove_dir_all(config_dir).unwrap();
+    }
+}
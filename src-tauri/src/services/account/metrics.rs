@@ -1,4 +1,5 @@
 use super::types::{AccountMetrics, QuotaItem, TriggerResult};
+use crate::error::AgentError;
 use serde_json::Value;
 
 struct ModelTarget {
@@ -35,42 +36,40 @@ async fn ensure_valid_token_with_refresh(
     email: &str,
     access_token: &str,
     refresh_token: Option<&str>,
-) -> Result<(crate::services::google_api::ValidToken, String), String> {
+) -> Result<(crate::services::google_api::ValidToken, String), AgentError> {
     use crate::services::google_api;
 
     match google_api::get_valid_token(email, access_token).await {
         Ok(info) => Ok((info, access_token.to_string())),
-        Err(error) => {
-            let is_unauthorized = error.contains("401") || error.contains("Unauthorized");
-            if !is_unauthorized {
-                return Err(error);
-            }
-
+        Err(AgentError::Unauthorized(error)) => {
             let refresh_token = refresh_token.ok_or_else(|| {
-                format!("Token expired (401) and no refresh token is available: {error}")
+                AgentError::Unauthorized(format!(
+                    "Token expired and no refresh token is available: {error}"
+                ))
             })?;
 
             let new_access_token = google_api::refresh_access_token(refresh_token)
                 .await
                 .map_err(|refresh_error| {
-                    format!("Token expired and refresh failed: {refresh_error}")
+                    refresh_error.context("Token expired and refresh failed")
                 })?;
 
             let token_info = google_api::get_valid_token(email, &new_access_token)
                 .await
                 .map_err(|retry_error| {
-                    format!("Token refresh succeeded but validation retry failed: {retry_error}")
+                    retry_error.context("Token refresh succeeded but validation retry failed")
                 })?;
 
             Ok((token_info, new_access_token))
         }
+        Err(error) => Err(error),
     }
 }
 
 pub async fn get_metrics(
     config_dir: &std::path::Path,
     email: String,
-) -> Result<AccountMetrics, String> {
+) -> Result<AccountMetrics, AgentError> {
     use crate::services::google_api;
 
     let (email, access_token, refresh_token) = google_api::load_account(config_dir, &email).await?;
@@ -79,11 +78,11 @@ pub async fn get_metrics(
 
     let project = google_api::fetch_code_assist_project(&valid_access_token)
         .await
-        .map_err(|e| format!("Failed to fetch project id: {e}"))?;
+        .map_err(|e| e.context("Failed to fetch project id"))?;
 
     let models_json = google_api::fetch_available_models(&valid_access_token, &project)
         .await
-        .map_err(|e| format!("Failed to fetch models: {e}"))?;
+        .map_err(|e| e.context("Failed to fetch models"))?;
 
     let quotas = parse_quotas_for_targets(&models_json)
         .into_iter()
@@ -101,8 +100,9 @@ pub async fn get_metrics(
 pub async fn trigger_quota_refresh(
     config_dir: &std::path::Path,
     email: String,
-) -> Result<TriggerResult, String> {
+) -> Result<TriggerResult, AgentError> {
     use crate::services::google_api;
+    use crate::telemetry::QuotaTriggerOutcome;
     use tracing::error;
 
     tracing::info!(email = %email, "Checking quotas and triggering refresh when needed");
@@ -111,11 +111,12 @@ pub async fn trigger_quota_refresh(
     let (token_info, valid_access_token) =
         ensure_valid_token_with_refresh(&email, &access_token, refresh_token.as_deref())
             .await
-            .map_err(|e| format!("Authentication failed: {e}"))?;
+            .map_err(|e| e.context("Authentication failed"))?;
 
     let project = match google_api::fetch_code_assist_project(&valid_access_token).await {
         Ok(project_id) => project_id,
         Err(error) => {
+            crate::telemetry::record_quota_trigger(QuotaTriggerOutcome::ProjectUnavailable, 1);
             return Ok(TriggerResult {
                 email,
                 triggered_models: Vec::new(),
@@ -130,7 +131,7 @@ pub async fn trigger_quota_refresh(
 
     let models_json = google_api::fetch_available_models(&valid_access_token, &project)
         .await
-        .map_err(|e| format!("Failed to fetch models for refresh trigger: {e}"))?;
+        .map_err(|e| e.context("Failed to fetch models for refresh trigger"))?;
     let parsed_quotas = parse_quotas_for_targets(&models_json);
 
     let mut triggered_models = Vec::new();
@@ -162,6 +163,10 @@ pub async fn trigger_quota_refresh(
         }
     }
 
+    crate::telemetry::record_quota_trigger(QuotaTriggerOutcome::Triggered, triggered_models.len());
+    crate::telemetry::record_quota_trigger(QuotaTriggerOutcome::Failed, failed_models.len());
+    crate::telemetry::record_quota_trigger(QuotaTriggerOutcome::Skipped, skipped_models.len());
+
     let success = failed_models.is_empty();
     let message = if !success {
         "Refresh trigger completed with failures".to_string()
@@ -248,15 +253,18 @@ async fn trigger_minimal_query(
         }
     });
 
-    let response = client
-        .post(&url)
-        .header(AUTHORIZATION, format!("Bearer {}", access_token))
-        .header(CONTENT_TYPE, "application/json")
-        .header(USER_AGENT, "antigravity/windows/amd64")
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Refresh trigger HTTP request failed: {e}"))?;
+    let response = crate::telemetry::observe_google_api(
+        "generateContent",
+        client
+            .post(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", access_token))
+            .header(CONTENT_TYPE, "application/json")
+            .header(USER_AGENT, "antigravity/windows/amd64")
+            .json(&body)
+            .send(),
+    )
+    .await
+    .map_err(|e| format!("Refresh trigger HTTP request failed: {e}"))?;
 
     if !response.status().is_success() {
         return Err(format!(
@@ -1,9 +1,22 @@
 mod lifecycle;
+mod metadata;
 mod metrics;
+mod redaction;
 mod storage;
 mod types;
 
 pub use lifecycle::{
-    backup_current, clear_all_data, get_all, get_current, is_running, restore, sign_in_new, switch,
+    auto_capture, backup_current, clear_all_data, get_all, get_current, is_running, restore,
+    sign_in_new, switch,
 };
+pub use metadata::{update_metadata, AccountMetadata, AccountMetadataUpdate};
 pub use metrics::{get_metrics, trigger_quota_refresh};
+pub use redaction::{reveal_secrets, Redaction};
+pub use storage::{account_id, resolve_account_name};
+
+pub(crate) use metadata::{
+    is_disabled, remove as remove_metadata, remove_all as remove_all_metadata,
+};
+pub use types::{
+    AccountMetrics, AccountSecrets, AntigravityAccountResponse, CommandResult, TriggerResult,
+};
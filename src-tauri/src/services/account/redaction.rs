@@ -0,0 +1,166 @@
+//! # 响应脱敏
+//!
+//! 账户相关响应在离开 Agent 之前统一经过本模块：
+//!
+//! - 令牌：构造 [`AntigravityAccountResponse`] 时即丢弃 `api_key` 与 OAuth access / refresh token。
+//!   没有单独的授权范围：任何管理 Token 都可以通过 [`reveal_secrets`]（`POST /api/reveal_account_secrets`，
+//!   记录审计日志）或 `GET /api/collect_account_contents` 取得原始令牌，CLI `export` 同样输出原始令牌
+//! - 邮箱：隐私模式（`AppSettings.private_mode`）开启时，按 `LogSanitizer::sanitize_email` 的规则遮盖，
+//!   与日志中的显示保持一致
+//!
+//! 事件总线上的 [`AgentEvent`] 在发给 SSE 与 `/ws` 订阅者时经过 [`Redaction::event`]，规则相同。
+//!
+//! 邮箱被遮盖后，客户端使用 `account_id` 指定账户，服务层通过 `resolve_account_name` 解析回账户名。
+
+use super::storage::{account_id, parse_backup_file, resolve_account_name};
+use super::types::{AccountMetrics, AccountSecrets, AntigravityAccountResponse, TriggerResult};
+use crate::error::AgentError;
+use crate::events::AgentEvent;
+use crate::host::AppHost;
+use crate::utils::log_sanitizer::LogSanitizer;
+use serde_json::Value;
+use std::path::Path;
+
+lazy_static::lazy_static! {
+    static ref SANITIZER: LogSanitizer = LogSanitizer::new();
+}
+
+/// 构造响应时始终移除的字段（`antigravityAuthStatus` 已转换为 snake_case）
+const SECRET_AUTH_FIELDS: [&str; 1] = ["api_key"];
+
+/// 隐私模式下额外移除的字段：其中编码了邮箱，无法逐字遮盖
+const PRIVATE_AUTH_FIELDS: [&str; 1] = ["user_status_proto_binary_base64"];
+
+/// 移除 `antigravityAuthStatus` 中的令牌
+pub(super) fn strip_secrets(auth_status: &mut Value) {
+    if let Value::Object(map) = auth_status {
+        for field in SECRET_AUTH_FIELDS {
+            map.remove(field);
+        }
+    }
+}
+
+/// 响应脱敏策略
+#[derive(Debug, Clone, Copy)]
+pub struct Redaction {
+    mask_emails: bool,
+}
+
+impl Redaction {
+    /// 根据隐私模式设置创建
+    pub fn new(private_mode: bool) -> Self {
+        Self {
+            mask_emails: private_mode,
+        }
+    }
+
+    /// 按宿主当前的隐私模式设置创建
+    pub fn for_host(host: &AppHost) -> Self {
+        Self::new(host.settings().get_settings().private_mode)
+    }
+
+    pub fn account(&self, mut account: AntigravityAccountResponse) -> AntigravityAccountResponse {
+        if !self.mask_emails {
+            return account;
+        }
+
+        if let Value::Object(map) = &mut account.antigravity_auth_status {
+            for field in PRIVATE_AUTH_FIELDS {
+                map.remove(field);
+            }
+        }
+        mask_emails_in(&mut account.antigravity_auth_status);
+        if let Some(user_status) = &mut account.user_status {
+            mask_emails_in(&mut user_status.raw_data);
+        }
+        account
+    }
+
+    pub fn accounts(
+        &self,
+        accounts: Vec<AntigravityAccountResponse>,
+    ) -> Vec<AntigravityAccountResponse> {
+        accounts
+            .into_iter()
+            .map(|account| self.account(account))
+            .collect()
+    }
+
+    pub fn metrics(&self, mut metrics: AccountMetrics) -> AccountMetrics {
+        if self.mask_emails {
+            metrics.email = self.email(&metrics.email);
+        }
+        metrics
+    }
+
+    pub fn trigger_result(&self, mut result: TriggerResult) -> TriggerResult {
+        if self.mask_emails {
+            result.email = self.email(&result.email);
+        }
+        result
+    }
+
+    /// 序列化事件；隐私模式下遮盖其中所有字符串里的邮箱（账户名、文件路径、切换消息、元数据、设置值等）
+    pub fn event(&self, event: &AgentEvent) -> Value {
+        let mut value = serde_json::to_value(event).unwrap_or(Value::Null);
+        if self.mask_emails {
+            mask_emails_in(&mut value);
+        }
+        value
+    }
+
+    pub fn email(&self, email: &str) -> String {
+        if self.mask_emails {
+            SANITIZER.sanitize_email(email)
+        } else {
+            email.to_string()
+        }
+    }
+}
+
+/// 遮盖 JSON 中所有字符串里的邮箱
+fn mask_emails_in(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = SANITIZER.sanitize_email(text),
+        Value::Array(items) => items.iter_mut().for_each(mask_emails_in),
+        Value::Object(map) => map.values_mut().for_each(mask_emails_in),
+        _ => {}
+    }
+}
+
+/// 读取账户的原始令牌
+///
+/// 调用方负责鉴权（HTTP 接口要求管理 Token，不需要额外确认），每次调用都会记录审计日志。
+pub async fn reveal_secrets(
+    config_dir: &Path,
+    account: &str,
+) -> Result<AccountSecrets, AgentError> {
+    let account_name = resolve_account_name(config_dir, account);
+    tracing::warn!(
+        target: "account::redaction",
+        account_id = %account_id(&account_name),
+        "读取账户原始令牌"
+    );
+
+    let (email, access_token, refresh_token) =
+        crate::services::google_api::load_account(config_dir, &account_name).await?;
+
+    let path =
+        crate::path_policy::account_file(&config_dir.join("antigravity-accounts"), &account_name)?;
+    let api_key = serde_json::from_str::<Value>(&parse_backup_file(&path)?.auth_status)
+        .ok()
+        .and_then(|status| {
+            status
+                .get("apiKey")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        });
+
+    Ok(AccountSecrets {
+        account_id: account_id(&account_name),
+        email,
+        api_key,
+        access_token,
+        refresh_token,
+    })
+}
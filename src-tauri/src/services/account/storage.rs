@@ -1,3 +1,4 @@
+use crate::error::AgentError;
 use rusqlite::{Connection, OptionalExtension};
 use serde_json::Value;
 use std::fs;
@@ -11,35 +12,39 @@ pub struct RawAccountFields {
     pub user_status: Option<String>,
 }
 
-pub fn resolve_antigravity_db_path() -> Result<PathBuf, String> {
+pub fn resolve_antigravity_db_path() -> Result<PathBuf, AgentError> {
     crate::platform::get_antigravity_db_path()
-        .ok_or_else(|| "Antigravity database path not found".to_string())
+        .ok_or_else(|| AgentError::NotFound("Antigravity database path not found".to_string()))
 }
 
-pub fn open_antigravity_connection() -> Result<(Connection, PathBuf), String> {
+#[tracing::instrument(name = "sqlite", skip_all, fields(op = "open"))]
+pub fn open_antigravity_connection() -> Result<(Connection, PathBuf), AgentError> {
     let db_path = resolve_antigravity_db_path()?;
     let conn = Connection::open(&db_path).map_err(|e| {
-        format!(
-            "Failed to open SQLite database ({}): {e}",
-            db_path.display()
+        AgentError::from_sqlite(
+            e,
+            format!("Failed to open SQLite database ({})", db_path.display()),
         )
     })?;
     Ok((conn, db_path))
 }
 
-pub fn query_item_value(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+#[tracing::instrument(name = "sqlite", level = "debug", skip(conn), fields(op = "query"))]
+pub fn query_item_value(conn: &Connection, key: &str) -> Result<Option<String>, AgentError> {
     conn.query_row("SELECT value FROM ItemTable WHERE key = ?", [key], |row| {
         row.get(0)
     })
     .optional()
-    .map_err(|e| format!("Failed to query key '{key}' from ItemTable: {e}"))
+    .map_err(|e| AgentError::from_sqlite(e, format!("Failed to query key '{key}' from ItemTable")))
 }
 
-pub fn load_current_raw_account_fields() -> Result<RawAccountFields, String> {
+pub fn load_current_raw_account_fields() -> Result<RawAccountFields, AgentError> {
     let (conn, _db_path) = open_antigravity_connection()?;
 
     let auth_status = query_item_value(&conn, crate::constants::database::AUTH_STATUS)?
-        .ok_or_else(|| "antigravityAuthStatus not found in database".to_string())?;
+        .ok_or_else(|| {
+            AgentError::NotFound("antigravityAuthStatus not found in database".to_string())
+        })?;
     let oauth_token = query_item_value(&conn, crate::constants::database::OAUTH_TOKEN)?;
     let user_status = query_item_value(&conn, crate::constants::database::USER_STATUS)?;
 
@@ -50,18 +55,21 @@ pub fn load_current_raw_account_fields() -> Result<RawAccountFields, String> {
     })
 }
 
-pub fn list_backup_json_files(config_dir: &Path) -> Result<Vec<PathBuf>, String> {
+pub fn list_backup_json_files(config_dir: &Path) -> Result<Vec<PathBuf>, AgentError> {
     let antigravity_dir = config_dir.join("antigravity-accounts");
     let entries = fs::read_dir(&antigravity_dir).map_err(|e| {
-        format!(
-            "Failed to read backup directory ({}): {e}",
-            antigravity_dir.display()
+        AgentError::from_io(
+            e,
+            format!(
+                "Failed to read backup directory ({})",
+                antigravity_dir.display()
+            ),
         )
     })?;
 
     let mut files = Vec::new();
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
+        let entry = entry.map_err(|e| AgentError::from_io(e, "Failed to read directory entry"))?;
         let path = entry.path();
         if path.extension().is_some_and(|ext| ext == "json") {
             files.push(path);
@@ -70,21 +78,59 @@ pub fn list_backup_json_files(config_dir: &Path) -> Result<Vec<PathBuf>, String>
     Ok(files)
 }
 
-pub fn parse_backup_file(path: &Path) -> Result<RawAccountFields, String> {
+/// 账户 ID 的 UUID v5 命名空间
+const ACCOUNT_ID_NAMESPACE: uuid::Uuid =
+    uuid::Uuid::from_u128(0x5b1c_8f0e_2d4a_4c6b_9e3f_7a1d_0c2b_6e84);
+
+/// 由账户名（备份文件名，即邮箱）派生的稳定 ID
+///
+/// 隐私模式下响应中的邮箱会被遮盖，客户端改用该 ID 指定账户。
+pub fn account_id(account_name: &str) -> String {
+    uuid::Uuid::new_v5(&ACCOUNT_ID_NAMESPACE, account_name.as_bytes())
+        .simple()
+        .to_string()
+}
+
+/// 将账户名或账户 ID 解析为账户名
+///
+/// 存在同名备份时直接返回；否则在备份中查找 ID 匹配的账户，找不到时原样返回，由调用方报告错误。
+pub fn resolve_account_name(config_dir: &Path, name_or_id: &str) -> String {
+    let accounts_dir = config_dir.join("antigravity-accounts");
+    if crate::path_policy::account_file(&accounts_dir, name_or_id).is_ok_and(|path| path.exists()) {
+        return name_or_id.to_string();
+    }
+
+    list_backup_json_files(config_dir)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| path.file_stem()?.to_str())
+        .find(|name| account_id(name) == name_or_id)
+        .map(str::to_string)
+        .unwrap_or_else(|| name_or_id.to_string())
+}
+
+pub fn parse_backup_file(path: &Path) -> Result<RawAccountFields, AgentError> {
     let file_name = path
         .file_name()
         .and_then(|name| name.to_str())
         .unwrap_or("unknown");
 
     let content = fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read backup file '{file_name}': {e}"))?;
-    let backup_data: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse backup file '{file_name}' as JSON: {e}"))?;
+        .map_err(|e| AgentError::from_io(e, format!("Failed to read backup file '{file_name}'")))?;
+    let backup_data: Value = serde_json::from_str(&content).map_err(|e| {
+        AgentError::Internal(format!(
+            "Failed to parse backup file '{file_name}' as JSON: {e}"
+        ))
+    })?;
 
     let auth_status = backup_data
         .get(crate::constants::database::AUTH_STATUS)
         .and_then(|v| v.as_str())
-        .ok_or_else(|| format!("Backup file '{file_name}' is missing antigravityAuthStatus"))?
+        .ok_or_else(|| {
+            AgentError::Internal(format!(
+                "Backup file '{file_name}' is missing antigravityAuthStatus"
+            ))
+        })?
         .to_string();
 
     let oauth_token = backup_data
@@ -113,9 +159,9 @@ pub fn backup_file_modified_time(path: &Path) -> SystemTime {
 pub fn write_backup_file(
     account_file_name: &str,
     fields: &RawAccountFields,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, AgentError> {
     let accounts_dir = crate::directories::get_accounts_directory();
-    let account_file = accounts_dir.join(format!("{account_file_name}.json"));
+    let account_file = crate::path_policy::account_file(&accounts_dir, account_file_name)?;
 
     let mut content_map = serde_json::Map::new();
     content_map.insert(
@@ -138,13 +184,17 @@ pub fn write_backup_file(
     }
 
     let content = serde_json::Value::Object(content_map);
-    let serialized = serde_json::to_string_pretty(&content)
-        .map_err(|e| format!("Failed to serialize account backup JSON: {e}"))?;
+    let serialized = serde_json::to_string_pretty(&content).map_err(|e| {
+        AgentError::Internal(format!("Failed to serialize account backup JSON: {e}"))
+    })?;
 
     fs::write(&account_file, serialized).map_err(|e| {
-        format!(
-            "Failed to write account backup file ({}): {e}",
-            account_file.display()
+        AgentError::from_io(
+            e,
+            format!(
+                "Failed to write account backup file ({})",
+                account_file.display()
+            ),
         )
     })?;
 
@@ -1,37 +1,56 @@
+use super::metadata::AccountMetadata;
+use crate::error::AgentError;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 账户信息
+///
+/// 不包含任何令牌（`api_key`、access / refresh token），原始令牌只能通过 `reveal_account_secrets` 获取。
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct AntigravityAccountResponse {
+    /// 稳定的账户 ID，可代替邮箱指定账户（隐私模式下邮箱会被遮盖）
+    pub account_id: String,
     pub antigravity_auth_status: Value,
     pub oauth_token: Option<OAuthTokenDecoded>,
     pub user_status: Option<UserStatusDecoded>,
+    /// 显示名称、标签、置顶 / 停用等账户元数据
+    pub metadata: AccountMetadata,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// OAuth 令牌的元数据（令牌本身在解码时丢弃）
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct OAuthTokenDecoded {
     pub sentinel_key: String,
-    pub access_token: String,
-    pub refresh_token: String,
     pub token_type: String,
     pub expiry_seconds: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 账户的原始令牌，仅由 `reveal_account_secrets` 返回
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct AccountSecrets {
+    pub account_id: String,
+    pub email: String,
+    pub api_key: Option<String>,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct UserStatusDecoded {
     pub sentinel_key: String,
     pub raw_data_type: String,
     pub raw_data: Value,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct QuotaItem {
     pub model_name: String,
     pub percentage: f64,
     pub reset_text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct AccountMetrics {
     pub email: String,
     pub user_id: String,
@@ -39,7 +58,7 @@ pub struct AccountMetrics {
     pub quotas: Vec<QuotaItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TriggerResult {
     pub email: String,
     pub triggered_models: Vec<String>,
@@ -50,7 +69,7 @@ pub struct TriggerResult {
     pub message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CommandResult {
     pub ok: bool,
     pub code: String,
@@ -73,6 +92,22 @@ impl CommandResult {
         self.details = Some(details);
         self
     }
+
+    /// 在 `details.request_id` 中回写请求 ID，便于从响应定位日志
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        let request_id = Value::String(request_id.into());
+        match &mut self.details {
+            Some(Value::Object(details)) => {
+                details.insert("request_id".to_string(), request_id);
+            }
+            Some(other) => {
+                let previous = other.take();
+                *other = serde_json::json!({ "value": previous, "request_id": request_id });
+            }
+            None => self.details = Some(serde_json::json!({ "request_id": request_id })),
+        }
+        self
+    }
 }
 
 pub fn normalize_json_keys_to_snake_case(value: Value) -> Value {
@@ -88,26 +123,26 @@ pub fn normalize_json_keys_to_snake_case(value: Value) -> Value {
     }
 }
 
-pub fn parse_auth_status_to_value(raw: &str) -> Result<Value, String> {
+pub fn parse_auth_status_to_value(raw: &str) -> Result<Value, AgentError> {
     let auth_status: Value = serde_json::from_str(raw)
-        .map_err(|e| format!("Failed to parse antigravityAuthStatus: {e}"))?;
+        .map_err(|e| AgentError::Internal(format!("Failed to parse antigravityAuthStatus: {e}")))?;
     Ok(normalize_json_keys_to_snake_case(auth_status))
 }
 
-pub fn decode_oauth_token_to_struct(raw: &str) -> Result<OAuthTokenDecoded, String> {
+pub fn decode_oauth_token_to_struct(raw: &str) -> Result<OAuthTokenDecoded, AgentError> {
     let decoded = crate::utils::codec::decode_oauth_token(raw)
-        .map_err(|e| format!("Failed to decode oauth token: {e}"))?;
+        .map_err(|e| AgentError::Internal(format!("Failed to decode oauth token: {e}")))?;
     let normalized = normalize_json_keys_to_snake_case(decoded);
     serde_json::from_value(normalized)
-        .map_err(|e| format!("Failed to parse oauth token payload: {e}"))
+        .map_err(|e| AgentError::Internal(format!("Failed to parse oauth token payload: {e}")))
 }
 
-pub fn decode_user_status_to_struct(raw: &str) -> Result<UserStatusDecoded, String> {
+pub fn decode_user_status_to_struct(raw: &str) -> Result<UserStatusDecoded, AgentError> {
     let decoded = crate::utils::codec::decode_user_status(raw)
-        .map_err(|e| format!("Failed to decode user status: {e}"))?;
+        .map_err(|e| AgentError::Internal(format!("Failed to decode user status: {e}")))?;
     let normalized = normalize_json_keys_to_snake_case(decoded);
     serde_json::from_value(normalized)
-        .map_err(|e| format!("Failed to parse user status payload: {e}"))
+        .map_err(|e| AgentError::Internal(format!("Failed to parse user status payload: {e}")))
 }
 
 fn normalize_object_keys(map: Map<String, Value>) -> Map<String, Value> {
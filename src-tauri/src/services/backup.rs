@@ -1,10 +1,12 @@
+use crate::error::AgentError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
 use std::time::SystemTime;
+use utoipa::ToSchema;
 
 /// 备份数据收集结构
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AccountExportedData {
     filename: String,
     #[serde(rename = "content")]
@@ -14,14 +16,14 @@ pub struct AccountExportedData {
 }
 
 /// 恢复结果
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RestoreResult {
     #[serde(rename = "restoredCount")]
     restored_count: u32,
     failed: Vec<FailedAccountExportedData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct FailedAccountExportedData {
     filename: String,
     error: String,
@@ -30,15 +32,16 @@ pub struct FailedAccountExportedData {
 /// 收集所有账户文件的完整内容, 用于导出
 pub async fn collect_contents(
     config_dir: &std::path::Path,
-) -> Result<Vec<AccountExportedData>, String> {
+) -> Result<Vec<AccountExportedData>, AgentError> {
     let mut backups_with_content = Vec::new();
 
     // 读取Antigravity账户目录中的JSON文件
     let antigravity_dir = config_dir.join("antigravity-accounts");
 
-    for entry in fs::read_dir(&antigravity_dir).map_err(|e| format!("读取用户目录失败: {}", e))?
+    for entry in fs::read_dir(&antigravity_dir)
+        .map_err(|e| AgentError::from_io(e, "Failed to read accounts directory"))?
     {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let entry = entry.map_err(|e| AgentError::from_io(e, "Failed to read directory entry"))?;
         let path = entry.path();
 
         if path.extension().is_some_and(|ext| ext == "json") {
@@ -52,8 +55,7 @@ pub async fn collect_contents(
                 continue;
             }
 
-            match fs::read_to_string(&path).map_err(|e| format!("读取文件失败 {}: {}", filename, e))
-            {
+            match fs::read_to_string(&path) {
                 Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
                     Ok(json_value) => {
                         backups_with_content.push(AccountExportedData {
@@ -83,7 +85,7 @@ pub async fn collect_contents(
 pub async fn restore_files(
     config_dir: &std::path::Path,
     account_file_data: Vec<AccountExportedData>,
-) -> Result<RestoreResult, String> {
+) -> Result<RestoreResult, AgentError> {
     let mut results = RestoreResult {
         restored_count: 0,
         failed: Vec::new(),
@@ -93,22 +95,36 @@ pub async fn restore_files(
     let antigravity_dir = config_dir.join("antigravity-accounts");
 
     // 确保目录存在
-    if let Err(e) = fs::create_dir_all(&antigravity_dir) {
-        return Err(format!("创建目录失败: {}", e));
-    }
+    fs::create_dir_all(&antigravity_dir)
+        .map_err(|e| AgentError::from_io(e, "Failed to create accounts directory"))?;
 
     // 遍历每个备份
     for account_file in account_file_data {
-        let file_path = antigravity_dir.join(&account_file.filename);
+        // 导入包中的文件名不可信，只允许写入账户目录下的 `<name>.json`
+        let file_path =
+            match crate::path_policy::import_file(&antigravity_dir, &account_file.filename) {
+                Ok(path) => path,
+                Err(e) => {
+                    results.failed.push(FailedAccountExportedData {
+                        filename: account_file.filename,
+                        error: e.message().to_string(),
+                    });
+                    continue;
+                }
+            };
 
         match fs::write(
             &file_path,
             serde_json::to_string_pretty(&account_file.content).unwrap_or_default(),
         )
-        .map_err(|e| format!("写入文件失败: {}", e))
+        .map_err(|e| format!("Failed to write file: {e}"))
         {
             Ok(_) => {
                 results.restored_count += 1;
+                crate::events::publish(crate::events::AgentEvent::BackupWritten {
+                    account_name: account_file.filename.trim_end_matches(".json").to_string(),
+                    file_path: file_path.display().to_string(),
+                });
             }
             Err(e) => {
                 results.failed.push(FailedAccountExportedData {
@@ -123,34 +139,44 @@ pub async fn restore_files(
 }
 
 /// 删除指定备份
-pub async fn delete(config_dir: &std::path::Path, name: String) -> Result<String, String> {
+pub async fn delete(config_dir: &std::path::Path, name: String) -> Result<String, AgentError> {
     // 只删除Antigravity账户JSON文件
+    let name = crate::services::account::resolve_account_name(config_dir, &name);
     let antigravity_dir = config_dir.join("antigravity-accounts");
-    let antigravity_file = antigravity_dir.join(format!("{}.json", name));
+    let antigravity_file = crate::path_policy::account_file(&antigravity_dir, &name)?;
 
-    fs::remove_file(&antigravity_file).map_err(|e| format!("删除用户文件失败: {}", e))?;
+    fs::remove_file(&antigravity_file)
+        .map_err(|e| AgentError::from_io(e, format!("Failed to delete account backup '{name}'")))?;
+    // 备份已删除，元数据清理失败不影响结果
+    if let Err(e) = crate::services::account::remove_metadata(config_dir, &name) {
+        tracing::warn!(target: "backup::delete", account_name = %name, error = %e.message(), "删除账户元数据失败");
+    }
     Ok(format!("删除用户成功: {}", name))
 }
 
 /// 清空所有备份
-pub async fn clear_all(config_dir: &std::path::Path) -> Result<String, String> {
+pub async fn clear_all(config_dir: &std::path::Path) -> Result<String, AgentError> {
     let antigravity_dir = config_dir.join("antigravity-accounts");
 
     // 读取目录中的所有文件
     let mut deleted_count = 0;
-    for entry in fs::read_dir(&antigravity_dir).map_err(|e| format!("读取用户目录失败: {}", e))?
+    for entry in fs::read_dir(&antigravity_dir)
+        .map_err(|e| AgentError::from_io(e, "Failed to read accounts directory"))?
     {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let entry = entry.map_err(|e| AgentError::from_io(e, "Failed to read directory entry"))?;
         let path = entry.path();
 
         // 只删除 JSON 文件
         if path.extension().is_some_and(|ext| ext == "json") {
-            fs::remove_file(&path)
-                .map_err(|e| format!("删除文件 {} 失败: {}", path.display(), e))?;
+            fs::remove_file(&path).map_err(|e| {
+                AgentError::from_io(e, format!("Failed to delete file {}", path.display()))
+            })?;
             deleted_count += 1;
         }
     }
 
+    crate::services::account::remove_all_metadata(config_dir)?;
+
     Ok(format!(
         "已清空所有用户备份，共删除 {} 个文件",
         deleted_count
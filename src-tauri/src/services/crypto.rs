@@ -1,9 +1,15 @@
+use crate::error::AgentError;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 
 /// 加密配置数据（用于账户导出）
-pub async fn encrypt_config_data(json_data: String, password: String) -> Result<String, String> {
+pub async fn encrypt_config_data(
+    json_data: String,
+    password: String,
+) -> Result<String, AgentError> {
     if password.is_empty() {
-        return Err("密码不能为空".to_string());
+        return Err(AgentError::InvalidInput(
+            "Password must not be empty".to_string(),
+        ));
     }
 
     let password_bytes = password.as_bytes();
@@ -25,14 +31,16 @@ pub async fn encrypt_config_data(json_data: String, password: String) -> Result<
 pub async fn decrypt_config_data(
     encrypted_data: String,
     password: String,
-) -> Result<String, String> {
+) -> Result<String, AgentError> {
     if password.is_empty() {
-        return Err("密码不能为空".to_string());
+        return Err(AgentError::InvalidInput(
+            "Password must not be empty".to_string(),
+        ));
     }
 
     let decoded = BASE64
         .decode(encrypted_data)
-        .map_err(|_| "Base64 解码失败".to_string())?;
+        .map_err(|_| AgentError::InvalidInput("Invalid Base64 data".to_string()))?;
 
     let password_bytes = password.as_bytes();
     let mut result = Vec::new();
@@ -42,8 +50,9 @@ pub async fn decrypt_config_data(
         result.push(byte ^ key_byte);
     }
 
-    let decrypted =
-        String::from_utf8(result).map_err(|_| "解密失败，数据可能已损坏".to_string())?;
+    let decrypted = String::from_utf8(result).map_err(|_| {
+        AgentError::InvalidInput("Decryption failed: wrong password or corrupted data".to_string())
+    })?;
 
     Ok(decrypted)
 }
@@ -0,0 +1,253 @@
+//! # 诊断服务
+//!
+//! - [`health`]：运行状态（版本、运行时长、API 监听地址、WebSocket 客户端数、数据库监控状态）
+//! - [`doctor`]：环境检查，每个未通过的检查都附带修复建议
+//!
+//! 出问题时用户不必再手动拼凑 `get_platform_info`、`detect_antigravity_installation` 和日志。
+
+use crate::error::AgentError;
+use crate::host::AppHost;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+lazy_static::lazy_static! {
+    /// 进程启动时间（首次访问时记录，由 [`record_listeners`] 在服务启动时触发）
+    static ref STARTED_AT: Instant = Instant::now();
+
+    /// 已绑定的 API 监听地址
+    static ref LISTENERS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+}
+
+/// `ItemTable` 的预期列（与 VS Code 的 state.vscdb 一致）
+const ITEM_TABLE_COLUMNS: [&str; 2] = ["key", "value"];
+
+/// 记录 HTTP 服务已绑定的地址
+pub fn record_listeners(addresses: Vec<String>) {
+    lazy_static::initialize(&STARTED_AT);
+    *LISTENERS.write() = addresses;
+}
+
+/// 运行状态
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_secs: u64,
+    pub headless: bool,
+    pub api: ApiHealth,
+    pub websocket: WebSocketHealth,
+    pub db_monitor: DbMonitorHealth,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiHealth {
+    /// 已绑定的监听地址（`http://127.0.0.1:<port>`、`unix:<path>`）
+    pub listeners: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebSocketHealth {
+    /// 已完成握手的扩展客户端数量
+    pub clients: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DbMonitorHealth {
+    pub running: bool,
+}
+
+/// 获取运行状态
+pub async fn health(host: &AppHost) -> Result<HealthReport, AgentError> {
+    let listeners = LISTENERS.read().clone();
+    Ok(HealthReport {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_secs: STARTED_AT.elapsed().as_secs(),
+        headless: host.is_headless(),
+        api: ApiHealth { listeners },
+        websocket: WebSocketHealth {
+            clients: crate::server::websocket::extension_client_count(),
+        },
+        db_monitor: DbMonitorHealth {
+            running: host.db_monitor().is_monitoring().await,
+        },
+    })
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+}
+
+/// 单项检查
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DoctorCheck {
+    /// 稳定的检查 ID（如 `state_db_exists`）
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// 修复建议（仅未通过时）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(id: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn fail(id: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// 诊断报告
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DoctorReport {
+    /// 所有检查均通过
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+/// 运行全部环境检查
+pub async fn doctor() -> Result<DoctorReport, AgentError> {
+    let mut checks = Vec::new();
+
+    match crate::platform::get_antigravity_db_path().filter(|path| path.exists()) {
+        Some(db_path) => {
+            checks.push(DoctorCheck::pass(
+                "state_db_exists",
+                format!("Found {}", db_path.display()),
+            ));
+            checks.push(check_db_writable(&db_path));
+            checks.push(check_item_table(&db_path));
+        }
+        None => checks.push(DoctorCheck::fail(
+            "state_db_exists",
+            "Antigravity database (state.vscdb) not found",
+            "Launch Antigravity and sign in once so that it creates its database",
+        )),
+    }
+
+    checks.push(check_executable().await);
+    checks.push(check_accounts_dir());
+    checks.push(check_antigravity_running());
+
+    Ok(DoctorReport {
+        ok: checks.iter().all(|check| check.status != CheckStatus::Fail),
+        checks,
+    })
+}
+
+fn check_db_writable(db_path: &Path) -> DoctorCheck {
+    match std::fs::OpenOptions::new().write(true).open(db_path) {
+        Ok(_) => DoctorCheck::pass("state_db_writable", "state.vscdb is writable"),
+        Err(e) => DoctorCheck::fail(
+            "state_db_writable",
+            format!("Cannot open state.vscdb for writing: {e}"),
+            format!(
+                "Make sure {} is owned by the current user and not read-only",
+                db_path.display()
+            ),
+        ),
+    }
+}
+
+fn check_item_table(db_path: &Path) -> DoctorCheck {
+    const ID: &str = "item_table_schema";
+    let fix = "Antigravity may have changed its storage format; update Antigravity Agent or report an issue";
+
+    let columns =
+        rusqlite::Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(|conn| {
+                let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('ItemTable')")?;
+                let names = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(names)
+            });
+
+    match columns {
+        Ok(columns) if columns.is_empty() => {
+            DoctorCheck::fail(ID, "state.vscdb has no ItemTable", fix)
+        }
+        Ok(columns) => {
+            let missing: Vec<&str> = ITEM_TABLE_COLUMNS
+                .into_iter()
+                .filter(|expected| !columns.iter().any(|column| column == expected))
+                .collect();
+            if missing.is_empty() {
+                DoctorCheck::pass(ID, "ItemTable schema matches")
+            } else {
+                DoctorCheck::fail(
+                    ID,
+                    format!("ItemTable is missing columns: {}", missing.join(", ")),
+                    fix,
+                )
+            }
+        }
+        Err(e) => DoctorCheck::fail(
+            ID,
+            format!("Failed to read ItemTable schema: {e}"),
+            "Close Antigravity and retry; if it persists the database may be corrupted",
+        ),
+    }
+}
+
+async fn check_executable() -> DoctorCheck {
+    const ID: &str = "executable_found";
+    let fix = "Set the Antigravity executable path in Settings (or POST /api/save_antigravity_executable)";
+
+    match crate::services::platform::detect_antigravity_executable().await {
+        Ok(result) if result.get("found").and_then(|v| v.as_bool()) == Some(true) => {
+            let path = result
+                .get("path")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            DoctorCheck::pass(ID, format!("Found {path}"))
+        }
+        Ok(_) => DoctorCheck::fail(ID, "Antigravity executable not found", fix),
+        Err(e) => DoctorCheck::fail(ID, format!("Failed to detect executable: {e}"), fix),
+    }
+}
+
+fn check_accounts_dir() -> DoctorCheck {
+    const ID: &str = "accounts_dir_writable";
+    let dir = crate::directories::get_config_directory().join("antigravity-accounts");
+
+    let probe = std::fs::create_dir_all(&dir).and_then(|_| tempfile::NamedTempFile::new_in(&dir));
+    match probe {
+        Ok(_) => DoctorCheck::pass(ID, format!("{} is writable", dir.display())),
+        Err(e) => DoctorCheck::fail(
+            ID,
+            format!("Cannot write to {}: {e}", dir.display()),
+            "Check the ownership and permissions of ~/.antigravity-agent",
+        ),
+    }
+}
+
+/// 仅供参考：运行与否都不算失败
+fn check_antigravity_running() -> DoctorCheck {
+    let message = if crate::platform::is_antigravity_running() {
+        "Antigravity is running"
+    } else {
+        "Antigravity is not running"
+    };
+    DoctorCheck::pass("antigravity_running", message)
+}
@@ -1,4 +1,6 @@
+use crate::error::AgentError;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use reqwest::StatusCode;
 use serde::Deserialize;
 use serde_json::Value;
 use std::fs;
@@ -27,15 +29,37 @@ pub struct ValidToken {
     pub avatar_url: String,
 }
 
+/// 将上游非 2xx 响应映射为错误：401 视为凭证失效，其余视为上游错误
+fn status_error(what: &str, status: StatusCode, body: &str) -> AgentError {
+    let message = format!("{what} failed with status {status}: {body}");
+    if status == StatusCode::UNAUTHORIZED {
+        AgentError::Unauthorized(message)
+    } else {
+        AgentError::Upstream(message)
+    }
+}
+
+fn network_error(what: &str, error: reqwest::Error) -> AgentError {
+    AgentError::Upstream(format!("{what} request failed: {error}"))
+}
+
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "load_account"))]
 pub async fn load_account(
     config_dir: &std::path::Path,
     target_email: &str,
-) -> Result<(String, String, Option<String>), String> {
+) -> Result<(String, String, Option<String>), AgentError> {
+    let target_email = &crate::services::account::resolve_account_name(config_dir, target_email);
     let antigravity_dir = config_dir.join("antigravity-accounts");
-    let path = antigravity_dir.join(format!("{}.json", target_email));
+    let path = crate::path_policy::account_file(&antigravity_dir, target_email)?;
 
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let json: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&path).map_err(|e| {
+        AgentError::from_io(e, format!("Failed to read account backup '{target_email}'"))
+    })?;
+    let json: Value = serde_json::from_str(&content).map_err(|e| {
+        AgentError::Internal(format!(
+            "Failed to parse account backup '{target_email}': {e}"
+        ))
+    })?;
 
     let auth_status_raw = json
         .get(crate::constants::database::AUTH_STATUS)
@@ -68,7 +92,8 @@ pub async fn load_account(
     Ok((email, access_token, refresh_token))
 }
 
-pub async fn refresh_access_token(refresh_token: &str) -> Result<String, String> {
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "refresh_access_token"))]
+pub async fn refresh_access_token(refresh_token: &str) -> Result<String, AgentError> {
     let client = reqwest::Client::new();
     let params = [
         ("client_id", CLIENT_ID),
@@ -77,36 +102,44 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<String, String>
         ("refresh_token", refresh_token),
     ];
 
-    let res = client
-        .post(TOKEN_URL)
-        .form(&params)
-        .send()
-        .await
-        .map_err(|e| format!("刷新 Token 请求失败: {}", e))?;
+    let res = crate::telemetry::observe_google_api(
+        "token_refresh",
+        client.post(TOKEN_URL).form(&params).send(),
+    )
+    .await
+    .map_err(|e| network_error("Token refresh", e))?;
 
     if !res.status().is_success() {
         let status = res.status();
         let text = res.text().await.unwrap_or_default();
-        return Err(format!("刷新 Token 失败 ({}): {}", status, text));
+        // Google 对吊销/过期的 refresh token 返回 400 invalid_grant
+        if status == StatusCode::BAD_REQUEST || status == StatusCode::UNAUTHORIZED {
+            return Err(AgentError::Unauthorized(format!(
+                "Token refresh rejected ({status}): {text}"
+            )));
+        }
+        return Err(status_error("Token refresh", status, &text));
     }
 
-    let json: RefreshTokenResponse = res
-        .json()
-        .await
-        .map_err(|e| format!("刷新 Token 响应解析失败: {}", e))?;
+    let json: RefreshTokenResponse = res.json().await.map_err(|e| {
+        AgentError::Upstream(format!("Failed to parse token refresh response: {e}"))
+    })?;
 
     Ok(json.access_token)
 }
 
-pub async fn get_valid_token(email: &str, access_token: &str) -> Result<ValidToken, String> {
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "get_valid_token"))]
+pub async fn get_valid_token(email: &str, access_token: &str) -> Result<ValidToken, AgentError> {
     let token = access_token.trim();
     if token.is_empty() {
-        return Err(format!("{} 的 apiKey 为空", email));
+        return Err(AgentError::Unauthorized(format!(
+            "Access token for {email} is empty"
+        )));
     }
 
     let info = fetch_user_info(token)
         .await
-        .map_err(|e| format!("{} 的 apiKey 校验失败: {}", email, e))?;
+        .map_err(|e| e.context(format!("Failed to validate access token for {email}")))?;
 
     Ok(ValidToken {
         access_token: token.to_string(),
@@ -115,52 +148,63 @@ pub async fn get_valid_token(email: &str, access_token: &str) -> Result<ValidTok
     })
 }
 
-pub async fn fetch_user_info(access_token: &str) -> Result<UserInfoResponse, String> {
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "userinfo"))]
+pub async fn fetch_user_info(access_token: &str) -> Result<UserInfoResponse, AgentError> {
     let client = reqwest::Client::new();
-    let res = client
-        .get("https://www.googleapis.com/oauth2/v2/userinfo")
-        .header(AUTHORIZATION, format!("Bearer {}", access_token))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let res = crate::telemetry::observe_google_api(
+        "userinfo",
+        client
+            .get("https://www.googleapis.com/oauth2/v2/userinfo")
+            .header(AUTHORIZATION, format!("Bearer {}", access_token))
+            .send(),
+    )
+    .await
+    .map_err(|e| network_error("userinfo", e))?;
 
     if !res.status().is_success() {
-        return Err(format!("Status: {}", res.status()));
+        return Err(status_error("userinfo", res.status(), ""));
     }
 
     res.json::<UserInfoResponse>()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| AgentError::Upstream(format!("Failed to parse userinfo response: {e}")))
 }
 
-pub async fn fetch_code_assist_project(access_token: &str) -> Result<String, String> {
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "loadCodeAssist"))]
+pub async fn fetch_code_assist_project(access_token: &str) -> Result<String, AgentError> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(5))
         .build()
-        .map_err(|e| e.to_string())?;
-
-    let res = client
-        .post(format!("{}/v1internal:loadCodeAssist", CLOUD_CODE_BASE_URL))
-        .header(AUTHORIZATION, format!("Bearer {}", access_token))
-        .header(CONTENT_TYPE, "application/json")
-        .header(USER_AGENT, "antigravity/windows/amd64")
-        .body(r#"{"metadata": {"ideType": "ANTIGRAVITY"}}"#)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AgentError::Internal(e.to_string()))?;
+
+    let res = crate::telemetry::observe_google_api(
+        "loadCodeAssist",
+        client
+            .post(format!("{}/v1internal:loadCodeAssist", CLOUD_CODE_BASE_URL))
+            .header(AUTHORIZATION, format!("Bearer {}", access_token))
+            .header(CONTENT_TYPE, "application/json")
+            .header(USER_AGENT, "antigravity/windows/amd64")
+            .body(r#"{"metadata": {"ideType": "ANTIGRAVITY"}}"#)
+            .send(),
+    )
+    .await
+    .map_err(|e| network_error("loadCodeAssist", e))?;
 
     let status = res.status();
-    let text = res.text().await.map_err(|e| e.to_string())?;
+    let text = res
+        .text()
+        .await
+        .map_err(|e| network_error("loadCodeAssist", e))?;
 
     if !status.is_success() {
-        return Err(format!("loadCodeAssist failed status {}: {}", status, text));
+        return Err(status_error("loadCodeAssist", status, &text));
     }
 
     let json: Value = serde_json::from_str(&text).map_err(|e| {
-        format!(
+        AgentError::Upstream(format!(
             "Failed to parse project response: {} | Raw Body: {:.100}",
             e, text
-        )
+        ))
     })?;
 
     let project_id = json
@@ -171,39 +215,48 @@ pub async fn fetch_code_assist_project(access_token: &str) -> Result<String, Str
 
     match project_id {
         Some(id) => Ok(id.to_string()),
-        None => Err("Project ID missing in loadCodeAssist response".to_string()),
+        None => Err(AgentError::Upstream(
+            "Project ID missing in loadCodeAssist response".to_string(),
+        )),
     }
 }
 
-pub async fn fetch_available_models(access_token: &str, project: &str) -> Result<Value, String> {
+#[tracing::instrument(name = "google_api", skip_all, fields(op = "fetchAvailableModels"))]
+pub async fn fetch_available_models(
+    access_token: &str,
+    project: &str,
+) -> Result<Value, AgentError> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(5))
         .build()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AgentError::Internal(e.to_string()))?;
 
     let body = serde_json::json!({ "project": project });
 
-    let res = client
-        .post(format!(
-            "{}/v1internal:fetchAvailableModels",
-            CLOUD_CODE_BASE_URL
-        ))
-        .header(AUTHORIZATION, format!("Bearer {}", access_token))
-        .header(CONTENT_TYPE, "application/json")
-        .header(USER_AGENT, "antigravity/windows/amd64")
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let res = crate::telemetry::observe_google_api(
+        "fetchAvailableModels",
+        client
+            .post(format!(
+                "{}/v1internal:fetchAvailableModels",
+                CLOUD_CODE_BASE_URL
+            ))
+            .header(AUTHORIZATION, format!("Bearer {}", access_token))
+            .header(CONTENT_TYPE, "application/json")
+            .header(USER_AGENT, "antigravity/windows/amd64")
+            .json(&body)
+            .send(),
+    )
+    .await
+    .map_err(|e| network_error("fetchAvailableModels", e))?;
 
     let status = res.status();
-    let text = res.text().await.map_err(|e| e.to_string())?;
+    let text = res
+        .text()
+        .await
+        .map_err(|e| network_error("fetchAvailableModels", e))?;
 
     if !status.is_success() {
-        return Err(format!(
-            "fetchAvailableModels failed status {}: {}",
-            status, text
-        ));
+        return Err(status_error("fetchAvailableModels", status, &text));
     }
 
     serde_json::from_str(&text).map_err(|e| {
@@ -211,9 +264,9 @@ pub async fn fetch_available_models(access_token: &str, project: &str) -> Result
             "JSON parse failed for fetchAvailableModels. Raw body: {}",
             text
         );
-        format!(
+        AgentError::Upstream(format!(
             "Failed to parse models JSON: {} | Raw Body: {:.500}",
             e, text
-        )
+        ))
     })
 }
@@ -2,7 +2,9 @@ pub mod account;
 
 pub mod backup;
 pub mod crypto;
+pub mod diagnostics;
 pub mod google_api;
 pub mod platform;
+pub mod remote;
 pub mod settings;
 pub mod system;
@@ -1,7 +1,8 @@
+use crate::error::AgentError;
 use serde_json::Value;
 
 /// 获取平台信息
-pub async fn get_platform_info() -> Result<Value, String> {
+pub async fn get_platform_info() -> Result<Value, AgentError> {
     let os_type = std::env::consts::OS;
     let arch = std::env::consts::ARCH;
     let family = std::env::consts::FAMILY;
@@ -22,7 +23,7 @@ pub async fn get_platform_info() -> Result<Value, String> {
 }
 
 /// 查找 Antigravity 安装位置
-pub async fn find_antigravity_installations() -> Result<Vec<String>, String> {
+pub async fn find_antigravity_installations() -> Result<Vec<String>, AgentError> {
     let paths = crate::platform::find_antigravity_installations();
     Ok(paths
         .iter()
@@ -31,14 +32,14 @@ pub async fn find_antigravity_installations() -> Result<Vec<String>, String> {
 }
 
 /// 验证 Antigravity 可执行文件路径
-pub async fn validate_antigravity_executable(path: String) -> Result<bool, String> {
+pub async fn validate_antigravity_executable(path: String) -> Result<bool, AgentError> {
     Ok(crate::antigravity::path_config::validate_executable_path(
         &path,
     ))
 }
 
 /// 检测 Antigravity 安装状态（数据库路径）
-pub async fn detect_antigravity_installation() -> Result<serde_json::Value, String> {
+pub async fn detect_antigravity_installation() -> Result<serde_json::Value, AgentError> {
     // 自动检测 Antigravity 数据库路径
     if let Some(db_path) = crate::platform::get_antigravity_db_path() {
         if db_path.exists() {
@@ -68,7 +69,7 @@ pub async fn detect_antigravity_installation() -> Result<serde_json::Value, Stri
 }
 
 /// 检测 Antigravity 可执行文件
-pub async fn detect_antigravity_executable() -> Result<serde_json::Value, String> {
+pub async fn detect_antigravity_executable() -> Result<serde_json::Value, AgentError> {
     // 1. 尝试从配置读取自定义可执行文件路径
     let custom_exec = crate::antigravity::path_config::get_custom_executable_path().unwrap_or(None);
 
@@ -105,10 +106,12 @@ pub async fn detect_antigravity_executable() -> Result<serde_json::Value, String
 }
 
 /// 保存用户自定义的 Antigravity 可执行文件路径
-pub async fn save_antigravity_executable(path: String) -> Result<String, String> {
+pub async fn save_antigravity_executable(path: String) -> Result<String, AgentError> {
     // 1. 验证路径有效性
     if !crate::antigravity::path_config::validate_executable_path(&path) {
-        return Err(format!("路径无效：文件 '{}' 不存在或不是可执行文件", path));
+        return Err(AgentError::InvalidInput(format!(
+            "Invalid path: '{path}' does not exist or is not an executable file"
+        )));
     }
 
     // 2. 保存路径到配置
@@ -118,7 +121,7 @@ pub async fn save_antigravity_executable(path: String) -> Result<String, String>
 }
 
 /// 获取当前配置的路径
-pub async fn get_current_paths() -> Result<serde_json::Value, String> {
+pub async fn get_current_paths() -> Result<serde_json::Value, AgentError> {
     let exec_path = crate::antigravity::path_config::get_custom_executable_path().unwrap_or(None);
 
     Ok(serde_json::json!({
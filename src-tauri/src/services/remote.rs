@@ -0,0 +1,327 @@
+//! # 局域网远程访问
+//!
+//! 可选的远程监听器（默认关闭）通过 TLS 在局域网中提供与本地相同的 API，
+//! 同一网络中的其他电脑可以查看配额、切换账户。监听器与证书见 [`crate::server::tls`]。
+//!
+//! ## 配对
+//!
+//! 1. 用户在 Agent 窗口（设置 → 远程访问）生成配对码：6 位数字，[`PAIRING_CODE_TTL_SECS`] 秒内有效，只能使用一次
+//! 2. 新设备通过远程监听器调用 `POST /api/pair_remote_device`，提交配对码与设备名
+//! 3. Agent 返回设备 Token，之后的请求携带 `Authorization: Bearer <device token>`
+//!
+//! 配对码连续输错 [`MAX_PAIRING_ATTEMPTS`] 次即作废，需要重新生成。
+//!
+//! ## 设备 Token
+//!
+//! - 保存在 `remote_devices.json`（Unix 下权限 0600），只存 Token 的 SHA-256 摘要
+//! - 新设备只有 `read` 作用域，需要在 Agent 窗口中显式授予 `admin`
+//! - 撤销后立即失效，已建立的 `/ws`、`/api/events` 连接在下一次检查时断开
+//! - 本地 Token（`api_tokens.json`）在远程监听器上无效，设备 Token 在本地监听器上同样无效
+//!
+//! 管理接口（生成配对码、设备列表、授权、撤销）只在本地监听器上提供。
+
+use crate::error::AgentError;
+use crate::events::{self, AgentEvent};
+use crate::host::AppHost;
+use crate::server::auth::{constant_time_eq, generate_token, ApiScope};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// 配对码有效期（秒）
+pub const PAIRING_CODE_TTL_SECS: i64 = 300;
+
+/// 配对码允许的最大错误次数
+pub const MAX_PAIRING_ATTEMPTS: u32 = 5;
+
+/// 设备名最大长度（字符）
+const MAX_DEVICE_NAME_CHARS: usize = 64;
+
+lazy_static::lazy_static! {
+    /// 已配对设备（首次访问时从 `remote_devices.json` 加载）
+    static ref DEVICES: RwLock<Vec<StoredDevice>> = RwLock::new(load_devices());
+
+    /// 当前有效的配对码（同一时间只有一个）
+    static ref PAIRING: Mutex<Option<PendingPairing>> = Mutex::new(None);
+
+    /// 远程监听器实际绑定的端口，未监听时为 `None`
+    static ref LISTENING_PORT: RwLock<Option<u16>> = RwLock::new(None);
+}
+
+/// 已配对的远程设备
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RemoteDevice {
+    /// 设备 ID
+    pub id: String,
+    /// 配对时提交的设备名
+    pub name: String,
+    /// 设备 Token 的作用域
+    pub scope: ApiScope,
+    pub paired_at: DateTime<Utc>,
+    /// 最近一次访问时间（仅在设备列表变化时写入文件）
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredDevice {
+    #[serde(flatten)]
+    device: RemoteDevice,
+    /// 设备 Token 的 SHA-256（十六进制）
+    token_sha256: String,
+}
+
+#[derive(Debug)]
+struct PendingPairing {
+    code: String,
+    expires_at: DateTime<Utc>,
+    failed_attempts: u32,
+}
+
+/// 新生成的配对码
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PairingCode {
+    /// 6 位数字
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 配对结果，`token` 只在此时返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PairedDevice {
+    pub device: RemoteDevice,
+    /// 设备 Token，后续请求以 `Authorization: Bearer <token>` 携带
+    pub token: String,
+}
+
+/// 远程访问状态
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RemoteAccessStatus {
+    /// 设置中是否开启（重启后生效）
+    pub enabled: bool,
+    /// 远程监听器是否正在运行
+    pub listening: bool,
+    pub port: u16,
+    /// 按本机局域网地址推测的访问地址（如 `https://192.168.1.5:56790`）
+    pub url: Option<String>,
+    /// TLS 证书的 SHA-256 指纹，尚未生成证书时为空
+    pub fingerprint: Option<String>,
+    /// 当前配对码的过期时间（配对码本身只在生成时返回）
+    pub pairing_expires_at: Option<DateTime<Utc>>,
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn load_devices() -> Vec<StoredDevice> {
+    let path = crate::directories::get_remote_devices_file();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!(target: "remote", error = %e, "远程设备文件无效，忽略已配对设备");
+        Vec::new()
+    })
+}
+
+fn save_devices(devices: &[StoredDevice]) -> Result<(), AgentError> {
+    let path = crate::directories::get_remote_devices_file();
+    let json = serde_json::to_string_pretty(devices)
+        .map_err(|e| AgentError::Internal(format!("Failed to serialize remote devices: {e}")))?;
+    crate::utils::private_file::write(&path, json)
+        .map_err(|e| AgentError::from_io(e, "Failed to write remote device file"))
+}
+
+fn notify_changed() {
+    events::publish(AgentEvent::RemoteDevicesChanged);
+}
+
+fn device_not_found(id: &str) -> AgentError {
+    AgentError::NotFound(format!("Remote device not found: {id}"))
+}
+
+/// 记录远程监听器已绑定的端口
+pub fn record_listening(port: u16) {
+    *LISTENING_PORT.write() = Some(port);
+}
+
+/// 本机的局域网地址
+///
+/// 连接 UDP socket 只会按路由表选择出口地址，不会发送任何数据。
+fn lan_address() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 9)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// 获取远程访问状态
+pub async fn status(host: &AppHost) -> Result<RemoteAccessStatus, AgentError> {
+    let settings = host.settings().get_settings();
+    let listening_port = *LISTENING_PORT.read();
+    let port = listening_port.unwrap_or(settings.remote_access_port);
+    let pairing_expires_at = PAIRING
+        .lock()
+        .as_ref()
+        .map(|pending| pending.expires_at)
+        .filter(|expires_at| *expires_at > Utc::now());
+
+    Ok(RemoteAccessStatus {
+        enabled: settings.remote_access_enabled,
+        listening: listening_port.is_some(),
+        port,
+        url: listening_port
+            .and(lan_address())
+            .map(|ip| format!("https://{}", std::net::SocketAddr::new(ip, port))),
+        fingerprint: crate::server::tls::fingerprint(),
+        pairing_expires_at,
+    })
+}
+
+/// 生成新的配对码，之前的配对码立即失效
+pub async fn create_pairing_code() -> Result<PairingCode, AgentError> {
+    let code = format!("{:06}", Uuid::new_v4().as_u128() % 1_000_000);
+    let expires_at = Utc::now() + Duration::seconds(PAIRING_CODE_TTL_SECS);
+
+    *PAIRING.lock() = Some(PendingPairing {
+        code: code.clone(),
+        expires_at,
+        failed_attempts: 0,
+    });
+    tracing::info!(target: "remote", "已生成远程设备配对码");
+    Ok(PairingCode { code, expires_at })
+}
+
+fn validate_device_name(name: &str) -> Result<String, AgentError> {
+    let name = name.trim();
+    if name.is_empty()
+        || name.chars().count() > MAX_DEVICE_NAME_CHARS
+        || name.chars().any(char::is_control)
+    {
+        return Err(AgentError::InvalidInput(format!(
+            "Device name must be 1-{MAX_DEVICE_NAME_CHARS} printable characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// 使用配对码登记新设备
+pub async fn pair(code: &str, device_name: &str) -> Result<PairedDevice, AgentError> {
+    let name = validate_device_name(device_name)?;
+    let invalid_code =
+        || AgentError::Unauthorized("Pairing code is invalid or expired".to_string());
+
+    {
+        let mut pending = PAIRING.lock();
+        if pending
+            .as_ref()
+            .is_some_and(|current| current.expires_at <= Utc::now())
+        {
+            *pending = None;
+        }
+        let Some(current) = pending.as_mut() else {
+            return Err(invalid_code());
+        };
+        if !constant_time_eq(code.trim().as_bytes(), current.code.as_bytes()) {
+            current.failed_attempts += 1;
+            if current.failed_attempts >= MAX_PAIRING_ATTEMPTS {
+                tracing::warn!(target: "remote", "配对码错误次数过多，已作废");
+                *pending = None;
+            }
+            return Err(invalid_code());
+        }
+        *pending = None;
+    }
+
+    let token = generate_token();
+    let device = RemoteDevice {
+        id: Uuid::new_v4().simple().to_string(),
+        name,
+        scope: ApiScope::Read,
+        paired_at: Utc::now(),
+        last_seen_at: None,
+    };
+    {
+        let mut devices = DEVICES.write();
+        devices.push(StoredDevice {
+            device: device.clone(),
+            token_sha256: hash_token(&token),
+        });
+        save_devices(&devices)?;
+    }
+
+    tracing::info!(target: "remote", device_id = %device.id, device_name = %device.name, "远程设备已配对");
+    notify_changed();
+    Ok(PairedDevice { device, token })
+}
+
+/// 列出已配对设备
+pub async fn list_devices() -> Result<Vec<RemoteDevice>, AgentError> {
+    Ok(DEVICES
+        .read()
+        .iter()
+        .map(|stored| stored.device.clone())
+        .collect())
+}
+
+/// 修改设备作用域
+pub async fn set_device_scope(id: &str, scope: ApiScope) -> Result<RemoteDevice, AgentError> {
+    let mut devices = DEVICES.write();
+    let stored = devices
+        .iter_mut()
+        .find(|stored| stored.device.id == id)
+        .ok_or_else(|| device_not_found(id))?;
+    stored.device.scope = scope;
+    let device = stored.device.clone();
+    save_devices(&devices)?;
+    drop(devices);
+
+    tracing::info!(target: "remote", device_id = %id, ?scope, "已修改远程设备作用域");
+    notify_changed();
+    Ok(device)
+}
+
+/// 撤销设备，其 Token 立即失效
+pub async fn revoke_device(id: &str) -> Result<(), AgentError> {
+    let mut devices = DEVICES.write();
+    let before = devices.len();
+    devices.retain(|stored| stored.device.id != id);
+    if devices.len() == before {
+        return Err(device_not_found(id));
+    }
+    save_devices(&devices)?;
+    drop(devices);
+
+    tracing::info!(target: "remote", device_id = %id, "已撤销远程设备");
+    notify_changed();
+    Ok(())
+}
+
+/// 校验设备 Token，返回设备 ID 与作用域
+pub fn authenticate(token: &str) -> Option<(String, ApiScope)> {
+    let token_sha256 = hash_token(token);
+    let mut devices = DEVICES.write();
+    let stored = devices
+        .iter_mut()
+        .find(|stored| constant_time_eq(stored.token_sha256.as_bytes(), token_sha256.as_bytes()))?;
+    stored.device.last_seen_at = Some(Utc::now());
+    Some((stored.device.id.clone(), stored.device.scope))
+}
+
+/// 设备当前的作用域，已撤销时返回 `None`
+///
+/// 长连接（`/ws`、`/api/events`）定期调用，使撤销和作用域变化对已建立的连接生效。
+pub fn device_scope(id: &str) -> Option<ApiScope> {
+    DEVICES
+        .read()
+        .iter()
+        .find(|stored| stored.device.id == id)
+        .map(|stored| stored.device.scope)
+}
@@ -1,7 +1,19 @@
-use tauri::{AppHandle, Manager};
+use crate::error::AgentError;
+use crate::events::{self, AgentEvent};
+use crate::host::AppHost;
+use tauri::Manager;
+
+/// 发布设置变更事件
+fn notify_changed(key: &str, value: serde_json::Value) {
+    events::publish(AgentEvent::SettingsChanged {
+        key: key.to_string(),
+        value,
+    });
+}
 
 /// 保存系统托盘状态
-pub async fn save_system_tray_state(app: &AppHandle, enabled: bool) -> Result<bool, String> {
+pub async fn save_system_tray_state(host: &AppHost, enabled: bool) -> Result<bool, AgentError> {
+    let app = host.app_handle("System tray")?;
     let system_tray = app.state::<crate::system_tray::SystemTrayManager>();
 
     if enabled {
@@ -10,48 +22,115 @@ pub async fn save_system_tray_state(app: &AppHandle, enabled: bool) -> Result<bo
         system_tray.disable(app)?;
     }
 
+    notify_changed("system_tray_enabled", enabled.into());
     Ok(enabled)
 }
 
 /// 保存静默启动状态
-pub async fn save_silent_start_state(app: &AppHandle, enabled: bool) -> Result<bool, String> {
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+pub async fn save_silent_start_state(host: &AppHost, enabled: bool) -> Result<bool, AgentError> {
+    let settings_manager = host.settings();
 
     settings_manager.update_settings(|settings| {
         settings.silent_start_enabled = enabled;
     })?;
 
     let settings = settings_manager.get_settings();
+    notify_changed("silent_start_enabled", settings.silent_start_enabled.into());
     Ok(settings.silent_start_enabled)
 }
 
 /// 保存隐私模式状态
-pub async fn save_private_mode_state(app: &AppHandle, enabled: bool) -> Result<bool, String> {
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+pub async fn save_private_mode_state(host: &AppHost, enabled: bool) -> Result<bool, AgentError> {
+    let settings_manager = host.settings();
 
     settings_manager.update_settings(|settings| {
         settings.private_mode = enabled;
     })?;
 
     let settings = settings_manager.get_settings();
+    notify_changed("private_mode", settings.private_mode.into());
     Ok(settings.private_mode)
 }
 
+/// 保存远程访问开关（监听器在重启后生效）
+pub async fn save_remote_access_state(host: &AppHost, enabled: bool) -> Result<bool, AgentError> {
+    let settings_manager = host.settings();
+
+    settings_manager.update_settings(|settings| {
+        settings.remote_access_enabled = enabled;
+    })?;
+
+    let settings = settings_manager.get_settings();
+    notify_changed(
+        "remote_access_enabled",
+        settings.remote_access_enabled.into(),
+    );
+    Ok(settings.remote_access_enabled)
+}
+
 /// 保存 Debug Mode 状态
-pub async fn save_debug_mode_state(app: &AppHandle, enabled: bool) -> Result<bool, String> {
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+pub async fn save_debug_mode_state(host: &AppHost, enabled: bool) -> Result<bool, AgentError> {
+    let settings_manager = host.settings();
 
     settings_manager.update_settings(|settings| {
         settings.debug_mode = enabled;
     })?;
 
     let settings = settings_manager.get_settings();
+    notify_changed("debug_mode", settings.debug_mode.into());
     Ok(settings.debug_mode)
 }
 
+/// 自动备份设置
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct AutoCaptureSettings {
+    /// 是否自动备份新登录或令牌已刷新的账户
+    pub enabled: bool,
+    /// 不自动备份的邮箱（不区分大小写）
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// 保存自动备份设置，排除列表去除空白项并去重
+pub async fn save_auto_capture(
+    host: &AppHost,
+    update: AutoCaptureSettings,
+) -> Result<AutoCaptureSettings, AgentError> {
+    let mut exclude: Vec<String> = Vec::new();
+    for email in update.exclude {
+        let email = email.trim().to_lowercase();
+        if email.is_empty() || exclude.contains(&email) {
+            continue;
+        }
+        if !email.contains('@') {
+            return Err(AgentError::InvalidInput(format!(
+                "Not an email address: '{email}'"
+            )));
+        }
+        exclude.push(email);
+    }
+
+    let settings_manager = host.settings();
+    settings_manager.update_settings(|settings| {
+        settings.auto_capture_enabled = update.enabled;
+        settings.auto_capture_exclude = exclude;
+    })?;
+
+    let settings = settings_manager.get_settings();
+    let saved = AutoCaptureSettings {
+        enabled: settings.auto_capture_enabled,
+        exclude: settings.auto_capture_exclude,
+    };
+    notify_changed(
+        "auto_capture",
+        serde_json::to_value(&saved).unwrap_or_default(),
+    );
+    Ok(saved)
+}
+
 /// 获取所有应用设置
-pub async fn get_all(app: &AppHandle) -> Result<serde_json::Value, String> {
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+pub async fn get_all(host: &AppHost) -> Result<serde_json::Value, AgentError> {
+    let settings_manager = host.settings();
     let settings = settings_manager.get_settings();
 
     Ok(serde_json::json!({
@@ -59,30 +138,36 @@ pub async fn get_all(app: &AppHandle) -> Result<serde_json::Value, String> {
         "silent_start_enabled": settings.silent_start_enabled,
         "debugMode": settings.debug_mode,
         "privateMode": settings.private_mode,
-        "language": settings.language
+        "language": settings.language,
+        "autoCaptureEnabled": settings.auto_capture_enabled,
+        "autoCaptureExclude": settings.auto_capture_exclude,
+        "headless": host.is_headless()
     }))
 }
 
 /// 获取语言偏好设置
-pub async fn get_language(app: &AppHandle) -> Result<String, String> {
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+pub async fn get_language(host: &AppHost) -> Result<String, AgentError> {
+    let settings_manager = host.settings();
     let settings = settings_manager.get_settings();
     Ok(settings.language.clone())
 }
 
 /// 保存语言偏好设置
-pub async fn set_language(app: &AppHandle, language: String) -> Result<(), String> {
+pub async fn set_language(host: &AppHost, language: String) -> Result<(), AgentError> {
     // Validate language code
-    let valid_languages = vec!["en", "zh-CN", "zh-TW"];
+    let valid_languages = ["en", "zh-CN", "zh-TW"];
     if !valid_languages.contains(&language.as_str()) {
-        return Err(format!("Unsupported language: {}", language));
+        return Err(AgentError::InvalidInput(format!(
+            "Unsupported language: {language}"
+        )));
     }
 
-    let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+    let settings_manager = host.settings();
     settings_manager.update_settings(|settings| {
         settings.language = language.clone();
     })?;
 
     tracing::info!("Language preference saved: {}", language);
+    notify_changed("language", language.into());
     Ok(())
 }
@@ -1,26 +1,37 @@
-use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use crate::error::AgentError;
+use crate::host::AppHost;
+use tauri::Manager;
 
 pub mod tray {
     use super::*;
     use crate::system_tray::{update_tray_menu, SystemTrayManager, TrayMenuLabels};
 
     pub async fn update_menu(
-        app: &AppHandle,
+        host: &AppHost,
         accounts: Vec<String>,
         labels: Option<TrayMenuLabels>,
-    ) -> Result<String, String> {
-        update_tray_menu(app, accounts, labels)?;
+    ) -> Result<String, AgentError> {
+        // 前端在隐私模式下传入 account_id，托盘菜单仍按账户名（邮箱）生成并自行打码显示
+        // 停用的账户不出现在托盘中
+        let config_dir = crate::directories::get_config_directory();
+        let accounts = accounts
+            .iter()
+            .map(|account| crate::services::account::resolve_account_name(&config_dir, account))
+            .filter(|account| !crate::services::account::is_disabled(&config_dir, account))
+            .collect();
+        update_tray_menu(host.app_handle("System tray")?, accounts, labels)?;
         Ok("托盘菜单已更新".to_string())
     }
 
-    pub async fn minimize(app: &AppHandle) -> Result<String, String> {
+    pub async fn minimize(host: &AppHost) -> Result<String, AgentError> {
+        let app = host.app_handle("Window management")?;
         let system_tray = app.state::<SystemTrayManager>();
         system_tray.minimize_to_tray(app)?;
         Ok("已最小化到托盘".to_string())
     }
 
-    pub async fn restore(app: &AppHandle) -> Result<String, String> {
+    pub async fn restore(host: &AppHost) -> Result<String, AgentError> {
+        let app = host.app_handle("Window management")?;
         let system_tray = app.state::<SystemTrayManager>();
         system_tray.restore_from_tray(app)?;
         Ok("已恢复窗口".to_string())
@@ -29,40 +40,40 @@ pub mod tray {
 
 pub mod db_monitor {
     use super::*;
-    use crate::db_monitor::DatabaseMonitor;
 
-    pub async fn is_running(_app: &AppHandle) -> Result<bool, String> {
+    pub async fn is_running(_host: &AppHost) -> Result<bool, AgentError> {
         // 智能监控现在是默认功能，总是返回 true
         Ok(true)
     }
 
-    pub async fn start(app: &AppHandle) -> Result<String, String> {
-        let monitor = app.state::<Arc<DatabaseMonitor>>();
-        monitor.start_monitoring().await;
+    pub async fn start(host: &AppHost) -> Result<String, AgentError> {
+        host.db_monitor().start_monitoring().await;
         Ok("数据库监控已启动".to_string())
     }
 
-    pub async fn stop(app: &AppHandle) -> Result<String, String> {
-        let monitor = app.state::<Arc<DatabaseMonitor>>();
-        monitor.stop_monitoring().await;
+    pub async fn stop(host: &AppHost) -> Result<String, AgentError> {
+        host.db_monitor().stop_monitoring().await;
         Ok("数据库监控已停止".to_string())
     }
 }
 
 pub mod logging {
+    use crate::error::AgentError;
     use std::fs;
-    use std::path::Path;
 
-    pub async fn write_text_file(path: String, content: String) -> Result<String, String> {
-        let file_path = Path::new(&path);
+    /// 写入文本文件（仅限用户在保存对话框中选择的导出文件，每次选择只能写入一次）
+    pub async fn write_text_file(path: String, content: String) -> Result<String, AgentError> {
+        let file_path = crate::path_policy::take_export_file(&path)?;
 
         // 确保父目录存在
         if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            fs::create_dir_all(parent)
+                .map_err(|e| AgentError::from_io(e, "Failed to create directory"))?;
         }
 
         // 写入文件
-        fs::write(file_path, content).map_err(|e| format!("写入文件失败: {}", e))?;
+        fs::write(&file_path, content)
+            .map_err(|e| AgentError::from_io(e, format!("Failed to write {path}")))?;
 
         Ok(format!("文件写入成功: {}", path))
     }
@@ -231,7 +242,7 @@ pub mod extension {
                 exe_path = Some(bin_exe);
                 break;
             }
-            if dir.extension().map_or(false, |ext| ext == "app") {
+            if dir.extension().is_some_and(|ext| ext == "app") {
                 let mac_cli = dir
                     .join("Contents")
                     .join("Resources")
@@ -1,4 +1,4 @@
-use crate::{app_settings, db_monitor, system_tray, window};
+use crate::{app_settings, db_monitor, events, system_tray, window};
 use std::sync::Arc;
 use tauri::{App, Manager};
 
@@ -6,8 +6,7 @@ pub fn init(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>
     tracing::info!(target: "app::setup", "开始应用程序设置");
 
     // 初始化应用设置管理器
-    let app_handle = app.handle();
-    app.manage(app_settings::AppSettingsManager::new(app_handle));
+    app.manage(Arc::new(app_settings::AppSettingsManager::new()));
 
     // 初始化系统托盘管理器
     app.manage(system_tray::SystemTrayManager::new());
@@ -22,11 +21,16 @@ pub fn init(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>
     }
 
     // 初始化数据库监控器
-    let db_monitor = Arc::new(db_monitor::DatabaseMonitor::new(app.handle().clone()));
+    let db_monitor = Arc::new(db_monitor::DatabaseMonitor::new(Some(app.handle().clone())));
     app.manage(db_monitor.clone());
 
-    // 数据库监控将在前端通过命令启动，避免在 setup 中使用 tokio::spawn
-    tracing::debug!(target: "app::setup::db_monitor", "数据库监控将根据前端设置自动启动");
+    // 数据库变化事件由前端通过命令启动；轮询不依赖前端，窗口关闭时也能自动备份新登录的账户
+    tauri::async_runtime::spawn(async move {
+        db_monitor.start_polling().await;
+    });
+
+    // 监听 Antigravity 进程启停，推送到事件总线
+    events::spawn_process_watcher();
 
     // 初始化窗口事件处理器
     if let Err(e) = window::init_window_event_handler(app) {
@@ -36,7 +40,7 @@ pub fn init(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>
     }
 
     // 检查静默启动设置
-    let settings_manager = app.state::<app_settings::AppSettingsManager>();
+    let settings_manager = app.state::<Arc<app_settings::AppSettingsManager>>();
     let settings = settings_manager.get_settings();
 
     // 根据设置决定是否创建系统托盘
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
 use crate::app_settings::AppSettingsManager;
@@ -14,7 +15,7 @@ impl SystemTrayManager {
     /// 启用系统托盘
     pub fn enable(&self, app_handle: &AppHandle) -> Result<(), String> {
         // 1. 更新设置
-        let settings_manager = app_handle.state::<AppSettingsManager>();
+        let settings_manager = app_handle.state::<Arc<AppSettingsManager>>();
         settings_manager
             .update_settings(|s| s.system_tray_enabled = true)
             .map_err(|e| e.to_string())?;
@@ -38,7 +39,7 @@ impl SystemTrayManager {
     /// 禁用系统托盘
     pub fn disable(&self, app_handle: &AppHandle) -> Result<(), String> {
         // 1. 更新设置
-        let settings_manager = app_handle.state::<AppSettingsManager>();
+        let settings_manager = app_handle.state::<Arc<AppSettingsManager>>();
         settings_manager
             .update_settings(|s| s.system_tray_enabled = false)
             .map_err(|e| e.to_string())?;
@@ -58,7 +59,7 @@ impl SystemTrayManager {
     /// 检查系统托盘是否应启用（基于设置）
     pub fn is_enabled_setting(&self, app_handle: &AppHandle) -> bool {
         app_handle
-            .state::<AppSettingsManager>()
+            .state::<Arc<AppSettingsManager>>()
             .get_settings()
             .system_tray_enabled
     }
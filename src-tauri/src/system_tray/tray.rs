@@ -8,8 +8,9 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::menu::{Menu, MenuBuilder, MenuItem};
 use tauri::tray::{TrayIcon, TrayIconBuilder};
 use tauri::{AppHandle, Emitter, Manager};
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct TrayMenuLabels {
     pub show_main: String,
     pub quit: String,
@@ -93,6 +94,10 @@ fn handle_tray_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
             if let Err(e) = app.emit("tray-switch-account", account_email) {
                 tracing::error!("发射账户切换事件失败: {e}");
             }
+            crate::events::publish(crate::events::AgentEvent::SwitchRequested {
+                account_name: account_email.to_string(),
+                source: "tray".to_string(),
+            });
         }
         _ => {
             tracing::warn!("未处理的菜单事件: {}", event.id.0);
@@ -107,7 +112,7 @@ pub fn update_tray_menu(
     labels: Option<TrayMenuLabels>,
 ) -> Result<(), String> {
     // 检查托盘是否应该启用
-    let settings_manager = app.state::<AppSettingsManager>();
+    let settings_manager = app.state::<std::sync::Arc<AppSettingsManager>>();
     let settings = settings_manager.get_settings();
 
     if !settings.system_tray_enabled {
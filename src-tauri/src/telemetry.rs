@@ -0,0 +1,218 @@
+//! # 运行指标
+//!
+//! 进程内收集的 Prometheus 指标，通过 `GET /metrics`（文本格式 0.0.4）导出，
+//! 不依赖任何外部服务。
+//!
+//! ## 设计
+//!
+//! - 基于 `prometheus` crate，所有指标注册在同一个 [`Registry`] 中
+//! - 通过 `lazy_static` 实现全局单例，与 `CONNECTION_MANAGER`、事件总线一致
+//! - 调用方只使用本模块提供的 `observe_*` / `record_*` 函数，不直接接触指标对象
+//!
+//! ## 指标
+//!
+//! | 名称 | 类型 | 标签 |
+//! | --- | --- | --- |
+//! | `antigravity_agent_http_requests_total` | counter | `method`, `route`, `status` |
+//! | `antigravity_agent_http_request_duration_seconds` | histogram | `method`, `route` |
+//! | `antigravity_agent_account_switches_total` | counter | `result` |
+//! | `antigravity_agent_google_api_requests_total` | counter | `endpoint`, `status` |
+//! | `antigravity_agent_google_api_request_duration_seconds` | histogram | `endpoint` |
+//! | `antigravity_agent_quota_triggers_total` | counter | `outcome` |
+//! | `antigravity_agent_websocket_clients` | gauge | |
+//! | `antigravity_agent_websocket_connections_total` | counter | |
+//! | `antigravity_agent_db_monitor_poll_errors_total` | counter | |
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 指标名前缀
+const NAMESPACE: &str = "antigravity_agent";
+
+/// 本地 API 延迟分桶（秒）：切换账户等操作可能耗时数秒
+const HTTP_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Google API 延迟分桶（秒）
+const GOOGLE_API_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: Registry = Registry::new();
+
+    static ref HTTP_REQUESTS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("http_requests_total", "Local API requests by route and status").namespace(NAMESPACE),
+        &["method", "route", "status"],
+    ));
+
+    static ref HTTP_DURATION: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new("http_request_duration_seconds", "Local API request latency")
+            .namespace(NAMESPACE)
+            .buckets(HTTP_BUCKETS.to_vec()),
+        &["method", "route"],
+    ));
+
+    static ref ACCOUNT_SWITCHES: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("account_switches_total", "Account switches by result").namespace(NAMESPACE),
+        &["result"],
+    ));
+
+    static ref GOOGLE_API_REQUESTS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("google_api_requests_total", "Google API calls by endpoint and HTTP status").namespace(NAMESPACE),
+        &["endpoint", "status"],
+    ));
+
+    static ref GOOGLE_API_DURATION: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new("google_api_request_duration_seconds", "Google API call latency")
+            .namespace(NAMESPACE)
+            .buckets(GOOGLE_API_BUCKETS.to_vec()),
+        &["endpoint"],
+    ));
+
+    static ref QUOTA_TRIGGERS: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("quota_triggers_total", "Quota refresh trigger outcomes per model").namespace(NAMESPACE),
+        &["outcome"],
+    ));
+
+    static ref WEBSOCKET_CLIENTS: IntGauge = register(IntGauge::with_opts(
+        Opts::new("websocket_clients", "Extension clients currently connected over /ws").namespace(NAMESPACE),
+    ));
+
+    static ref WEBSOCKET_CONNECTIONS: IntCounter = register(IntCounter::with_opts(
+        Opts::new("websocket_connections_total", "Extension clients that completed the /ws handshake").namespace(NAMESPACE),
+    ));
+
+    static ref DB_MONITOR_POLL_ERRORS: IntCounter = register(IntCounter::with_opts(
+        Opts::new("db_monitor_poll_errors_total", "Database monitor polls that failed to read state.vscdb").namespace(NAMESPACE),
+    ));
+}
+
+/// 注册指标；指标名是常量，注册失败属于编程错误
+fn register<M>(metric: prometheus::Result<M>) -> M
+where
+    M: prometheus::core::Collector + Clone + 'static,
+{
+    let metric = metric.expect("invalid metric definition");
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("duplicate metric registration");
+    metric
+}
+
+/// 账户切换结果
+#[derive(Debug, Clone, Copy)]
+pub enum SwitchResult {
+    Success,
+    Failure,
+}
+
+/// 单个模型的配额触发结果
+#[derive(Debug, Clone, Copy)]
+pub enum QuotaTriggerOutcome {
+    Triggered,
+    Failed,
+    Skipped,
+    /// 无法获取项目 ID，整个账户被跳过
+    ProjectUnavailable,
+}
+
+/// 记录一次本地 API 请求
+///
+/// `route` 应为路由模板（如 `/api/get_account_metrics`），避免标签基数随参数增长。
+pub fn observe_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    HTTP_REQUESTS
+        .with_label_values(&[method, route, &status.to_string()])
+        .inc();
+    HTTP_DURATION
+        .with_label_values(&[method, route])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 记录一次账户切换
+pub fn record_account_switch(result: SwitchResult) {
+    let label = match result {
+        SwitchResult::Success => "success",
+        SwitchResult::Failure => "failure",
+    };
+    ACCOUNT_SWITCHES.with_label_values(&[label]).inc();
+}
+
+/// 执行并记录一次 Google API 请求
+///
+/// 网络错误（未收到响应）记为 `status="error"`。
+pub async fn observe_google_api<F>(
+    endpoint: &str,
+    request: F,
+) -> Result<reqwest::Response, reqwest::Error>
+where
+    F: Future<Output = Result<reqwest::Response, reqwest::Error>>,
+{
+    let started = Instant::now();
+    let result = request.await;
+
+    let status = match &result {
+        Ok(response) => response.status().as_u16().to_string(),
+        Err(_) => "error".to_string(),
+    };
+    GOOGLE_API_REQUESTS
+        .with_label_values(&[endpoint, &status])
+        .inc();
+    GOOGLE_API_DURATION
+        .with_label_values(&[endpoint])
+        .observe(started.elapsed().as_secs_f64());
+
+    result
+}
+
+/// 记录配额触发结果
+pub fn record_quota_trigger(outcome: QuotaTriggerOutcome, count: usize) {
+    let label = match outcome {
+        QuotaTriggerOutcome::Triggered => "triggered",
+        QuotaTriggerOutcome::Failed => "failed",
+        QuotaTriggerOutcome::Skipped => "skipped",
+        QuotaTriggerOutcome::ProjectUnavailable => "project_unavailable",
+    };
+    QUOTA_TRIGGERS
+        .with_label_values(&[label])
+        .inc_by(count as u64);
+}
+
+/// 扩展客户端完成握手
+pub fn record_websocket_connected(clients: usize) {
+    WEBSOCKET_CONNECTIONS.inc();
+    WEBSOCKET_CLIENTS.set(clients as i64);
+}
+
+/// 扩展客户端断开
+pub fn record_websocket_disconnected(clients: usize) {
+    WEBSOCKET_CLIENTS.set(clients as i64);
+}
+
+/// 数据库监控读取失败
+pub fn record_db_monitor_poll_error() {
+    DB_MONITOR_POLL_ERRORS.inc();
+}
+
+/// 以 Prometheus 文本格式导出全部指标
+pub fn render() -> String {
+    // 无标签的指标在首次访问时才注册，这里确保从未触发过的指标也以 0 导出
+    lazy_static::initialize(&WEBSOCKET_CLIENTS);
+    lazy_static::initialize(&WEBSOCKET_CONNECTIONS);
+    lazy_static::initialize(&DB_MONITOR_POLL_ERRORS);
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&REGISTRY.gather(), &mut buffer) {
+        tracing::error!(target: "telemetry", error = %e, "导出指标失败");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// 导出内容的 Content-Type
+pub fn content_type() -> String {
+    TextEncoder::new().format_type().to_string()
+}
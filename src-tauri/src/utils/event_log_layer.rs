@@ -0,0 +1,67 @@
+/// 将日志转发到事件总线的 tracing Layer
+///
+/// 扩展订阅 `logs` 主题后可以实时看到 Agent 日志，无需打开日志文件。
+/// 只转发 INFO 及以上级别，消息与文件日志一样经过脱敏。
+use crate::events::{AgentEvent, EVENT_BUS};
+use std::fmt::Write;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 事件总线与 WebSocket 推送自身的日志不转发，避免循环
+const SKIPPED_TARGETS: [&str; 3] = ["events", "server::websocket", "server::events"];
+
+/// 事件总线日志 Layer
+pub struct EventLogLayer;
+
+/// 收集日志消息与字段
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for EventLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > Level::INFO || !EVENT_BUS.has_subscribers() {
+            return;
+        }
+
+        let target = metadata.target();
+        if SKIPPED_TARGETS
+            .iter()
+            .any(|skipped| target.contains(skipped))
+        {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = format!("{}{}", visitor.message, visitor.fields);
+
+        EVENT_BUS.publish(AgentEvent::Log {
+            level: metadata.level().to_string(),
+            target: target.to_string(),
+            message: crate::utils::log_sanitizer::sanitize_log_message(&message),
+        });
+    }
+}
@@ -1,8 +1,9 @@
 //! 工具模块
 
 pub mod codec;
-pub mod log_decorator;
+pub mod event_log_layer;
 pub mod log_sanitizer;
+pub mod private_file;
 pub mod sanitizing_layer;
 pub mod tracing_config;
 pub mod user_context_view;
@@ -5,6 +5,7 @@ import { useAntigravityAccount } from './modules/use-antigravity-account.ts';
 import { DATABASE_EVENTS, useDbMonitoringStore } from './modules/db-monitoring-store';
 import { useAntigravityIsRunning } from './hooks/use-antigravity-is-running.ts';
 import { Toaster } from 'react-hot-toast';
+import { listen } from '@tauri-apps/api/event';
 import AppDock from './components/app/AppDock.tsx';
 import { AppContent } from "@/components/app/AppContent.tsx";
 import { AppLoader } from "@/components/app/AppLoader.tsx";
@@ -31,12 +32,14 @@ function App() {
     // 初始化监控（自动启动）
     dbMonitoringActions.start();
 
-    // 添加事件监听器
-    const unlisten = dbMonitoringActions.addListener(DATABASE_EVENTS.DATA_CHANGED, antigravityAccount.insertOrUpdateCurrentAccount);
+    // 添加事件监听器；新登录由后端自动备份，这里只刷新列表
+    const unlisten = dbMonitoringActions.addListener(DATABASE_EVENTS.DATA_CHANGED, antigravityAccount.refresh);
+    const unlistenCaptured = listen('account-captured', () => antigravityAccount.refresh());
 
     // 组件卸载时移除监听器
     return () => {
       unlisten()
+      unlistenCaptured.then(f => f())
       dbMonitoringActions.stop()
     };
   }, []);
@@ -46,7 +49,7 @@ function App() {
 
   useEffect(() => {
     antigravityIsRunning.start();
-    antigravityAccount.insertOrUpdateCurrentAccount()
+    antigravityAccount.refresh()
 
     return () => antigravityIsRunning.stop();
   }, []);
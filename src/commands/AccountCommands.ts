@@ -1,5 +1,11 @@
 import { universalInvoke } from '@/lib/invoke-adapter';
-import { AntigravityAccount, CommandResult } from "@/commands/types/account.types.ts";
+import {
+  AccountMetadata,
+  AccountMetadataUpdate,
+  AccountSecrets,
+  AntigravityAccount,
+  CommandResult,
+} from "@/commands/types/account.types.ts";
 
 type AnyRecord = Record<string, unknown>;
 
@@ -10,6 +16,17 @@ function asRecord(value: unknown): AnyRecord | null {
   return null;
 }
 
+const DEFAULT_METADATA: AccountMetadata = {
+  display_name: null,
+  notes: '',
+  tags: [],
+  color: null,
+  pinned: false,
+  disabled: false,
+  created_at: null,
+  last_switched: null,
+};
+
 function normalizeAccount(raw: unknown): AntigravityAccount | null {
   const record = asRecord(raw);
   if (!record) {
@@ -26,24 +43,21 @@ function normalizeAccount(raw: unknown): AntigravityAccount | null {
     return null;
   }
 
-  const apiKey = authRaw.api_key;
   const authStatus: AntigravityAccount['antigravity_auth_status'] = {
     ...authRaw,
     email: emailValue,
   };
 
-  if (typeof apiKey === 'string') {
-    authStatus.api_key = apiKey;
-  }
-
   if (typeof authRaw.name === 'string') {
     authStatus.name = authRaw.name;
   }
 
   return {
+    account_id: typeof record.account_id === 'string' ? record.account_id : emailValue,
     antigravity_auth_status: authStatus,
     oauth_token: (record.oauth_token ?? null) as AntigravityAccount['oauth_token'],
     user_status: (record.user_status ?? null) as AntigravityAccount['user_status'],
+    metadata: { ...DEFAULT_METADATA, ...(asRecord(record.metadata) ?? {}) },
   };
 }
 
@@ -86,9 +100,30 @@ export class AccountCommands {
     return universalInvoke('save_antigravity_current_account');
   }
 
+  /**
+   * 获取账户的原始令牌（api_key、access / refresh token）
+   * @param accountName 账户 ID 或邮箱
+   */
+  static async revealAccountSecrets(accountName: string): Promise<AccountSecrets> {
+    return universalInvoke('reveal_account_secrets', { accountName });
+  }
+
+  /**
+   * 修改账户元数据
+   * @param accountName 账户 ID 或邮箱
+   * @param update 要修改的字段
+   * @returns 修改后的完整元数据
+   */
+  static async updateAccountMetadata(
+    accountName: string,
+    update: AccountMetadataUpdate,
+  ): Promise<AccountMetadata> {
+    return universalInvoke('update_account_metadata', { accountName, ...update });
+  }
+
   /**
    * 切换到指定账户（完整流程：关闭进程 → 恢复数据 → 重启）
-   * @param accountName 账户名（邮箱）
+   * @param accountName 账户 ID 或邮箱
    * @returns 切换结果消息
    */
   static async switchToAntigravityAccount(accountName: string): Promise<CommandResult> {
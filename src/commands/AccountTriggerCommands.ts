@@ -6,7 +6,7 @@ export class AccountTriggerCommands {
      * Trigger a quota refresh check for the given account.
      * This will send a minimal query ("Hi") to any model with ~100% quota
      * to start the reset timer.
-     * @param email The account email
+     * @param email The account ID or email
      */
     static async triggerQuotaRefresh(email: string): Promise<TriggerResult> {
         return universalInvoke('trigger_quota_refresh', { email });
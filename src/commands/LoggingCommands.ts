@@ -1,3 +1,4 @@
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
 import { universalInvoke } from '@/lib/invoke-adapter';
 import type { FrontendLogEntry } from './types/logging.types';
 
@@ -36,4 +37,14 @@ export class LoggingCommands {
   static async writeTextFile(path: string, content: string): Promise<string> {
     return universalInvoke('write_text_file', { path, content });
   }
+
+  /**
+   * 登记用户在保存对话框中选择的导出位置，之后 writeTextFile 才能写入该目录
+   *
+   * 仅通过 Tauri IPC 调用，HTTP 客户端无法自行扩大可写范围
+   * @param path 保存对话框返回的文件路径
+   */
+  static async allowExportPath(path: string): Promise<void> {
+    return tauriInvoke('allow_export_path', { path });
+  }
 }
@@ -0,0 +1,57 @@
+import { universalInvoke } from '@/lib/invoke-adapter';
+import type { PairingCode, RemoteAccessStatus, RemoteDevice, RemoteDeviceScope } from './types/remote.types';
+
+/**
+ * 局域网远程访问命令
+ */
+export class RemoteAccessCommands {
+  /**
+   * 获取远程访问状态
+   * @returns 开关、监听状态、访问地址与证书指纹
+   */
+  static async getStatus(): Promise<RemoteAccessStatus> {
+    return universalInvoke('get_remote_access_status');
+  }
+
+  /**
+   * 保存远程访问开关（重启后生效）
+   * @param enabled 是否启用
+   * @returns 保存后的状态
+   */
+  static async saveState(enabled: boolean): Promise<boolean> {
+    const result = await universalInvoke<{ value: boolean }>('save_remote_access_state', { enabled });
+    return result.value;
+  }
+
+  /**
+   * 生成一次性配对码，之前的配对码立即失效
+   * @returns 配对码与过期时间
+   */
+  static async createPairingCode(): Promise<PairingCode> {
+    return universalInvoke('create_remote_pairing_code');
+  }
+
+  /**
+   * 列出已配对设备
+   */
+  static async getDevices(): Promise<RemoteDevice[]> {
+    return universalInvoke('get_remote_devices');
+  }
+
+  /**
+   * 修改设备作用域
+   * @param deviceId 设备 ID
+   * @param scope `read` 或 `admin`
+   */
+  static async setDeviceScope(deviceId: string, scope: RemoteDeviceScope): Promise<RemoteDevice> {
+    return universalInvoke('set_remote_device_scope', { device_id: deviceId, scope });
+  }
+
+  /**
+   * 撤销设备，其 Token 立即失效
+   * @param deviceId 设备 ID
+   */
+  static async revokeDevice(deviceId: string): Promise<void> {
+    await universalInvoke('revoke_remote_device', { device_id: deviceId });
+  }
+}
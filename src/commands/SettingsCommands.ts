@@ -1,5 +1,5 @@
 import { universalInvoke } from '@/lib/invoke-adapter';
-import type { AppSettings } from './types/settings.types';
+import type { AppSettings, AutoCaptureSettings } from './types/settings.types';
 
 /**
  * 设置管理命令
@@ -33,6 +33,15 @@ export class SettingsCommands {
     return universalInvoke('save_private_mode_state', { enabled });
   }
 
+  /**
+   * 保存自动备份设置
+   * @param settings 开关与排除的邮箱
+   * @returns 保存后的设置（排除列表已去重、转为小写）
+   */
+  static async saveAutoCaptureSettings(settings: AutoCaptureSettings): Promise<AutoCaptureSettings> {
+    return universalInvoke('save_auto_capture_settings', { ...settings });
+  }
+
   /**
    * 保存 Debug Mode 状态
    * @param enabled 是否启用
@@ -24,7 +24,7 @@ export class TrayCommands {
 
   /**
    * 更新托盘菜单
-   * @param accounts 账户邮箱列表
+   * @param accounts 账户 ID（或邮箱）列表
    * @param labels 菜单标签（多语言）
    * @returns 更新结果消息
    */
@@ -1,12 +1,35 @@
 // Antigravity account response
 export interface AntigravityAccount {
+  // 稳定的账户 ID；隐私模式下邮箱被后端遮盖，切换、删除、配额等操作都使用它
+  account_id: string
   antigravity_auth_status: AntigravityAuthStatus
   oauth_token: OAuthTokenDecoded | null
   user_status: UserStatusDecoded | null
-}
-
+  metadata: AccountMetadata
+}
+
+// 账户元数据：显示名称、备注、标签、颜色、置顶 / 停用，时间为 RFC 3339 字符串
+export interface AccountMetadata {
+  display_name: string | null
+  notes: string
+  tags: string[]
+  color: string | null
+  pinned: boolean
+  disabled: boolean
+  created_at: string | null
+  last_switched: string | null
+}
+
+// 只修改提供的字段；display_name、color 传空字符串时清除
+export type AccountMetadataUpdate = Partial<
+  Pick<AccountMetadata, 'notes' | 'tags' | 'pinned' | 'disabled'> & {
+    display_name: string
+    color: string
+  }
+>
+
+// 后端不会返回 api_key 等令牌，需要时调用 AccountCommands.revealAccountSecrets
 export interface AntigravityAuthStatus {
-  api_key?: string
   email: string
   name?: string
   user_status_proto_binary_base64?: string
@@ -15,12 +38,19 @@ export interface AntigravityAuthStatus {
 
 export interface OAuthTokenDecoded {
   sentinel_key: string
-  access_token: string
-  refresh_token: string
   token_type: string
   expiry_seconds: number | null
 }
 
+// reveal_account_secrets 返回的原始令牌（需要管理 Token）
+export interface AccountSecrets {
+  account_id: string
+  email: string
+  api_key: string | null
+  access_token: string
+  refresh_token: string | null
+}
+
 export type UserStatusDecoded = UserStatusProtoDecoded
 
 export interface UserStatusProtoDecoded {
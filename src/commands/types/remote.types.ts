@@ -0,0 +1,48 @@
+/**
+ * 远程访问相关类型定义
+ */
+
+/** 远程设备 Token 的作用域 */
+export type RemoteDeviceScope = 'read' | 'admin';
+
+/**
+ * 远程访问状态
+ */
+export interface RemoteAccessStatus {
+  /** 设置中是否开启（重启后生效） */
+  enabled: boolean;
+
+  /** 远程监听器是否正在运行 */
+  listening: boolean;
+
+  /** 监听端口 */
+  port: number;
+
+  /** 推测的局域网访问地址 */
+  url: string | null;
+
+  /** TLS 证书的 SHA-256 指纹 */
+  fingerprint: string | null;
+
+  /** 当前配对码的过期时间 */
+  pairing_expires_at: string | null;
+}
+
+/**
+ * 已配对的远程设备
+ */
+export interface RemoteDevice {
+  id: string;
+  name: string;
+  scope: RemoteDeviceScope;
+  paired_at: string;
+  last_seen_at: string | null;
+}
+
+/**
+ * 新生成的配对码
+ */
+export interface PairingCode {
+  code: string;
+  expires_at: string;
+}
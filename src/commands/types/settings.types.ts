@@ -20,4 +20,18 @@ export interface AppSettings {
 
   /** 语言偏好设置 */
   language: string;
+
+  /** 自动备份新登录或令牌已刷新的账户 */
+  autoCaptureEnabled: boolean;
+
+  /** 不自动备份的邮箱 */
+  autoCaptureExclude: string[];
+}
+
+/**
+ * 自动备份设置
+ */
+export interface AutoCaptureSettings {
+  enabled: boolean;
+  exclude: string[];
 }
@@ -38,7 +38,7 @@ export function AppContent() {
   const deleteAccount = useAntigravityAccount((state) => state.delete);
   const clearAllAccounts = useAntigravityAccount((state) => state.clearAllAccounts);
   const switchToAccount = useAntigravityAccount((state) => state.switchToAccount);
-  const insertOrUpdateCurrentAccount = useAntigravityAccount((state) => state.insertOrUpdateCurrentAccount);
+  const refreshAccounts = useAntigravityAccount((state) => state.refresh);
   const accountAdditionData = useAccountAdditionData();
   const currentAntigravityAccount = useCurrentAntigravityAccount();
   const appGlobalLoader = useAppGlobalLoader();
@@ -100,10 +100,10 @@ export function AppContent() {
   }, [accounts.length, t]);
 
   // 由于 Antigravity (>=1.16.5) 仅在程序关闭时保存凭证，您需要先关闭一次 Antigravity 才能完成账户保存。
-  // 所以这里判断下，发现程序关闭下就调用 insertOrUpdateCurrentAccount
+  // 后端数据库监控会在凭证写入后自动备份，这里在进程状态变化时刷新列表
   useEffect(() => {
     antigravityIsRunning.addStatusChangeListener(isRunning => {
-      insertOrUpdateCurrentAccount()
+      refreshAccounts()
     })
   }, []);
 
@@ -126,22 +126,22 @@ export function AppContent() {
         {t('account:delete.message', { email: user.email })}
       </p>,
       onOk() {
-        return confirmDeleteAccount(user.email);
+        return confirmDeleteAccount(user);
       },
       onCancel() {
       },
     });
   };
 
-  const confirmDeleteAccount = async (email: string) => {
-    await deleteAccount(email);
-    toast.success(t('account:delete.success', { email }));
+  const confirmDeleteAccount = async (user: AccountSessionListAccountItem) => {
+    await deleteAccount(user.accountId);
+    toast.success(t('account:delete.success', { email: user.email }));
   };
 
   const handleSwitchAccount = async (user: AccountSessionListAccountItem) => {
     try {
       appGlobalLoader.open({ label: t('account:switch.loading', { email: maskEmail(user.email) }) });
-      await switchToAccount(user.email);
+      await switchToAccount(user.accountId);
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error);
       toast.error(t('account:switch.error', { error: errorMessage }));
@@ -182,7 +182,7 @@ export function AppContent() {
 
 
   const accountsWithData: AccountSessionListAccountItem[] = accounts.map((account) => {
-    const accountAdditionDatum = accountAdditionData.data[account.antigravity_auth_status.email]
+    const accountAdditionDatum = accountAdditionData.data[account.account_id]
 
     return {
       geminiProQuote: accountAdditionDatum?.geminiProQuote ?? -1,
@@ -193,10 +193,10 @@ export function AppContent() {
       geminiImageQuoteRestIn: accountAdditionDatum?.geminiImageQuoteRestIn,
       claudeQuote: accountAdditionDatum?.claudeQuote ?? -1,
       claudeQuoteRestIn: accountAdditionDatum?.claudeQuoteRestIn,
+      accountId: account.account_id,
       email: account.antigravity_auth_status.email,
       nickName: account.antigravity_auth_status.name,
       userAvatar: accountAdditionDatum?.userAvatar ?? "",
-      apiKey: account.antigravity_auth_status.api_key ?? '',
       // 使用后端返回的真实 tier_id，如果获取失败或为 null 则回退到 'free-tier'
       tier: (account.user_status?.raw_data?.plan?.tier_id || 'free-tier') as UserTier,
       persisted: account.oauth_token !== null,
@@ -220,9 +220,9 @@ export function AppContent() {
     })
     .sort((a, b) => {
       // 当前账户始终置顶
-      const currentEmail = currentAntigravityAccount?.antigravity_auth_status.email;
-      if (a.email === currentEmail) return -1;
-      if (b.email === currentEmail) return 1;
+      const currentAccountId = currentAntigravityAccount?.account_id;
+      if (a.accountId === currentAccountId) return -1;
+      if (b.accountId === currentAccountId) return 1;
 
       const nameA = a.nickName || a.email;
       const nameB = b.nickName || b.email;
@@ -275,7 +275,7 @@ export function AppContent() {
           onSwitch={handleSwitchAccount}
           onDelete={handleDeleteBackup}
           onSelect={handleUserClick}
-          currentUserEmail={currentAntigravityAccount?.antigravity_auth_status.email}
+          currentAccountId={currentAntigravityAccount?.account_id}
         />
       </section>
 
@@ -8,6 +8,7 @@ import { logger } from '@/lib/logger.ts';
 import { Modal } from "antd";
 import { AccountSessionListAccountItem } from "@/components/business/AccountSessionList.tsx";
 import { Avatar } from "@/components/ui/avatar.tsx";
+import { AccountCommands } from "@/commands/AccountCommands.ts";
 
 interface BusinessUserDetailProps {
   isOpen: boolean;
@@ -24,9 +25,14 @@ const BusinessUserDetail: React.FC<BusinessUserDetailProps> = ({
   const [copiedField, setCopiedField] = useState<string | null>(null);
 
   // 复制到剪贴板功能
+  // API Key 不随账户列表下发，复制时再单独向后端获取
   const copyToClipboard = async (text: string, fieldName: string) => {
     try {
-      await navigator.clipboard.writeText(account[fieldName]);
+      if (fieldName === 'apiKey') {
+        const secrets = await AccountCommands.revealAccountSecrets(account.accountId);
+        text = secrets.api_key ?? secrets.access_token;
+      }
+      await navigator.clipboard.writeText(text);
       setCopiedField(fieldName);
       setTimeout(() => setCopiedField(null), 2000);
     } catch (error) {
@@ -16,17 +16,17 @@ export interface AccountSessionListAccountItem {
   geminiImageQuoteRestIn: string
   claudeQuote: number | -1
   claudeQuoteRestIn: string
+  accountId: string;
   email: string;
   nickName: string;
   userAvatar: string;
   tier: UserTier;
-  apiKey: string;
   persisted: boolean;
 }
 
 export interface AccountSessionListProps {
   accounts: AccountSessionListAccountItem[];
-  currentUserEmail?: string;
+  currentAccountId?: string;
   onSelect: (user: AccountSessionListAccountItem) => void;
   onSwitch: (user: AccountSessionListAccountItem) => void;
   onDelete: (user: AccountSessionListAccountItem) => void;
@@ -70,7 +70,7 @@ const itemVariants: Variants = {
 
 export function AccountSessionList({
   accounts,
-  currentUserEmail,
+  currentAccountId,
   onSelect,
   onSwitch,
   onDelete,
@@ -92,7 +92,7 @@ export function AccountSessionList({
       <AnimatePresence mode="popLayout">
         {accounts.map((account) => (
           <motion.div
-            key={account.email}
+            key={account.accountId}
             layout // 关键：当有元素被删除时，其他元素会自动平滑移动填补空缺
             variants={itemVariants} // 继承父级的 hidden/show 状态
             className="z-10" // 确保在背景之上
@@ -109,7 +109,7 @@ export function AccountSessionList({
               userAvatar={account.userAvatar}
               tier={account.tier}
               persisted={account.persisted}
-              isCurrentUser={currentUserEmail === account.email}
+              isCurrentUser={currentAccountId === account.accountId}
               email={privateMode ? maskEmail(account.email) : account.email}
               nickName={privateMode ? maskName(account.nickName) : account.nickName}
               onSelect={() => onSelect(account)}
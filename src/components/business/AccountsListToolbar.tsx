@@ -117,7 +117,7 @@ const AccountsListToolbar: React.FC<BusinessListToolbarProps> = ({
           console.log("REFRESH: Triggering for", email);
           toast(t('toolbar.refreshingQuota') + ' ' + email, { id: 'refresh-' + email });
 
-          return AccountTriggerCommands.triggerQuotaRefresh(email)
+          return AccountTriggerCommands.triggerQuotaRefresh(acc.account_id)
             .then(res => {
               console.log("REFRESH: Result for", email, res);
               if (res.triggered_models.length > 0) {
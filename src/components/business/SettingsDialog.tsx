@@ -1,5 +1,5 @@
 import React, { useEffect, useState } from 'react';
-import { Bug, EyeOff, FileCode, FolderOpen, Monitor, Settings, VolumeX } from 'lucide-react';
+import { Bug, EyeOff, FileCode, FolderOpen, KeyRound, Monitor, Settings, Trash2, VolumeX, Wifi } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { getVersion } from '@tauri-apps/api/app';
 import { BaseButton } from '@/components/base-ui/BaseButton';
@@ -8,6 +8,10 @@ import { PlatformCommands } from "@/commands/PlatformCommands.ts";
 import { Modal } from "antd";
 import { useAppSettings } from "@/modules/use-app-settings.ts";
 import { LoggingCommands } from "@/commands/LoggingCommands.ts";
+import { RemoteAccessCommands } from "@/commands/RemoteAccessCommands.ts";
+import type { PairingCode, RemoteAccessStatus, RemoteDevice } from "@/commands/types/remote.types.ts";
+import { logger } from "@/lib/logger.ts";
+import toast from 'react-hot-toast';
 import { useTranslation } from 'react-i18next';
 
 
@@ -186,6 +190,10 @@ const BusinessSettingsDialog: React.FC<BusinessSettingsDialogProps> = ({
 
         <div className="h-px bg-gray-100 dark:bg-gray-800" />
 
+        <RemoteAccessSection isOpen={isOpen} />
+
+        <div className="h-px bg-gray-100 dark:bg-gray-800" />
+
         <div className="space-y-1">
           <a target={"_blank"} href={"https://github.com/MonchiLin/antigravity-agent/issues"}>{t('links.issues')}</a>
         </div>
@@ -285,4 +293,150 @@ const SettingToggle = ({
   </div>
 );
 
+// 内部组件：局域网远程访问（开关、配对码、已配对设备）
+const RemoteAccessSection = ({ isOpen }: { isOpen: boolean }) => {
+  const { t } = useTranslation('settings');
+  const [status, setStatus] = useState<RemoteAccessStatus | null>(null);
+  const [devices, setDevices] = useState<RemoteDevice[]>([]);
+  const [pairing, setPairing] = useState<PairingCode | null>(null);
+  const [toggling, setToggling] = useState(false);
+
+  const refresh = async () => {
+    try {
+      const [nextStatus, nextDevices] = await Promise.all([
+        RemoteAccessCommands.getStatus(),
+        RemoteAccessCommands.getDevices(),
+      ]);
+      setStatus(nextStatus);
+      setDevices(nextDevices);
+    } catch (error) {
+      logger.error('获取远程访问状态失败', {
+        module: 'RemoteAccessSection',
+        error: error instanceof Error ? error.message : String(error)
+      });
+    }
+  };
+
+  useEffect(() => {
+    if (isOpen) {
+      refresh();
+    } else {
+      setPairing(null);
+    }
+  }, [isOpen]);
+
+  // 显示配对码期间轮询：配对成功、过期或输错次数过多后配对码失效
+  useEffect(() => {
+    if (!pairing) return;
+    const timer = setInterval(async () => {
+      const nextStatus = await RemoteAccessCommands.getStatus().catch(() => null);
+      if (!nextStatus || !nextStatus.pairing_expires_at) {
+        setPairing(null);
+        refresh();
+      }
+    }, 2000);
+    return () => clearInterval(timer);
+  }, [pairing]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    try {
+      await action();
+    } catch (error) {
+      toast.error(t('remoteAccess.actionFailed', { error: error instanceof Error ? error.message : String(error) }));
+    }
+    await refresh();
+  };
+
+  const handleToggle = async (enabled: boolean) => {
+    setToggling(true);
+    await run(() => RemoteAccessCommands.saveState(enabled));
+    setToggling(false);
+  };
+
+  const handlePair = () => run(async () => {
+    setPairing(await RemoteAccessCommands.createPairingCode());
+  });
+
+  return (
+    <div className="space-y-3">
+      <SettingToggle
+        icon={<Wifi className="h-4 w-4 text-sky-500" />}
+        title={t('remoteAccess.title')}
+        description={t('remoteAccess.description')}
+        checked={status?.enabled ?? false}
+        onChange={handleToggle}
+        isLoading={toggling || !status}
+      />
+
+      {status && status.enabled !== status.listening && (
+        <div className="px-2 text-xs text-amber-600 dark:text-amber-400">
+          {t('remoteAccess.restartRequired')}
+        </div>
+      )}
+
+      {status?.listening && (
+        <div className="space-y-3 px-2">
+          <div className="space-y-1 text-xs text-gray-500 dark:text-gray-400">
+            <div>
+              {t('remoteAccess.address')}: <span className="font-mono select-all">{status.url ?? `https://<ip>:${status.port}`}</span>
+            </div>
+            {status.fingerprint && (
+              <div className="break-all">
+                {t('remoteAccess.fingerprint')}: <span className="font-mono select-all">{status.fingerprint}</span>
+              </div>
+            )}
+          </div>
+
+          {pairing ? (
+            <div className="rounded-md border border-gray-200 dark:border-gray-800 p-3 text-center space-y-1">
+              <div className="text-2xl font-mono tracking-[0.3em] text-gray-900 dark:text-gray-100 select-all">{pairing.code}</div>
+              <div className="text-xs text-gray-500 dark:text-gray-400">{t('remoteAccess.pairingHint')}</div>
+            </div>
+          ) : (
+            <BaseButton variant="outline" size="sm" onClick={handlePair}>
+              <KeyRound className="h-4 w-4 mr-1.5" />
+              {t('remoteAccess.pair')}
+            </BaseButton>
+          )}
+
+          <div className="space-y-1">
+            <div className="text-sm font-medium text-gray-900 dark:text-gray-100">{t('remoteAccess.devices')}</div>
+            {devices.length === 0 && (
+              <div className="text-xs text-gray-500 dark:text-gray-400">{t('remoteAccess.noDevices')}</div>
+            )}
+            {devices.map(device => (
+              <div key={device.id} className="flex items-center justify-between gap-2 py-1">
+                <div className="min-w-0">
+                  <div className="text-sm text-gray-900 dark:text-gray-100 truncate">{device.name}</div>
+                  <div className="text-xs text-gray-500 dark:text-gray-400">
+                    {device.scope === 'admin' ? t('remoteAccess.scopeAdmin') : t('remoteAccess.scopeRead')}
+                  </div>
+                </div>
+                <div className="flex gap-1 shrink-0">
+                  <BaseButton
+                    variant="outline"
+                    size="sm"
+                    onClick={() => run(() => RemoteAccessCommands.setDeviceScope(device.id, device.scope === 'admin' ? 'read' : 'admin'))}
+                  >
+                    {device.scope === 'admin' ? t('remoteAccess.makeReadOnly') : t('remoteAccess.grantAdmin')}
+                  </BaseButton>
+                  <BaseButton
+                    variant="outline"
+                    size="icon"
+                    className="h-8 w-8"
+                    title={t('remoteAccess.revoke')}
+                    onClick={() => run(() => RemoteAccessCommands.revokeDevice(device.id))}
+                  >
+                    <Trash2 className="h-4 w-4 text-red-500" />
+                  </BaseButton>
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
+
 export default BusinessSettingsDialog;
@@ -53,10 +53,16 @@ export function useTrayMenu() {
   }, [switchToAccount, t]);
 
 
+  // 停用状态变化时同样需要重建菜单
+  const disabledKey = accounts
+    .filter((user) => user.metadata?.disabled)
+    .map((user) => user.account_id)
+    .join(',');
+
   // 当账户列表或语言变化时更新托盘菜单
   useEffect(() => {
-    // 提取邮箱列表并更新托盘菜单
-    const emails = accounts.map((user) => user.antigravity_auth_status.email);
-    updateTrayMenu(emails);
-  }, [accounts.length, updateTrayMenu, i18n.language]);
+    // 隐私模式下邮箱已被后端遮盖，传账户 ID，由后端解析为账户名并打码显示；停用的账户由后端过滤
+    const accountIds = accounts.map((user) => user.account_id);
+    updateTrayMenu(accountIds);
+  }, [accounts.length, disabledKey, updateTrayMenu, i18n.language]);
 }
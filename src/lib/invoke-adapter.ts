@@ -1,12 +1,68 @@
 
 
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
+
 // 检测是否在 VS Code 扩展环境中运行
 // 我们可以通过检查是否存在 __TAURI__ 对象来判断，或者使用环境变量
 // 假设 VS Code 扩展会注入一个特定的全局变量或者我们构建时设置 VITE_ENV
 const isExtension = !('__TAURI_INTERNALS__' in window) && !('__TAURI__' in window); // 简单的启发式检查
 
-// 本地服务器地址 (与 main.rs 中配置的一致)
-const SERVER_URL = 'http://127.0.0.1:56789/api';
+// 本地服务器默认地址 (端口可在 app_settings.json 的 api_tcp_port 中修改)
+const DEFAULT_SERVER_URL = 'http://127.0.0.1:56789/api';
+
+declare global {
+  interface Window {
+    /** VS Code 扩展注入的本地 API Token */
+    ANTIGRAVITY_API_TOKEN?: string;
+    /** VS Code 扩展注入的本地 API 地址 */
+    ANTIGRAVITY_API_BASE_URL?: string;
+  }
+}
+
+let apiTokenPromise: Promise<string> | null = null;
+let serverUrlPromise: Promise<string> | null = null;
+
+/**
+ * 获取本地 API 地址
+ * - VS Code 扩展：由扩展在 HTML 中注入 window.ANTIGRAVITY_API_BASE_URL
+ * - Tauri：通过 IPC 命令 get_api_base_url 获取（只获取一次）
+ */
+function getServerUrl(): Promise<string> {
+  if (window.ANTIGRAVITY_API_BASE_URL) {
+    return Promise.resolve(window.ANTIGRAVITY_API_BASE_URL);
+  }
+  if (isExtension) {
+    return Promise.resolve(DEFAULT_SERVER_URL);
+  }
+  if (!serverUrlPromise) {
+    serverUrlPromise = tauriInvoke<string>('get_api_base_url').catch((error) => {
+      serverUrlPromise = null;
+      throw error;
+    });
+  }
+  return serverUrlPromise;
+}
+
+/**
+ * 获取本地 API Token
+ * - VS Code 扩展：由扩展在 HTML 中注入 window.ANTIGRAVITY_API_TOKEN
+ * - Tauri：通过 IPC 命令 get_api_token 获取（只获取一次）
+ */
+function getApiToken(): Promise<string> {
+  if (window.ANTIGRAVITY_API_TOKEN) {
+    return Promise.resolve(window.ANTIGRAVITY_API_TOKEN);
+  }
+  if (isExtension) {
+    return Promise.resolve('');
+  }
+  if (!apiTokenPromise) {
+    apiTokenPromise = tauriInvoke<string>('get_api_token').catch((error) => {
+      apiTokenPromise = null;
+      throw error;
+    });
+  }
+  return apiTokenPromise;
+}
 
 /**
  * 通用命令调用适配器
@@ -31,6 +87,8 @@ const POST_COMMANDS = new Set([
   'clear_all_antigravity_data',
   'sign_in_new_antigravity_account',
   'trigger_quota_refresh',
+  'reveal_account_secrets',
+  'update_account_metadata',
   'restore_backup_files',
   'delete_backup',
   'clear_all_backups',
@@ -38,6 +96,7 @@ const POST_COMMANDS = new Set([
   'save_silent_start_state',
   'save_private_mode_state',
   'save_debug_mode_state',
+  'save_auto_capture_settings',
   'set_language',
   'validate_antigravity_executable',
   'save_antigravity_executable',
@@ -48,6 +107,10 @@ const POST_COMMANDS = new Set([
   'restore_from_tray',
   'start_database_monitoring',
   'stop_database_monitoring',
+  'save_remote_access_state',
+  'create_remote_pairing_code',
+  'set_remote_device_scope',
+  'revoke_remote_device',
 ]);
 
 // 在 HTTP 模式下忽略的命令（返回 undefined）
@@ -71,13 +134,14 @@ async function httpInvoke<T>(cmd: string, args?: Record<string, any>): Promise<T
   }
 
   // 直接使用命令名作为路由
-  const url = `${SERVER_URL}/${cmd}`;
+  const url = `${await getServerUrl()}/${cmd}`;
   const method = POST_COMMANDS.has(cmd) ? 'POST' : 'GET';
 
   const options: RequestInit = {
     method,
     headers: {
       'Content-Type': 'application/json',
+      'Authorization': `Bearer ${await getApiToken()}`,
     },
   };
 
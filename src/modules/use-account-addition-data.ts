@@ -30,11 +30,12 @@ export const useAccountAdditionData = create<State & Actions>((setState, getStat
   data: {},
   update: async (antigravityAccount: AntigravityAccount) => {
     const email = antigravityAccount.antigravity_auth_status.email;
+    const accountId = antigravityAccount.account_id;
 
     try {
       logger.debug(`开始获取账户指标 (Rust Singular): ${email}`);
 
-      const metric = await AccountMetricsCommands.getAccountMetrics(email);
+      const metric = await AccountMetricsCommands.getAccountMetrics(accountId);
 
       // 映射 Rust 数据结构 -> 前端 Store 结构
       // 注意：后端返回的 quotas 数组需要转换为具名字段
@@ -56,7 +57,7 @@ export const useAccountAdditionData = create<State & Actions>((setState, getStat
       setState({
         data: {
           ...getState().data,
-          [email]: {
+          [accountId]: {
             geminiProQuote: geminiPro.percentage,
             geminiProQuoteRestIn: geminiPro.resetText,
             geminiFlashQuote: geminiFlash.percentage,
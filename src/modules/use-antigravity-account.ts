@@ -15,10 +15,12 @@ export interface AntigravityAccountState {
 
 // Store Actions
 export interface AntigravityAccountActions {
-  // 基础操作
-  delete: (email: string) => Promise<void>;
+  // 基础操作（账户用 account_id 指定，也兼容邮箱）
+  delete: (accountId: string) => Promise<void>;
   insertOrUpdateCurrentAccount: () => Promise<void>;
-  switchToAccount: (email: string) => Promise<void>;
+  // 重新读取账户列表与当前账户（不备份，自动备份由后端完成）
+  refresh: () => Promise<void>;
+  switchToAccount: (accountId: string) => Promise<void>;
 
   // 批量操作
   clearAllAccounts: () => Promise<void>;
@@ -34,9 +36,9 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
   currentAuthInfo: null,
 
   // ============ 基础操作 ============
-  delete: async (email: string): Promise<void> => {
+  delete: async (accountId: string): Promise<void> => {
     try {
-      await AccountManageCommands.deleteBackup(email);
+      await AccountManageCommands.deleteBackup(accountId);
 
       // 删除成功后重新获取数据
       const accounts = await AccountCommands.getAntigravityAccounts();
@@ -44,7 +46,7 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
     } catch (error) {
       logger.error('用户删除失败', {
         module: 'UserManagement',
-        email,
+        accountId,
         error: error instanceof Error ? error.message : String(error)
       });
       throw error;
@@ -56,8 +58,8 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
       // 1. 获取当前 Antigravity 用户信息
       const currentInfo = await AccountCommands.getCurrentAntigravityAccount();
 
-      // 2. 检查是否有有效的用户信息（通过API Key或用户状态判断）
-      if (currentInfo?.antigravity_auth_status?.api_key) {
+      // 2. 检查是否有有效的用户信息（后端不返回 API Key，以邮箱判断）
+      if (currentInfo?.antigravity_auth_status?.email) {
         // 3. 执行保存操作
         await AccountCommands.saveAntigravityCurrentAccount();
 
@@ -82,14 +84,28 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
     }
   },
 
-  switchToAccount: async (email: string): Promise<void> => {
+  refresh: async (): Promise<void> => {
+    try {
+      const accounts = await AccountCommands.getAntigravityAccounts();
+      set({ accounts });
+      const currentInfo = await AccountCommands.getCurrentAntigravityAccount();
+      set({ currentAuthInfo: currentInfo });
+    } catch (error) {
+      logger.warn('刷新账户列表失败', {
+        module: 'UserManagement',
+        error: error instanceof Error ? error.message : String(error)
+      });
+    }
+  },
+
+  switchToAccount: async (accountId: string): Promise<void> => {
     try {
       // 调用后端切换用户命令
-      await AccountCommands.switchToAntigravityAccount(email);
+      await AccountCommands.switchToAntigravityAccount(accountId);
     } catch (error) {
       logger.error('切换用户失败', {
         module: 'UserManagement',
-        email,
+        accountId,
         error: error instanceof Error ? error.message : String(error)
       });
       throw error;
@@ -132,6 +148,6 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
 export const useCurrentAntigravityAccount: () => AntigravityAccount | undefined = () =>
   useAntigravityAccount((state) =>
     state.accounts.find(
-      (user) => user.antigravity_auth_status?.email === state.currentAuthInfo?.antigravity_auth_status?.email
+      (user) => user.account_id === state.currentAuthInfo?.account_id
     )
   );
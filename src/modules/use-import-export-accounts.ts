@@ -208,7 +208,8 @@ export const useImportExportAccount = create<ConfigState & ConfigActions>()(
             return;
           }
 
-          // 保存加密文件
+          // 保存加密文件（先登记用户选择的目录，后端只允许写入已登记的导出目录）
+          await LoggingCommands.allowExportPath(savePath);
           await LoggingCommands.writeTextFile(savePath, encryptedData);
 
           toast.success(i18n.t('notifications:backup.saveSuccess', { path: savePath }));
@@ -44,6 +44,7 @@ const seedMocks = (
     getAccounts: async () => accounts,
     delete: async () => { },
     insertOrUpdateCurrentAccount: async () => { },
+    refresh: async () => { },
     switchToAccount: async () => { },
     clearAllAccounts: async () => { },
   });
@@ -178,6 +179,7 @@ export const ActiveAccountPinned: Story = {
       getAccounts: async () => mockAccounts,
       delete: async () => { },
       insertOrUpdateCurrentAccount: async () => { },
+      refresh: async () => { },
       switchToAccount: async () => { },
       clearAllAccounts: async () => { },
     });
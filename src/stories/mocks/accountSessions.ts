@@ -11,7 +11,6 @@ type BaseMockAccount = {
   tier: UserTier;
   nickName?: string;
   userAvatar?: string;
-  apiKey?: string;
   idToken?: string;
   quotas?: Partial<AccountAdditionData>;
 };
@@ -166,17 +165,26 @@ const baseMockAccounts: BaseMockAccount[] = [
 
 
 function makeAccount(base: BaseMockAccount): AntigravityAccount {
-  const [local] = base.email.split('@');
   return {
+    account_id: base.email,
     antigravity_auth_status: {
-      api_key: base.apiKey ?? `sk_mock_${local}`,
       email: base.email,
       name: base.nickName || base.planName,
       // Mocking nested properties if needed for backward compatibility or future use,
       // but based on current type definition, we only need core fields.
     },
     oauth_token: null,
-    user_status: null
+    user_status: null,
+    metadata: {
+      display_name: null,
+      notes: '',
+      tags: [],
+      color: null,
+      pinned: false,
+      disabled: false,
+      created_at: null,
+      last_switched: null,
+    },
   };
 }
 
@@ -194,6 +202,7 @@ function makeSessionItem(
   const [local] = base.email.split('@');
   return {
     nickName: base.nickName ?? local,
+    accountId: base.email,
     email: base.email,
     userAvatar: addition.userAvatar ?? defaultQuotas.userAvatar,
     geminiProQuote: addition.geminiProQuote,
@@ -205,7 +214,6 @@ function makeSessionItem(
     claudeQuote: addition.claudeQuote,
     claudeQuoteRestIn: addition.claudeQuoteRestIn,
     tier: base.tier,
-    apiKey: `sk_${local}`,
     persisted: true,
   };
 }
@@ -219,9 +227,9 @@ function buildGridItems(
     const [name, domain] = base.email.split('@');
     return {
       ...base,
+      accountId: `${name}+${i}@${domain}`,
       email: `${name}+${i}@${domain}`,
       nickName: `${base.nickName} #${i + 1}`,
-      apiKey: `${base.apiKey}_${i}`,
     };
   });
 }
@@ -229,6 +237,7 @@ function buildGridItems(
 const longEmailItem: AccountSessionListAccountItem = {
   nickName:
     'ThisIsAnExcessivelyLongNickName_ToTest_TextOverflow_AndLayoutStability_InUserCardHeader',
+  accountId: 'long-email',
   email:
     'this.is.a.super.long.email.address.with.many.sections.and.tags+storybook-overflow-test@subdomain1.subdomain2.subdomain3.subdomain4.some-very-long-company-domain.example.corp.company.com',
   userAvatar: 'https://api.dicebear.com/9.x/avataaars/svg?seed=LongEmail',
@@ -241,7 +250,6 @@ const longEmailItem: AccountSessionListAccountItem = {
   claudeQuote: 0.77,
   claudeQuoteRestIn: '2025-12-22T09:00:00Z',
   tier: 'g1-pro-tier',
-  apiKey: 'sk_mock_long_email',
   persisted: true,
 };
 
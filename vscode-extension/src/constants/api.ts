@@ -2,9 +2,21 @@
  * Shared constants for API configuration
  */
 export const API_CONFIG = {
-    BASE_URL: 'http://127.0.0.1:56789/api',
     ENDPOINTS: {
         GET_CURRENT_ACCOUNT: 'get_current_antigravity_account_info',
         GET_METRICS: 'get_account_metrics',
     }
 };
+
+
+/**
+ * Agent methods callable over the WebSocket connection (JSON-RPC 2.0)
+ */
+export const RPC_METHODS = {
+    LIST_ACCOUNTS: 'accounts.list',
+    GET_CURRENT_ACCOUNT: 'accounts.current',
+    SWITCH_ACCOUNT: 'accounts.switch',
+    BACKUP_CURRENT: 'accounts.backup_current',
+    GET_METRICS: 'metrics.get',
+    REFRESH_QUOTA: 'quota.refresh',
+};
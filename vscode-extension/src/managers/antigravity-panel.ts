@@ -1,9 +1,12 @@
 import * as vscode from 'vscode';
 import * as fs from 'fs';
 import { Logger } from '../utils/logger';
+import { getAdminToken } from '../utils/api-token';
+import { getApiBaseUrl, getApiOrigins } from '../utils/agent-endpoint';
 import { AutoAcceptManager } from './auto-accept-manager';
 import { TranslationManager } from './translation-manager';
 import { StatusBarManager } from './status-bar-manager';
+import { getWebSocketClient } from '../services/websocket-client';
 
 // Declare global function injected by Vite build or shim
 // declare const __getWebviewHtml__: (options: any) => string;
@@ -155,6 +158,18 @@ export class AntigravityPanel {
             null,
             this._disposables
         );
+
+        // Forward account and quota events pushed by the agent to the webview
+        const client = getWebSocketClient();
+        void client.subscribe(['accounts', 'quotas']);
+        this._disposables.push(client.onEvent(event => {
+            if (event.topic === 'accounts' || event.topic === 'quotas') {
+                this._panel.webview.postMessage({ command: 'agentEvent', ...event });
+            }
+        }));
+        this._disposables.push(client.onReadyChange(enabled => {
+            this._panel.webview.postMessage({ command: 'agentPushState', enabled });
+        }));
     }
 
     public dispose() {
@@ -188,6 +203,10 @@ export class AntigravityPanel {
                 command: 'showAccountState',
                 enabled: config.get<boolean>('showAccount', true)
             });
+            this._panel.webview.postMessage({
+                command: 'agentPushState',
+                enabled: getWebSocketClient().isReady()
+            });
         }, 100);
     }
 
@@ -205,13 +224,14 @@ export class AntigravityPanel {
             // Replace Placeholders using strict CSP logic
             html = html.replace(/{{cspSource}}/g, webview.cspSource);
             html = html.replace(/{{nonce}}/g, nonce);
+            html = html.replace(/{{apiOrigins}}/g, getApiOrigins());
 
             // Fix absolute asset paths to webview URIs
             const rootUri = webview.asWebviewUri(distPath);
             html = html.replace(/(href|src)="(\.?\/)?assets\//g, `$1="${rootUri}/assets/`);
 
             // Inject Language
-            const languageScript = `<script nonce="${nonce}">window.VSCODE_LANGUAGE = "${vscode.env.language}";</script>`;
+            const languageScript = `<script nonce="${nonce}">window.VSCODE_LANGUAGE = "${vscode.env.language}"; window.ANTIGRAVITY_API_TOKEN = ${JSON.stringify(getAdminToken())}; window.ANTIGRAVITY_API_BASE_URL = ${JSON.stringify(getApiBaseUrl())};</script>`;
             html = html.replace('</head>', `${languageScript}</head>`);
 
             return html;
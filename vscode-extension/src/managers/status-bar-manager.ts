@@ -4,7 +4,10 @@ import { Logger } from '../utils/logger';
 import { AccountMetrics, AntigravityAccount } from '@/commands/types/account.types';
 import { getQuotaCategory } from '../constants/model-mappings';
 import { TranslationManager } from './translation-manager';
-import { API_CONFIG } from '../constants/api';
+import { API_CONFIG, RPC_METHODS } from '../constants/api';
+import { getReadOnlyToken } from '../utils/api-token';
+import { agentFetch } from '../utils/agent-endpoint';
+import { AgentEventParams, getWebSocketClient } from '../services/websocket-client';
 // Dynamic import or require is used inside render to avoid top-level issues if needed, 
 // but standard import is better if file exists. 
 // However, since we just added the file, let's use standard import.
@@ -18,7 +21,6 @@ export class StatusBarManager {
     private static interval: NodeJS.Timeout | undefined;
     private static metricsItem: vscode.StatusBarItem; // Display Model & Quota
     private static userItem: vscode.StatusBarItem;    // Display User Email
-    private static readonly API_BASE = API_CONFIG.BASE_URL;
 
     private static currentMetrics: AccountMetrics | null = null;
     private static currentAccount: AntigravityAccount | null = null;
@@ -49,6 +51,21 @@ export class StatusBarManager {
         this.startPolling();
         context.subscriptions.push({ dispose: () => this.stopPolling() });
 
+        // Prefer pushed updates from the agent; fall back to polling while disconnected
+        const client = getWebSocketClient();
+        void client.subscribe(['accounts', 'quotas']);
+        context.subscriptions.push(client.onReadyChange(ready => {
+            if (ready) {
+                Logger.log('[StatusBar] Agent push connected, polling stopped');
+                this.stopPolling();
+                this.requestUpdate();
+            } else {
+                this.currentPollDuration = 30000;
+                this.startPolling();
+            }
+        }));
+        context.subscriptions.push(client.onEvent(event => this.handleAgentEvent(event)));
+
         // Listen for configuration changes
         context.subscriptions.push(vscode.workspace.onDidChangeConfiguration(e => {
             const config = vscode.workspace.getConfiguration('antigravity-agent');
@@ -120,11 +137,38 @@ export class StatusBarManager {
         }
     }
 
+    /**
+     * Applies account and quota updates pushed by the agent.
+     */
+    private static handleAgentEvent(event: AgentEventParams) {
+        switch (event.name) {
+            case 'account_changed':
+                this.currentMetrics = null;
+                this.requestUpdate();
+                break;
+            case 'switch_progress':
+                if (event.data?.stage === 'completed') {
+                    this.requestUpdate();
+                }
+                break;
+            case 'quota_updated':
+                if (event.data?.account_id && event.data.account_id === this.currentAccount?.account_id) {
+                    this.currentMetrics = event.data.metrics as AccountMetrics;
+                    this.render(this.currentMetrics, this.currentAccount ?? undefined);
+                }
+                break;
+        }
+    }
+
     private static startPolling(intervalMs: number = 30000, triggerImmediate: boolean = true) {
         this.stopPolling();
         if (triggerImmediate) {
             this.requestUpdate();
         }
+        // The agent pushes updates while the WebSocket is ready
+        if (getWebSocketClient().isReady()) {
+            return;
+        }
         // Poll
         this.interval = setInterval(() => this.requestUpdate(), intervalMs);
     }
@@ -166,7 +210,7 @@ export class StatusBarManager {
         const t = TranslationManager.getInstance().t.bind(TranslationManager.getInstance());
         try {
             // 1. Get Current Account
-            const accRes = await fetch(`${this.API_BASE}/${API_CONFIG.ENDPOINTS.GET_CURRENT_ACCOUNT}`);
+            const currentAccount = await this.fetchCurrentAccount();
 
             // Connection successful - reset warning visual
             this.metricsItem.color = undefined;
@@ -174,8 +218,6 @@ export class StatusBarManager {
             this.metricsItem.tooltip = undefined; // clear previous error tooltip
             this.userItem.color = undefined;
 
-            if (!accRes.ok) throw new Error('Failed to fetch account info');
-            const currentAccount = await accRes.json() as AntigravityAccount | null;
             this.currentAccount = currentAccount;
 
             // Switch back to normal polling (30s) only after a successful response.
@@ -196,19 +238,15 @@ export class StatusBarManager {
             const email = currentAccount.antigravity_auth_status.email;
 
             // 2. Get Metrics
-            const metricRes = await fetch(`${this.API_BASE}/${API_CONFIG.ENDPOINTS.GET_METRICS}`, {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({ email })
-            });
+            const metrics = await this.fetchMetrics(currentAccount.account_id ?? email);
 
-            if (!metricRes.ok) {
+            if (!metrics) {
                 this.metricsItem.tooltip = `Current: ${email}\n${t('status.failedMetrics')}`;
                 this.userItem.text = `$(account) ${email}`;
                 return;
             }
 
-            this.currentMetrics = await metricRes.json() as AccountMetrics;
+            this.currentMetrics = metrics;
             this.render(this.currentMetrics, currentAccount);
 
         } catch (error) {
@@ -237,6 +275,53 @@ export class StatusBarManager {
         }
     }
 
+    /**
+     * Fetches the current account, preferring the extension WebSocket (JSON-RPC)
+     * and falling back to the HTTP API while the socket is not ready.
+     * Throws when the agent is unreachable.
+     */
+    private static async fetchCurrentAccount(): Promise<AntigravityAccount | null> {
+        const client = getWebSocketClient();
+        if (client.isReady()) {
+            try {
+                return await client.call<AntigravityAccount | null>(RPC_METHODS.GET_CURRENT_ACCOUNT);
+            } catch (e) {
+                Logger.log('[StatusBar] RPC accounts.current failed, falling back to HTTP', e);
+            }
+        }
+
+        const accRes = await agentFetch(API_CONFIG.ENDPOINTS.GET_CURRENT_ACCOUNT, {
+            token: getReadOnlyToken()
+        });
+        if (!accRes.ok) throw new Error('Failed to fetch account info');
+        return await accRes.json() as AntigravityAccount | null;
+    }
+
+    /**
+     * Fetches quota metrics for the given account (`account_id` or email).
+     * Returns null when the agent reports an error (e.g. expired token) so the
+     * caller can show a hint.
+     */
+    private static async fetchMetrics(account: string): Promise<AccountMetrics | null> {
+        const client = getWebSocketClient();
+        if (client.isReady()) {
+            try {
+                return await client.call<AccountMetrics>(RPC_METHODS.GET_METRICS, { email: account });
+            } catch (e) {
+                Logger.log('[StatusBar] RPC metrics.get failed', e);
+                return null;
+            }
+        }
+
+        const metricRes = await agentFetch(API_CONFIG.ENDPOINTS.GET_METRICS, {
+            method: 'POST',
+            token: getReadOnlyToken(),
+            body: { email: account }
+        });
+        if (!metricRes.ok) return null;
+        return await metricRes.json() as AccountMetrics;
+    }
+
     // ... inside render method ...
     private static render(metrics: AccountMetrics, currentAccount?: AntigravityAccount) {
         if (!metrics) return;
@@ -0,0 +1,270 @@
+import * as crypto from 'crypto';
+import * as http from 'http';
+import type { Socket } from 'net';
+import { getAgentSocketPath, getAgentTcpPort } from '../utils/agent-endpoint';
+
+/**
+ * # Agent WebSocket 传输层
+ *
+ * 为 websocket-client 提供统一的连接对象：
+ *
+ * - Agent 的 Unix socket（`~/.antigravity-agent/agent.sock`）存在时，通过 socket 建立 WebSocket。
+ *   Node 内置的 WebSocket 不支持 Unix socket，这里实现了客户端所需的最小协议子集
+ *   （握手、文本帧、分片、ping/pong、close）。
+ * - 否则使用内置 WebSocket 连接 TCP 端口。
+ *
+ * @module agent-socket
+ */
+
+/** 连接事件回调 */
+export interface AgentSocketHandlers {
+    onOpen(): void;
+    onClose(code: number, reason: string): void;
+    onError(error: unknown): void;
+    onMessage(data: string): void;
+}
+
+/** 已建立（或正在建立）的连接 */
+export interface AgentSocket {
+    /** 连接是否已打开 */
+    isOpen(): boolean;
+    send(data: string): void;
+    close(): void;
+}
+
+/**
+ * 连接 Agent 的 `/ws`
+ *
+ * @param token - 管理 Token（通过查询参数传递）
+ * @param handlers - 连接事件回调
+ */
+export function openAgentSocket(token: string, handlers: AgentSocketHandlers): AgentSocket {
+    const requestPath = `/ws?access_token=${encodeURIComponent(token)}`;
+    const socketPath = getAgentSocketPath();
+    if (socketPath) {
+        return new UnixWebSocket(socketPath, requestPath, handlers);
+    }
+    return new TcpWebSocket(`ws://127.0.0.1:${getAgentTcpPort()}${requestPath}`, handlers);
+}
+
+/** 内置 WebSocket（TCP） */
+class TcpWebSocket implements AgentSocket {
+    private readonly ws: WebSocket;
+
+    constructor(url: string, handlers: AgentSocketHandlers) {
+        this.ws = new WebSocket(url);
+        this.ws.onopen = () => handlers.onOpen();
+        this.ws.onclose = (event) => handlers.onClose(event.code, event.reason);
+        this.ws.onerror = (error) => handlers.onError(error);
+        this.ws.onmessage = (event) => handlers.onMessage(String(event.data));
+    }
+
+    public isOpen(): boolean {
+        return this.ws.readyState === WebSocket.OPEN;
+    }
+
+    public send(data: string): void {
+        this.ws.send(data);
+    }
+
+    public close(): void {
+        this.ws.close();
+    }
+}
+
+// =============================================================================
+// Unix socket 上的 WebSocket（RFC 6455 客户端子集）
+// =============================================================================
+
+const WS_GUID = '258EAFA5-E914-47DA-95CA-C5AB0DC85B11';
+
+const OPCODE_CONTINUATION = 0x0;
+const OPCODE_TEXT = 0x1;
+const OPCODE_BINARY = 0x2;
+const OPCODE_CLOSE = 0x8;
+const OPCODE_PING = 0x9;
+const OPCODE_PONG = 0xa;
+
+/** 未收到 close 帧时的关闭码（与浏览器 WebSocket 一致） */
+const CLOSE_ABNORMAL = 1006;
+
+class UnixWebSocket implements AgentSocket {
+    private readonly request: http.ClientRequest;
+    private socket: Socket | null = null;
+    private open = false;
+    private closed = false;
+    private buffer: Buffer = Buffer.alloc(0);
+    private fragments: Buffer[] = [];
+    private closeCode = CLOSE_ABNORMAL;
+    private closeReason = '';
+
+    constructor(socketPath: string, requestPath: string, private readonly handlers: AgentSocketHandlers) {
+        const key = crypto.randomBytes(16).toString('base64');
+        const req = this.request = http.request({
+            socketPath,
+            path: requestPath,
+            headers: {
+                'Connection': 'Upgrade',
+                'Upgrade': 'websocket',
+                'Sec-WebSocket-Version': '13',
+                'Sec-WebSocket-Key': key,
+            },
+        });
+
+        req.on('upgrade', (res, socket, head) => {
+            const expected = crypto.createHash('sha1').update(key + WS_GUID).digest('base64');
+            if (res.headers['sec-websocket-accept'] !== expected) {
+                socket.destroy();
+                this.fail(new Error('Invalid Sec-WebSocket-Accept'));
+                return;
+            }
+
+            this.socket = socket;
+            this.open = true;
+            socket.on('data', (chunk: Buffer) => this.receive(chunk));
+            socket.on('close', () => this.finish());
+            socket.on('error', (error) => this.handlers.onError(error));
+            this.handlers.onOpen();
+            if (head.length > 0) {
+                this.receive(head);
+            }
+        });
+
+        // 未升级（如 401）时服务端返回普通 HTTP 响应
+        req.on('response', (res) => {
+            res.resume();
+            this.fail(new Error(`Unexpected response: ${res.statusCode}`));
+        });
+        req.on('error', (error) => this.fail(error));
+        req.end();
+    }
+
+    public isOpen(): boolean {
+        return this.open;
+    }
+
+    public send(data: string): void {
+        this.writeFrame(OPCODE_TEXT, Buffer.from(data, 'utf-8'));
+    }
+
+    public close(): void {
+        if (this.open) {
+            const payload = Buffer.alloc(2);
+            payload.writeUInt16BE(1000, 0);
+            this.writeFrame(OPCODE_CLOSE, payload);
+            this.open = false;
+        }
+        if (this.socket) {
+            this.socket.end();
+        } else {
+            // 握手尚未完成
+            this.request.destroy();
+        }
+    }
+
+    private fail(error: unknown): void {
+        this.handlers.onError(error);
+        this.finish();
+    }
+
+    private finish(): void {
+        if (this.closed) {
+            return;
+        }
+        this.closed = true;
+        this.open = false;
+        this.handlers.onClose(this.closeCode, this.closeReason);
+    }
+
+    /** 客户端发出的帧必须加掩码 */
+    private writeFrame(opcode: number, payload: Buffer): void {
+        if (!this.socket || !this.open) {
+            return;
+        }
+
+        const length = payload.length;
+        const header = length < 126 ? Buffer.alloc(2) : length < 0x10000 ? Buffer.alloc(4) : Buffer.alloc(10);
+        header[0] = 0x80 | opcode;
+        if (length < 126) {
+            header[1] = 0x80 | length;
+        } else if (length < 0x10000) {
+            header[1] = 0x80 | 126;
+            header.writeUInt16BE(length, 2);
+        } else {
+            header[1] = 0x80 | 127;
+            header.writeBigUInt64BE(BigInt(length), 2);
+        }
+
+        const mask = crypto.randomBytes(4);
+        const masked = Buffer.alloc(length);
+        for (let i = 0; i < length; i++) {
+            masked[i] = payload[i] ^ mask[i % 4];
+        }
+        this.socket.write(Buffer.concat([header, mask, masked]));
+    }
+
+    private receive(chunk: Buffer): void {
+        this.buffer = Buffer.concat([this.buffer, chunk]);
+
+        while (this.buffer.length >= 2) {
+            const fin = (this.buffer[0] & 0x80) !== 0;
+            const opcode = this.buffer[0] & 0x0f;
+            const masked = (this.buffer[1] & 0x80) !== 0;
+            let length = this.buffer[1] & 0x7f;
+            let offset = 2;
+
+            if (length === 126) {
+                if (this.buffer.length < 4) return;
+                length = this.buffer.readUInt16BE(2);
+                offset = 4;
+            } else if (length === 127) {
+                if (this.buffer.length < 10) return;
+                length = Number(this.buffer.readBigUInt64BE(2));
+                offset = 10;
+            }
+
+            const maskOffset = offset;
+            if (masked) offset += 4;
+            if (this.buffer.length < offset + length) return;
+
+            let payload = this.buffer.subarray(offset, offset + length);
+            if (masked) {
+                const mask = this.buffer.subarray(maskOffset, maskOffset + 4);
+                payload = Buffer.from(payload.map((byte, i) => byte ^ mask[i % 4]));
+            }
+            this.buffer = this.buffer.subarray(offset + length);
+
+            this.handleFrame(fin, opcode, Buffer.from(payload));
+        }
+    }
+
+    private handleFrame(fin: boolean, opcode: number, payload: Buffer): void {
+        switch (opcode) {
+            case OPCODE_TEXT:
+            case OPCODE_BINARY:
+            case OPCODE_CONTINUATION:
+                this.fragments.push(payload);
+                if (fin) {
+                    const message = Buffer.concat(this.fragments).toString('utf-8');
+                    this.fragments = [];
+                    this.handlers.onMessage(message);
+                }
+                break;
+            case OPCODE_PING:
+                this.writeFrame(OPCODE_PONG, payload);
+                break;
+            case OPCODE_CLOSE:
+                if (payload.length >= 2) {
+                    this.closeCode = payload.readUInt16BE(0);
+                    this.closeReason = payload.subarray(2).toString('utf-8');
+                }
+                // 回应 close 帧后关闭连接
+                this.writeFrame(OPCODE_CLOSE, payload.subarray(0, 2));
+                this.open = false;
+                this.socket?.end();
+                break;
+            default:
+                break;
+        }
+    }
+}
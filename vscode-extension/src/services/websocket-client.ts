@@ -23,10 +23,13 @@
  *
  * ## 核心功能
  *
- * - **自动连接**: 扩展激活时自动连接 WebSocket 服务器
+ * - **自动连接**: 扩展激活时自动连接 WebSocket 服务器，Agent 的 Unix socket 存在时优先使用（见 agent-socket）
+ * - **JSON-RPC 2.0**: 所有消息遵循 JSON-RPC 2.0，支持批量调用
+ * - **握手**: 连接后立即调用 `hello`，上报扩展版本、协议版本、窗口/工作区信息和能力列表
  * - **自动重连**: 连接断开后每 5 秒尝试重连
  * - **RPC 处理**: 接收并执行 Rust 发送的 RPC 调用（如 reloadWindow）
- * - **心跳保活**: 响应服务端心跳，防止连接超时
+ * - **调用 Agent**: 通过 `call()` 调用 Agent 方法（如 `accounts.current`），无需再走 HTTP
+ * - **主题订阅**: 通过 `subscribe()` 订阅事件主题，Agent 主动推送，重连后自动恢复订阅
  *
  * ## 使用示例
  *
@@ -41,26 +44,24 @@
  * client.registerHandler('customMethod', (params) => {
  *     console.log('Received:', params);
  * });
+ *
+ * // 调用 Agent 方法
+ * const account = await client.call('accounts.current');
  * ```
  *
  * @module websocket-client
  */
 
 import * as vscode from 'vscode';
+import { randomUUID } from 'crypto';
 import { Logger } from '../utils/logger';
+import { getAdminToken } from '../utils/api-token';
+import { AgentSocket, openAgentSocket } from './agent-socket';
 
 // =============================================================================
 // 常量配置
 // =============================================================================
 
-/**
- * WebSocket 服务器地址
- *
- * 与 Tauri 后端的 Actix 服务器地址一致。
- * 默认运行在 localhost:56789。
- */
-const WS_URL = 'ws://127.0.0.1:56789/ws';
-
 /**
  * 重连延迟（毫秒）
  *
@@ -69,56 +70,161 @@ const WS_URL = 'ws://127.0.0.1:56789/ws';
  */
 const RECONNECT_DELAY = 5000;
 
+/**
+ * WebSocket 协议版本
+ *
+ * 必须与 Rust 端 `websocket.rs` 中的 `PROTOCOL_VERSION` 兼容。
+ */
+const PROTOCOL_VERSION = 2;
+
+/**
+ * 协议版本不兼容时服务端使用的关闭码
+ */
+const CLOSE_CODE_INCOMPATIBLE_PROTOCOL = 4001;
+
+/**
+ * 调用 Agent 方法的默认超时（毫秒）
+ */
+const DEFAULT_CALL_TIMEOUT = 15000;
+
+/**
+ * JSON-RPC 标准错误码
+ */
+const RPC_METHOD_NOT_FOUND = -32601;
+const RPC_INTERNAL_ERROR = -32603;
+
 // =============================================================================
 // 消息协议类型定义
 // =============================================================================
 
+/** JSON-RPC 请求 ID */
+type RpcId = number | string;
+
 /**
- * RPC 请求消息
+ * JSON-RPC 请求或通知
  *
- * 从 Rust 发送到扩展，请求执行指定方法。
+ * 没有 `id` 的请求是通知，接收方不返回响应。
  */
 interface RpcRequest {
-    /** 请求唯一 ID，用于匹配响应 */
-    id: string;
+    jsonrpc: '2.0';
+    /** 请求 ID，用于匹配响应 */
+    id?: RpcId;
     /** 方法名（如 "reloadWindow"） */
     method: string;
     /** 方法参数 */
-    params: any;
+    params?: any;
 }
 
 /**
- * RPC 响应消息
- *
- * 从扩展返回到 Rust，包含方法执行结果。
+ * JSON-RPC 错误对象
+ */
+interface RpcErrorObject {
+    code: number;
+    message: string;
+    /** Agent 返回的错误中包含 `{ code: "not_found" }` 等稳定错误码 */
+    data?: any;
+}
+
+/**
+ * JSON-RPC 响应
  */
 interface RpcResponse {
-    /** 对应请求的 ID */
-    id: string;
+    jsonrpc: '2.0';
+    /** 对应请求的 ID（解析失败时为 null） */
+    id: RpcId | null;
     /** 成功时的返回值 */
     result?: any;
-    /** 失败时的错误信息 */
-    error?: string;
+    /** 失败时的错误对象 */
+    error?: RpcErrorObject;
 }
 
 /**
- * WebSocket 消息类型
- *
- * 所有 WebSocket 通信使用此统一格式，通过 `type` 字段区分消息类型。
+ * 单条 JSON-RPC 消息
+ */
+type RpcMessage = RpcRequest | RpcResponse;
+
+/**
+ * Agent 方法调用失败
+ */
+export class RpcCallError extends Error {
+    constructor(public readonly code: number, message: string, public readonly data?: any) {
+        super(message);
+        this.name = 'RpcCallError';
+    }
+}
+
+/**
+ * 等待响应中的调用
+ */
+interface PendingCall {
+    resolve: (result: any) => void;
+    reject: (error: Error) => void;
+    timer: NodeJS.Timeout;
+}
+
+/**
+ * 握手消息
  *
- * @example
- * // RPC 请求
- * { type: 'rpc_request', id: 'xxx', method: 'reloadWindow', params: {} }
+ * 连接建立后发送的第一条消息，描述当前窗口。
+ */
+interface HelloMessage {
+    /** 扩展版本 */
+    extension_version: string;
+    /** 协议版本 */
+    protocol_version: number;
+    /** 窗口标识（重载窗口后保持不变；同一工作区的多个窗口可能相同） */
+    window_id: string;
+    /** 宿主应用名称 */
+    app_name: string;
+    /** 宿主应用版本 */
+    app_version: string;
+    /** 工作区名称 */
+    workspace_name?: string;
+    /** 工作区文件夹路径 */
+    workspace_folders: string[];
+    /** 支持的能力（已注册的 RPC 方法） */
+    capabilities: string[];
+}
+
+/**
+ * 握手结果
+ */
+interface WelcomeResult {
+    client_id: string;
+    protocol_version: number;
+    agent_version: string;
+    /** Agent 提供的方法列表 */
+    methods: string[];
+}
+
+/**
+ * 事件主题
  *
- * // RPC 响应
- * { type: 'rpc_response', id: 'xxx', result: null }
+ * 与 Rust 端 `events.rs` 中的 `Topic` 一致。
+ */
+export type EventTopic = 'accounts' | 'quotas' | 'process' | 'settings' | 'logs';
+
+/**
+ * 事件通知参数（`event` 通知）
+ */
+export interface AgentEventParams {
+    /** 事件主题 */
+    topic: EventTopic;
+    /** 事件名称（如 "account_changed"、"quota_updated"） */
+    name: string;
+    /** 事件数据 */
+    data: any;
+}
+
+/**
+ * 事件监听函数
+ */
+type EventListener = (event: AgentEventParams) => void;
+
+/**
+ * 连接就绪状态监听函数
  */
-type WsMessage =
-    | { type: 'rpc_request' } & RpcRequest
-    | { type: 'rpc_response' } & RpcResponse
-    | { type: 'event'; name: string; data: any }
-    | { type: 'ping' }
-    | { type: 'pong' };
+type ReadyListener = (ready: boolean) => void;
 
 /**
  * 方法处理器函数类型
@@ -139,11 +245,12 @@ type MethodHandler = (params: any) => Promise<any> | any;
  * - 自动重连
  * - 处理 RPC 调用
  * - 发送响应
+ * - 调用 Agent 方法并等待响应
  *
  * ## 生命周期
  *
  * 1. 调用 `connect()` 建立连接
- * 2. 连接成功后，开始接收消息
+ * 2. 连接成功后，调用 `hello` 完成握手，之后才能调用 Agent 方法
  * 3. 收到 RPC 请求时，查找并执行对应处理器
  * 4. 连接断开时，自动尝试重连
  * 5. 调用 `disconnect()` 关闭连接并停止重连
@@ -162,8 +269,8 @@ type MethodHandler = (params: any) => Promise<any> | any;
  * ```
  */
 export class WebSocketClient {
-    /** WebSocket 实例 */
-    private ws: WebSocket | null = null;
+    /** WebSocket 连接（优先通过 Agent 的 Unix socket，见 agent-socket） */
+    private ws: AgentSocket | null = null;
 
     /** 方法处理器映射表 */
     private handlers: Map<string, MethodHandler> = new Map();
@@ -177,6 +284,51 @@ export class WebSocketClient {
     /** 是否已销毁（停止重连） */
     private disposed: boolean = false;
 
+    /** 扩展版本（握手时上报） */
+    private extensionVersion: string = 'unknown';
+
+    /** 窗口标识（握手时上报） */
+    private windowId: string = vscode.env.sessionId;
+
+    /** 是否已提示过协议不兼容（避免每次重连都弹窗） */
+    private incompatibleNotified: boolean = false;
+
+    /** 是否已完成握手 */
+    private ready: boolean = false;
+
+    /** 下一个请求 ID */
+    private nextId: number = 1;
+
+    /** 等待响应的调用 */
+    private pending: Map<RpcId, PendingCall> = new Map();
+
+    /** 已订阅的主题（重连后自动重新订阅） */
+    private topics: Set<EventTopic> = new Set();
+
+    /** 事件监听器 */
+    private eventListeners: Set<EventListener> = new Set();
+
+    /** 就绪状态监听器 */
+    private readyListeners: Set<ReadyListener> = new Set();
+
+    /**
+     * 设置握手时上报的扩展版本
+     *
+     * @param version - 扩展版本（package.json 中的 version）
+     */
+    public setExtensionVersion(version: string): void {
+        this.extensionVersion = version;
+    }
+
+    /**
+     * 设置握手时上报的窗口标识
+     *
+     * @param windowId - 重载窗口后保持不变的窗口标识，Agent 据此确认窗口重载后已重新连接
+     */
+    public setWindowId(windowId: string): void {
+        this.windowId = windowId;
+    }
+
     /**
      * 连接到 WebSocket 服务器
      *
@@ -185,7 +337,7 @@ export class WebSocketClient {
      */
     public connect(): void {
         // 防止重复连接
-        if (this.disposed || this.isConnecting || this.ws?.readyState === WebSocket.OPEN) {
+        if (this.disposed || this.isConnecting || this.ws?.isOpen()) {
             return;
         }
 
@@ -193,28 +345,33 @@ export class WebSocketClient {
         Logger.log('🔌 正在连接 WebSocket...');
 
         try {
-            this.ws = new WebSocket(WS_URL);
-
-            this.ws.onopen = () => {
-                this.isConnecting = false;
-                Logger.log('✅ WebSocket 连接成功');
-                this.clearReconnectTimer();
-            };
-
-            this.ws.onclose = (event) => {
-                this.isConnecting = false;
-                Logger.log(`WebSocket 连接关闭: ${event.code} ${event.reason}`);
-                this.scheduleReconnect();
-            };
-
-            this.ws.onerror = (error) => {
-                this.isConnecting = false;
-                Logger.log(`WebSocket 错误: ${error}`);
-            };
-
-            this.ws.onmessage = (event) => {
-                this.handleMessage(event.data);
-            };
+            // WebSocket API 无法设置请求头，Token 通过查询参数传递
+            this.ws = openAgentSocket(getAdminToken(), {
+                onOpen: () => {
+                    this.isConnecting = false;
+                    Logger.log('✅ WebSocket 连接成功');
+                    this.clearReconnectTimer();
+                    this.sendHello();
+                },
+                onClose: (code, reason) => {
+                    this.isConnecting = false;
+                    this.setReady(false);
+                    this.rejectPending(new Error(`WebSocket closed: ${code} ${reason}`));
+                    Logger.log(`WebSocket 连接关闭: ${code} ${reason}`);
+                    if (code === CLOSE_CODE_INCOMPATIBLE_PROTOCOL && !this.incompatibleNotified) {
+                        this.incompatibleNotified = true;
+                        vscode.window.showWarningMessage(`Antigravity Agent: ${reason}`);
+                    }
+                    this.scheduleReconnect();
+                },
+                onError: (error) => {
+                    this.isConnecting = false;
+                    Logger.log(`WebSocket 错误: ${error}`);
+                },
+                onMessage: (data) => {
+                    this.handleMessage(data);
+                },
+            });
         } catch (error) {
             this.isConnecting = false;
             Logger.log(`WebSocket 连接失败: ${error}`);
@@ -230,7 +387,9 @@ export class WebSocketClient {
      */
     public disconnect(): void {
         this.disposed = true;
+        this.setReady(false);
         this.clearReconnectTimer();
+        this.rejectPending(new Error('WebSocket disconnected'));
         if (this.ws) {
             this.ws.close();
             this.ws = null;
@@ -263,13 +422,159 @@ export class WebSocketClient {
         Logger.log(`📝 注册 RPC 方法: ${method}`);
     }
 
+    /**
+     * 调用 Agent 方法
+     *
+     * 必须在握手完成后调用（见 `isReady()`）。
+     *
+     * @param method - 方法名（见 `RPC_METHODS`）
+     * @param params - 方法参数
+     * @param timeoutMs - 超时时间（毫秒）
+     * @returns 方法返回值
+     * @throws {RpcCallError} Agent 返回错误对象时
+     *
+     * @example
+     * ```typescript
+     * const metrics = await client.call('metrics.get', { email });
+     * ```
+     */
+    public call<T = any>(method: string, params?: any, timeoutMs: number = DEFAULT_CALL_TIMEOUT): Promise<T> {
+        if (!this.ready && method !== 'hello') {
+            return Promise.reject(new Error('WebSocket is not ready'));
+        }
+        return this.request<T>(method, params, timeoutMs);
+    }
+
+    /**
+     * 订阅事件主题
+     *
+     * 未连接时只记录订阅，握手完成后自动发送；重连后同样会恢复。
+     *
+     * @param topics - 要订阅的主题
+     */
+    public async subscribe(topics: EventTopic[]): Promise<void> {
+        topics.forEach((topic) => this.topics.add(topic));
+        if (this.isReady()) {
+            await this.call('subscribe', { topics });
+        }
+    }
+
+    /**
+     * 监听 Agent 推送的事件
+     *
+     * @param listener - 事件监听函数
+     * @returns 用于取消监听的 Disposable
+     */
+    public onEvent(listener: EventListener): vscode.Disposable {
+        this.eventListeners.add(listener);
+        return new vscode.Disposable(() => this.eventListeners.delete(listener));
+    }
+
+    /**
+     * 监听连接就绪状态变化
+     *
+     * 就绪后 Agent 会主动推送已订阅主题的事件；断开期间调用方应自行轮询。
+     *
+     * @param listener - 状态监听函数
+     * @returns 用于取消监听的 Disposable
+     */
+    public onReadyChange(listener: ReadyListener): vscode.Disposable {
+        this.readyListeners.add(listener);
+        return new vscode.Disposable(() => this.readyListeners.delete(listener));
+    }
+
+    /**
+     * 更新就绪状态并通知监听器
+     */
+    private setReady(ready: boolean): void {
+        if (this.ready === ready) {
+            return;
+        }
+        this.ready = ready;
+        this.readyListeners.forEach((listener) => listener(ready));
+    }
+
+    /**
+     * 分发事件到监听器
+     */
+    private emitEvent(event: AgentEventParams): void {
+        Logger.log(`📨 收到事件: ${event?.topic}/${event?.name}`);
+        this.eventListeners.forEach((listener) => {
+            try {
+                listener(event);
+            } catch (error) {
+                Logger.log(`事件处理失败: ${event?.name} - ${error}`);
+            }
+        });
+    }
+
+    /**
+     * 发送请求并等待响应
+     */
+    private request<T>(method: string, params: any, timeoutMs: number): Promise<T> {
+        if (!this.ws?.isOpen()) {
+            return Promise.reject(new Error('WebSocket is not connected'));
+        }
+
+        const id = this.nextId++;
+        return new Promise<T>((resolve, reject) => {
+            const timer = setTimeout(() => {
+                this.pending.delete(id);
+                reject(new Error(`RPC call '${method}' timed out after ${timeoutMs}ms`));
+            }, timeoutMs);
+
+            this.pending.set(id, { resolve, reject, timer });
+            this.send({ jsonrpc: '2.0', id, method, params });
+        });
+    }
+
+    /**
+     * 拒绝所有等待中的调用
+     */
+    private rejectPending(error: Error): void {
+        for (const call of this.pending.values()) {
+            clearTimeout(call.timer);
+            call.reject(error);
+        }
+        this.pending.clear();
+    }
+
+    /**
+     * 发送握手请求
+     *
+     * 服务端在收到 `hello` 之前不会处理其他业务消息。
+     */
+    private async sendHello(): Promise<void> {
+        const hello: HelloMessage = {
+            extension_version: this.extensionVersion,
+            protocol_version: PROTOCOL_VERSION,
+            window_id: this.windowId,
+            app_name: vscode.env.appName,
+            app_version: vscode.version,
+            workspace_name: vscode.workspace.name,
+            workspace_folders: (vscode.workspace.workspaceFolders ?? []).map((folder) => folder.uri.fsPath),
+            capabilities: Array.from(this.handlers.keys())
+        };
+
+        try {
+            const welcome = await this.request<WelcomeResult>('hello', hello, DEFAULT_CALL_TIMEOUT);
+            Logger.log(`🤝 握手完成: client_id=${welcome.client_id}, agent=${welcome.agent_version}`);
+            if (this.topics.size > 0) {
+                await this.request('subscribe', { topics: Array.from(this.topics) }, DEFAULT_CALL_TIMEOUT);
+            }
+            this.setReady(true);
+        } catch (error) {
+            Logger.log(`握手失败: ${error}`);
+        }
+    }
+
     /**
      * 发送消息到服务器
      *
-     * @param message - 要发送的消息
+     * @param message - 要发送的单条或批量消息
      */
-    private send(message: WsMessage): void {
-        if (this.ws?.readyState === WebSocket.OPEN) {
+    private send(message: RpcMessage | RpcMessage[]): void {
+        if (this.ws?.isOpen()) {
             this.ws.send(JSON.stringify(message));
         }
     }
@@ -277,75 +582,118 @@ export class WebSocketClient {
     /**
      * 处理收到的消息
      *
-     * 根据消息类型分发到不同的处理逻辑。
+     * 数组为批量消息，逐条处理后将请求的响应合并为数组返回。
      *
      * @param data - 收到的原始消息数据
      */
     private async handleMessage(data: string): Promise<void> {
+        let parsed: RpcMessage | RpcMessage[];
         try {
-            const message: WsMessage = JSON.parse(data);
-
-            switch (message.type) {
-                case 'rpc_request':
-                    // 处理 RPC 调用
-                    await this.handleRpcRequest(message);
-                    break;
-                case 'ping':
-                    // 响应心跳
-                    this.send({ type: 'pong' });
-                    break;
-                case 'event':
-                    // 处理事件通知
-                    Logger.log(`📨 收到事件: ${message.name}`);
-                    // TODO: 可以添加事件处理器机制
-                    break;
-                default:
-                    Logger.log(`未知消息类型: ${(message as any).type}`);
-            }
+            parsed = JSON.parse(data);
         } catch (error) {
             Logger.log(`解析 WebSocket 消息失败: ${error}`);
+            return;
+        }
+
+        if (Array.isArray(parsed)) {
+            const responses = await Promise.all(parsed.map((message) => this.handleSingle(message)));
+            const replies = responses.filter((response): response is RpcResponse => response !== null);
+            if (replies.length > 0) {
+                this.send(replies);
+            }
+            return;
+        }
+
+        const response = await this.handleSingle(parsed);
+        if (response) {
+            this.send(response);
+        }
+    }
+
+    /**
+     * 处理单条消息
+     *
+     * @returns 需要回复的响应（通知和响应消息返回 null）
+     */
+    private async handleSingle(message: RpcMessage): Promise<RpcResponse | null> {
+        if ('method' in message) {
+            if (message.method === 'event' && message.id === undefined) {
+                this.emitEvent(message.params as AgentEventParams);
+                return null;
+            }
+            return this.handleRpcRequest(message);
+        }
+
+        this.handleRpcResponse(message);
+        return null;
+    }
+
+    /**
+     * 处理 Agent 返回的响应，唤醒对应的调用
+     */
+    private handleRpcResponse(response: RpcResponse): void {
+        if (response.id === null) {
+            Logger.log(`⚠️ 收到无 ID 的错误响应: ${response.error?.message}`);
+            return;
+        }
+
+        const call = this.pending.get(response.id);
+        if (!call) {
+            return;
+        }
+        this.pending.delete(response.id);
+        clearTimeout(call.timer);
+
+        if (response.error) {
+            call.reject(new RpcCallError(response.error.code, response.error.message, response.error.data));
+        } else {
+            call.resolve(response.result);
         }
     }
 
     /**
      * 处理 RPC 请求
      *
-     * 查找并执行对应的方法处理器，将结果或错误发送回服务器。
+     * 查找并执行对应的方法处理器，返回结果或错误对象。
      *
      * @param request - RPC 请求消息
+     * @returns 响应（通知返回 null）
      */
-    private async handleRpcRequest(request: RpcRequest & { type: 'rpc_request' }): Promise<void> {
+    private async handleRpcRequest(request: RpcRequest): Promise<RpcResponse | null> {
         Logger.log(`📥 收到 RPC 调用: ${request.method}`);
+        const id = request.id;
 
         const handler = this.handlers.get(request.method);
         if (!handler) {
             Logger.log(`⚠️ 未找到方法处理器: ${request.method}`);
-            this.send({
-                type: 'rpc_response',
-                id: request.id,
-                error: `Method not found: ${request.method}`
-            });
-            return;
+            if (id === undefined) {
+                return null;
+            }
+            return {
+                jsonrpc: '2.0',
+                id,
+                error: { code: RPC_METHOD_NOT_FOUND, message: `Method not found: ${request.method}` }
+            };
         }
 
         try {
             // 执行处理器
             const result = await handler(request.params);
-            // 发送成功响应
-            this.send({
-                type: 'rpc_response',
-                id: request.id,
-                result: result ?? null
-            });
             Logger.log(`✅ RPC 调用完成: ${request.method}`);
+            return id === undefined ? null : { jsonrpc: '2.0', id, result: result ?? null };
         } catch (error) {
-            // 发送错误响应
             Logger.log(`❌ RPC 调用失败: ${request.method} - ${error}`);
-            this.send({
-                type: 'rpc_response',
-                id: request.id,
-                error: error instanceof Error ? error.message : String(error)
-            });
+            if (id === undefined) {
+                return null;
+            }
+            return {
+                jsonrpc: '2.0',
+                id,
+                error: {
+                    code: RPC_INTERNAL_ERROR,
+                    message: error instanceof Error ? error.message : String(error)
+                }
+            };
         }
     }
 
@@ -382,7 +730,14 @@ export class WebSocketClient {
      * @returns 如果 WebSocket 处于 OPEN 状态则返回 true
      */
     public isConnected(): boolean {
-        return this.ws?.readyState === WebSocket.OPEN;
+        return this.ws?.isOpen();
+    }
+
+    /**
+     * 检查是否已完成握手，可以调用 Agent 方法
+     */
+    public isReady(): boolean {
+        return this.ready && this.isConnected();
     }
 }
 
@@ -407,6 +762,25 @@ export function getWebSocketClient(): WebSocketClient {
     return globalClient;
 }
 
+/** 窗口标识在 workspaceState 中的键 */
+const WINDOW_ID_KEY = 'antigravityAgent.windowId';
+
+/**
+ * 获取重载后保持不变的窗口标识
+ *
+ * `vscode.env.sessionId` 在重载窗口后会变化，因此首次生成后保存在 workspaceState 中。
+ * workspaceState 按工作区保存，同一工作区的多个窗口（或多个空窗口）会得到相同的标识，
+ * Agent 确认重新连接时按次数匹配，而不是假设标识唯一。
+ */
+function getWindowId(context: vscode.ExtensionContext): string {
+    let windowId = context.workspaceState.get<string>(WINDOW_ID_KEY);
+    if (!windowId) {
+        windowId = randomUUID();
+        void context.workspaceState.update(WINDOW_ID_KEY, windowId);
+    }
+    return windowId;
+}
+
 /**
  * 初始化 WebSocket 并注册默认方法
  *
@@ -435,13 +809,17 @@ export function getWebSocketClient(): WebSocketClient {
  */
 export function initializeWebSocket(context: vscode.ExtensionContext): WebSocketClient {
     const client = getWebSocketClient();
+    client.setExtensionVersion(context.extension.packageJSON.version);
+    client.setWindowId(getWindowId(context));
 
     // 注册默认方法：重载窗口
     // 当 Rust 调用 call_all_extensions("reloadWindow", {}) 时，
     // 此处理器会执行 VSCode 的窗口重载命令
+    // 不等待命令完成：窗口重载会结束扩展宿主，先返回确认，Agent 据此判断热切换是否成功
     client.registerHandler('reloadWindow', () => {
         Logger.log('🔄 执行 reloadWindow');
-        vscode.commands.executeCommand('workbench.action.reloadWindow');
+        setTimeout(() => vscode.commands.executeCommand('workbench.action.reloadWindow'), 100);
+        return { reloading: true };
     });
 
     // 连接到 WebSocket 服务器
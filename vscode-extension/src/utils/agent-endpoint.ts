@@ -0,0 +1,106 @@
+import * as fs from 'fs';
+import * as http from 'http';
+import * as os from 'os';
+import * as path from 'path';
+import { Logger } from './logger';
+
+/**
+ * Local API endpoints of the Antigravity Agent.
+ *
+ * On Linux and macOS the agent listens on ~/.antigravity-agent/agent.sock (mode 0600),
+ * which only the owning user can reach. The TCP port (56789 by default, `api_tcp_port`
+ * in app_settings.json) may be disabled on headless hosts, so the socket is preferred
+ * whenever it exists.
+ */
+const CONFIG_DIR = path.join(os.homedir(), '.antigravity-agent');
+const SOCKET_FILE = path.join(CONFIG_DIR, 'agent.sock');
+const SETTINGS_FILE = path.join(CONFIG_DIR, 'app_settings.json');
+const DEFAULT_TCP_PORT = 56789;
+
+/** Path of the agent's Unix socket, or null when it is not available. */
+export function getAgentSocketPath(): string | null {
+    if (process.platform === 'win32') {
+        return null;
+    }
+    try {
+        return fs.statSync(SOCKET_FILE).isSocket() ? SOCKET_FILE : null;
+    } catch {
+        return null;
+    }
+}
+
+/** TCP port configured in app_settings.json (read on every call, like the token file). */
+export function getAgentTcpPort(): number {
+    try {
+        const settings = JSON.parse(fs.readFileSync(SETTINGS_FILE, 'utf-8'));
+        const port = Number(settings?.api_tcp_port);
+        return Number.isInteger(port) && port > 0 && port < 65536 ? port : DEFAULT_TCP_PORT;
+    } catch {
+        return DEFAULT_TCP_PORT;
+    }
+}
+
+/** Base URL of the REST API over TCP, e.g. `http://127.0.0.1:56789/api`. */
+export function getApiBaseUrl(): string {
+    return `http://127.0.0.1:${getAgentTcpPort()}/api`;
+}
+
+/** Origins the dashboard webview may connect to (used in its CSP). */
+export function getApiOrigins(): string {
+    const port = getAgentTcpPort();
+    return `http://127.0.0.1:${port} ws://127.0.0.1:${port}`;
+}
+
+export interface AgentRequestInit {
+    method?: 'GET' | 'POST';
+    token: string;
+    body?: unknown;
+}
+
+/**
+ * Calls `/api/<endpoint>` through the Unix socket when present, otherwise over TCP.
+ * A stale socket file (nobody listening) falls back to TCP as well.
+ */
+export async function agentFetch(endpoint: string, init: AgentRequestInit): Promise<Response> {
+    const socketPath = getAgentSocketPath();
+    if (socketPath) {
+        try {
+            return await requestOverSocket(socketPath, `/api/${endpoint}`, init);
+        } catch (error) {
+            Logger.log(`Agent socket request failed, falling back to TCP: ${error}`);
+        }
+    }
+
+    return fetch(`${getApiBaseUrl()}/${endpoint}`, {
+        method: init.method ?? 'GET',
+        headers: {
+            'Authorization': `Bearer ${init.token}`,
+            'Content-Type': 'application/json',
+        },
+        body: init.body === undefined ? undefined : JSON.stringify(init.body),
+    });
+}
+
+function requestOverSocket(socketPath: string, requestPath: string, init: AgentRequestInit): Promise<Response> {
+    const payload = init.body === undefined ? undefined : JSON.stringify(init.body);
+
+    return new Promise((resolve, reject) => {
+        const req = http.request({
+            socketPath,
+            path: requestPath,
+            method: init.method ?? 'GET',
+            headers: {
+                'Authorization': `Bearer ${init.token}`,
+                'Content-Type': 'application/json',
+                ...(payload === undefined ? {} : { 'Content-Length': Buffer.byteLength(payload) }),
+            },
+        }, (res) => {
+            const chunks: Buffer[] = [];
+            res.on('data', (chunk: Buffer) => chunks.push(chunk));
+            res.on('end', () => resolve(new Response(Buffer.concat(chunks), { status: res.statusCode ?? 500 })));
+            res.on('error', reject);
+        });
+        req.on('error', reject);
+        req.end(payload);
+    });
+}
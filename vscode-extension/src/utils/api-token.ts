@@ -0,0 +1,40 @@
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { Logger } from './logger';
+
+/**
+ * Local API tokens generated by the Antigravity Agent on first launch.
+ * Stored at ~/.antigravity-agent/api_tokens.json (mode 0600 on Unix).
+ */
+interface ApiTokens {
+    /** Full access: switching accounts, deleting backups, ... */
+    admin: string;
+    /** Read-only access: account info and quota metrics */
+    read_only: string;
+}
+
+const TOKEN_FILE = path.join(os.homedir(), '.antigravity-agent', 'api_tokens.json');
+
+/**
+ * Reads the token file on every call so that a token regenerated by the agent
+ * is picked up without reloading the extension.
+ */
+function readTokens(): ApiTokens | null {
+    try {
+        return JSON.parse(fs.readFileSync(TOKEN_FILE, 'utf-8')) as ApiTokens;
+    } catch (error) {
+        Logger.log(`Failed to read API token file: ${error}`);
+        return null;
+    }
+}
+
+/** Token allowed to call every endpoint (used by the dashboard panel and WebSocket). */
+export function getAdminToken(): string {
+    return readTokens()?.admin ?? '';
+}
+
+/** Token limited to read-only endpoints (used by the status bar). */
+export function getReadOnlyToken(): string {
+    return readTokens()?.read_only ?? '';
+}
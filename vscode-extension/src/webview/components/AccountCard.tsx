@@ -21,7 +21,7 @@ interface AccountCardProps {
     account: AntigravityAccount;
     data?: AccountData;
     isCurrent: boolean;
-    onSwitch: (email: string) => void;
+    onSwitch: (accountId: string) => void;
     privacyMode: boolean;
 }
 
@@ -77,7 +77,7 @@ export const AccountCard: React.FC<AccountCardProps> = ({ account, data, isCurre
                         <VSCodeButton
                             appearance="secondary"
                             className="h-6"
-                            onClick={() => onSwitch(email)}
+                            onClick={() => onSwitch(account.account_id)}
                         >
                             {t('common:actions.switch')}
                         </VSCodeButton>
@@ -1,4 +1,4 @@
-import React, { useEffect } from 'react';
+import React, { useEffect, useState } from 'react';
 import { VSCodeProgressRing } from '@vscode/webview-ui-toolkit/react';
 import { useAccountAdditionData } from '@/modules/use-account-addition-data';
 import { useAntigravityAccount } from '@/modules/use-antigravity-account';
@@ -21,7 +21,9 @@ export const AccountsTab: React.FC<AccountsTabProps> = ({ privacyMode }) => {
     const validAccounts = accounts.filter(
         (acc) => typeof acc?.antigravity_auth_status?.email === 'string' && acc.antigravity_auth_status.email.trim() !== ''
     );
-    const currentEmail = currentAuthInfo?.antigravity_auth_status?.email;
+    const currentAccountId = currentAuthInfo?.account_id;
+    // Agent 通过 WebSocket 主动推送时无需轮询
+    const [pushEnabled, setPushEnabled] = useState(false);
 
     // 初始化加载
     useEffect(() => {
@@ -34,6 +36,28 @@ export const AccountsTab: React.FC<AccountsTabProps> = ({ privacyMode }) => {
         init();
     }, []);
 
+    // 接收扩展转发的 Agent 推送
+    useEffect(() => {
+        const handleMessage = (event: MessageEvent) => {
+            const message = event.data;
+            if (message.command === 'agentPushState') {
+                setPushEnabled(message.enabled);
+            } else if (message.command === 'agentEvent') {
+                if (message.topic === 'accounts') {
+                    getAccounts();
+                    insertOrUpdateCurrentAccount();
+                } else if (message.name === 'quota_updated') {
+                    const account = accounts.find(acc => acc.account_id === message.data?.account_id);
+                    if (account) {
+                        additionData.update(account).catch(e => console.error("Failed to update quota", e));
+                    }
+                }
+            }
+        };
+        window.addEventListener('message', handleMessage);
+        return () => window.removeEventListener('message', handleMessage);
+    }, [accounts]);
+
     // 轮询更新账户列表和额度信息
     useEffect(() => {
         if (accounts.length === 0) return;
@@ -43,6 +67,9 @@ export const AccountsTab: React.FC<AccountsTabProps> = ({ privacyMode }) => {
             additionData.update(account).catch(e => console.error("Failed to update quota", e));
         });
 
+        // Agent 推送可用时不再轮询
+        if (pushEnabled) return;
+
         // 定时轮询
         const intervalId = setInterval(() => {
             getAccounts();
@@ -53,10 +80,10 @@ export const AccountsTab: React.FC<AccountsTabProps> = ({ privacyMode }) => {
         }, 30 * 1000);
 
         return () => clearInterval(intervalId);
-    }, [accounts.length]); // 依赖 accounts.length 避免频繁重置定时器，但确保有账户时才开始干活
+    }, [accounts.length, pushEnabled]); // 依赖 accounts.length 避免频繁重置定时器，但确保有账户时才开始干活
 
-    const handleSwitchAccount = async (email: string) => {
-        await switchToAccount(email);
+    const handleSwitchAccount = async (accountId: string) => {
+        await switchToAccount(accountId);
         // 切换后刷新数据
         await Promise.all([
             getAccounts(),
@@ -77,10 +104,10 @@ export const AccountsTab: React.FC<AccountsTabProps> = ({ privacyMode }) => {
             <div className="accounts-grid">
                 {validAccounts.map((acc) => (
                     <AccountCard
-                        key={acc.antigravity_auth_status.email}
+                        key={acc.account_id}
                         account={acc}
-                        data={additionData.data[acc.antigravity_auth_status.email]}
-                        isCurrent={currentEmail === acc.antigravity_auth_status.email}
+                        data={additionData.data[acc.account_id]}
+                        isCurrent={currentAccountId === acc.account_id}
                         onSwitch={handleSwitchAccount}
                         privacyMode={privacyMode}
                     />